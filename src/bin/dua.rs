@@ -1,15 +1,30 @@
 //! Disk Usage CLI (dua) - Main binary entry point
 
-use dua::cli::args::{Command, parse_args};
-use dua::cli::output::format_json;
+use dua::cli::args::{Command, SnapshotCommand, parse_args};
+use dua::cli::interrupt::{self, InterruptHandler};
+use dua::cli::output::{
+    AdaptivePreviewStrategy, PreviewStrategy, format_diff_json, format_diff_text,
+    format_hardlinks_json, format_hardlinks_text, format_json, format_text_with_all_entries,
+};
+use dua::cli::tui::TuiState;
+use dua::io::snapshot::{EntryFilter, SnapshotReader};
 use dua::models::ProgressSnapshot;
 use dua::services::aggregate::{SortBy, get_immediate_children, sort_and_limit};
-use dua::services::format::format_size;
-use dua::{ScanOptions, SizeBasis, StrategyKind};
+use dua::services::diff::{diff_subtree, ensure_comparable};
+use dua::services::filter::read_exclude_file;
+use dua::services::format::{format_delta, format_size};
+use dua::services::hardlinks::read_hardlink_report;
+use dua::services::traverse::throttle;
+use dua::services::watch::Watch;
+use dua::{
+    CancellationToken, HardlinkPolicy, IgnoreFileMode, IoPriority, ScanOptions, SizeBasis,
+    StrategyKind,
+};
+use std::path::{Path, PathBuf};
 use std::process;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 fn main() {
     // Initialize logger (controlled by RUST_LOG environment variable)
@@ -49,6 +64,13 @@ fn main() {
     let exit_code = match &cli_args.command {
         Command::Scan(scan_args) => handle_scan(scan_args),
         Command::View(view_args) => handle_view(view_args),
+        Command::Diff(diff_args) => handle_diff(diff_args),
+        Command::Tui(tui_args) => handle_tui(tui_args),
+        Command::Watch(watch_args) => handle_watch(watch_args),
+        Command::Hardlinks(hardlinks_args) => handle_hardlinks(hardlinks_args),
+        Command::Snapshot(SnapshotCommand::Upgrade(upgrade_args)) => {
+            handle_snapshot_upgrade(upgrade_args)
+        }
     };
 
     process::exit(exit_code);
@@ -56,8 +78,8 @@ fn main() {
 
 #[allow(clippy::too_many_lines)]
 fn handle_scan(args: &dua::cli::args::ScanArgs) -> i32 {
-    // Snapshot is required for scan
-    let snapshot_path = if let Some(ref path) = args.snapshot {
+    // Snapshot is required for scan; resuming defaults to updating the resumed snapshot
+    let snapshot_path = if let Some(path) = args.snapshot.as_ref().or(args.resume.as_ref()) {
         path.clone()
     } else {
         eprintln!("Error: --snapshot is required for scan command");
@@ -75,12 +97,67 @@ fn handle_scan(args: &dua::cli::args::ScanArgs) -> i32 {
         }
     };
 
+    let hardlink_policy = match args.hardlinks.as_str() {
+        "dedupe" => HardlinkPolicy::Dedupe,
+        "count" => HardlinkPolicy::Count,
+        _ => {
+            eprintln!(
+                "Invalid hardlink policy: {}. Use 'dedupe' or 'count'",
+                args.hardlinks
+            );
+            return 2;
+        }
+    };
+
+    let io_priority = match args.ionice.as_deref() {
+        None | Some("normal") => IoPriority::Normal,
+        Some("idle") => IoPriority::Idle,
+        Some(other) => {
+            eprintln!("Invalid I/O class: {other}. Use 'idle' or 'normal'");
+            return 2;
+        }
+    };
+
+    let mut excludes = args.excludes.clone();
+    for exclude_file in &args.exclude_from {
+        match read_exclude_file(exclude_file) {
+            Ok(patterns) => excludes.extend(patterns),
+            Err(e) => {
+                eprintln!("Error reading exclude file {exclude_file}: {e}");
+                return 2;
+            }
+        }
+    }
+
     // Create scan options
     let mut opts = ScanOptions {
         basis,
         max_depth: args.max_depth,
+        threads: args.threads,
+        max_open_fds: args.max_open_fds,
+        nice: args.nice,
+        io_priority,
+        max_ops_per_sec: args.max_ops_per_sec,
+        hardlink_policy,
+        follow_symlinks: args.follow_symlinks,
+        cross_filesystem: args.cross_mounts,
+        resume_from: args.resume.as_ref().map(PathBuf::from),
+        baseline: args.baseline.as_ref().map(PathBuf::from),
+        excludes,
+        ignore_files: if args.collapse_ignored {
+            IgnoreFileMode::Collapse
+        } else if args.respect_ignore_files {
+            IgnoreFileMode::Skip
+        } else {
+            IgnoreFileMode::Disabled
+        },
         ..ScanOptions::default()
     };
+    if args.nice {
+        // Workers can only be niced one by one on Linux; elsewhere the whole
+        // process is, once, which is fine for a process that exits after the scan.
+        throttle::lower_process_priority();
+    }
 
     if let Some(label) = args.strategy_override.as_deref() {
         match StrategyKind::from_str(label) {
@@ -105,6 +182,17 @@ fn handle_scan(args: &dua::cli::args::ScanArgs) -> i32 {
         opts.progress_byte_trigger = u64::MAX;
     }
 
+    // Ctrl-C stops traversal cooperatively so the snapshot footer still gets written.
+    let token = CancellationToken::new();
+    let interrupts = match InterruptHandler::install(&token) {
+        Ok(handler) => Some(handler),
+        Err(err) => {
+            log::warn!("Failed to install signal handlers: {err}");
+            None
+        }
+    };
+    opts.cancellation = Some(token);
+
     if !args.quiet {
         opts.progress_notifier = Some(Arc::new(move |snapshot: &ProgressSnapshot| {
             #[allow(clippy::cast_precision_loss)]
@@ -130,6 +218,16 @@ fn handle_scan(args: &dua::cli::args::ScanArgs) -> i32 {
 
     let summary = match dua::scan_to_snapshot(&args.path, &opts, &snapshot_path) {
         Ok(s) => s,
+        Err(dua::Error::Interrupted { summary, .. }) => {
+            let signal = interrupts.as_ref().and_then(InterruptHandler::received);
+            let name = signal.map_or("cancellation", interrupt::signal_name);
+            eprintln!("Interrupted by {name}; scan stopped early");
+            eprintln!(
+                "Partial snapshot saved: {snapshot_path} ({} entries)",
+                summary.entry_count
+            );
+            return signal.map_or(interrupt::EXIT_INTERRUPTED, interrupt::exit_code);
+        }
         Err(e) => {
             eprintln!("Error: {e}");
             return match e {
@@ -142,6 +240,12 @@ fn handle_scan(args: &dua::cli::args::ScanArgs) -> i32 {
 
     if !args.quiet {
         eprintln!("Found {} entries", summary.entry_count);
+        if !summary.skipped_mounts.is_empty() {
+            eprintln!(
+                "Skipped {} mount point(s) on other filesystems; use --cross-mounts to include them",
+                summary.skipped_mounts.len()
+            );
+        }
         eprintln!("Snapshot saved: {snapshot_path}");
     }
 
@@ -165,24 +269,35 @@ fn handle_view(args: &dua::cli::args::ViewArgs) -> i32 {
         }
     };
 
-    // Read snapshot
-    let (meta, all_entries, errors) = match dua::io::snapshot::read_snapshot(&args.from_snapshot) {
-        Ok(data) => data,
+    // Open snapshot; only the footer is read up front
+    let reader = match SnapshotReader::open(&args.from_snapshot) {
+        Ok(reader) => reader,
         Err(e) => {
             eprintln!("Error reading snapshot: {e}");
             return 4;
         }
     };
+    let meta = reader.meta();
+    if meta.incomplete {
+        eprintln!(
+            "Warning: snapshot is incomplete (scan stopped: {})",
+            meta.incomplete_reason.as_deref().unwrap_or("unknown")
+        );
+    }
 
     // Determine root path and depth for filtering
     let (display_root, parent_depth) = if let Some(ref drill_path) = args.path {
         // Find the entry for this path to get its depth
-        let entry = all_entries.iter().find(|e| e.path == *drill_path);
-        if let Some(e) = entry {
-            (drill_path.clone(), e.depth)
-        } else {
-            eprintln!("Error: Path '{drill_path}' not found in snapshot");
-            return 2;
+        match reader.find_entry(drill_path) {
+            Ok(Some(e)) => (drill_path.clone(), e.depth),
+            Ok(None) => {
+                eprintln!("Error: Path '{drill_path}' not found in snapshot");
+                return 2;
+            }
+            Err(e) => {
+                eprintln!("Error reading snapshot: {e}");
+                return 4;
+            }
         }
     } else {
         (meta.scan_root.clone(), 0)
@@ -190,12 +305,40 @@ fn handle_view(args: &dua::cli::args::ViewArgs) -> i32 {
 
     let strategy = StrategyKind::from_str(&meta.strategy).unwrap_or(StrategyKind::Legacy);
 
+    // Load only the levels the preview can reach below the target path
+    let preview = AdaptivePreviewStrategy::default();
+    let max_depth = parent_depth
+        .saturating_add(preview.max_preview_depth())
+        .saturating_add(1);
+    let filter = EntryFilter::new()
+        .subtree(display_root.as_str())
+        .max_depth(max_depth);
+    let (subtree_entries, errors, progress) = match reader
+        .read_entries(filter)
+        .and_then(|entries| Ok((entries, reader.errors()?, reader.progress()?)))
+    {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error reading snapshot: {e}");
+            return 4;
+        }
+    };
+
     // Get immediate children of the target path
-    let mut entries = get_immediate_children(&all_entries, &display_root, parent_depth);
+    let mut entries = get_immediate_children(&subtree_entries, &display_root, parent_depth);
 
     // Sort and limit
     entries = sort_and_limit(entries, sort_by, Some(args.top));
 
+    // Mount points the scan stayed out of, inside the viewed subtree
+    let subtree_prefix = format!("{}/", display_root.trim_end_matches('/'));
+    let skipped_mounts = meta
+        .skipped_mounts
+        .iter()
+        .filter(|mount| mount.path.starts_with(&subtree_prefix))
+        .cloned()
+        .collect();
+
     // Create a summary-like structure for output
     let summary = dua::Summary {
         root: display_root,
@@ -204,8 +347,9 @@ fn handle_view(args: &dua::cli::args::ViewArgs) -> i32 {
         started_at: std::time::SystemTime::UNIX_EPOCH, // Placeholder
         finished_at: std::time::SystemTime::UNIX_EPOCH, // Placeholder
         strategy,
-        progress: Vec::new(),
-        entry_count: all_entries.len() as u64,
+        progress,
+        entry_count: reader.entry_count(),
+        skipped_mounts,
     };
 
     // Output
@@ -213,28 +357,276 @@ fn handle_view(args: &dua::cli::args::ViewArgs) -> i32 {
         let json = format_json(&summary, &entries);
         println!("{json}");
     } else {
-        use dua::cli::output::{AdaptivePreviewStrategy, format_text_with_all_entries};
-        format_text_with_all_entries(
-            &summary,
-            &entries,
-            &all_entries,
-            &AdaptivePreviewStrategy::default(),
-        );
+        format_text_with_all_entries(&summary, &entries, &subtree_entries, &preview);
+    }
+
+    0
+}
+
+fn handle_diff(args: &dua::cli::args::DiffArgs) -> i32 {
+    let (old_meta, old_entries) = match dua::io::snapshot::read_snapshot_entries(&args.old_snapshot)
+    {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error reading snapshot {}: {e}", args.old_snapshot);
+            return 4;
+        }
+    };
+
+    let (new_meta, new_entries) = match dua::io::snapshot::read_snapshot_entries(&args.new_snapshot)
+    {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error reading snapshot {}: {e}", args.new_snapshot);
+            return 4;
+        }
+    };
+
+    if let Err(e) = ensure_comparable(&old_meta, &new_meta) {
+        eprintln!("Error: {e}");
+        return 2;
+    }
+
+    let target = args.path.clone().unwrap_or(new_meta.scan_root);
+
+    let Some(diff) = diff_subtree(&old_entries, &new_entries, &target, Some(args.top)) else {
+        eprintln!("Error: Path '{target}' not found in either snapshot");
+        return 2;
+    };
+
+    if args.json {
+        println!("{}", format_diff_json(&diff));
+    } else {
+        format_diff_text(&diff);
+    }
+
+    0
+}
+
+fn handle_hardlinks(args: &dua::cli::args::HardlinksArgs) -> i32 {
+    let report = match SnapshotReader::open(&args.snapshot)
+        .and_then(|reader| read_hardlink_report(&reader))
+    {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Error reading snapshot {}: {e}", args.snapshot);
+            return 4;
+        }
+    };
+
+    if args.json {
+        println!("{}", format_hardlinks_json(&report, args.top));
+    } else {
+        format_hardlinks_text(&report, args.top);
+    }
+
+    0
+}
+
+fn handle_tui(args: &dua::cli::args::TuiArgs) -> i32 {
+    let sort_by = match args.sort.as_str() {
+        "size" => SortBy::Size,
+        "files" => SortBy::Files,
+        "dirs" => SortBy::Dirs,
+        _ => {
+            eprintln!("Invalid sort: {}. Use 'size', 'files' or 'dirs'", args.sort);
+            return 2;
+        }
+    };
+
+    let (meta, all_entries) = match dua::io::snapshot::read_snapshot_entries(&args.from_snapshot) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("Error reading snapshot: {e}");
+            return 4;
+        }
+    };
+
+    let start = args.path.clone().unwrap_or(meta.scan_root);
+    let Some(state) = TuiState::new(all_entries, &start, sort_by) else {
+        eprintln!("Error: Path '{start}' not found in snapshot");
+        return 2;
+    };
+
+    if let Err(e) = dua::cli::tui::run(state) {
+        eprintln!("Error running TUI: {e}");
+        return 4;
     }
 
     0
 }
 
+fn handle_watch(args: &dua::cli::args::WatchArgs) -> i32 {
+    let basis = match args.basis.as_str() {
+        "physical" => SizeBasis::Physical,
+        "logical" => SizeBasis::Logical,
+        _ => {
+            eprintln!("Invalid basis: {}. Use 'physical' or 'logical'", args.basis);
+            return 2;
+        }
+    };
+
+    // Ctrl-C is the normal way to stop watching; the loop exits and checkpoints.
+    let token = CancellationToken::new();
+    if let Err(err) = InterruptHandler::install(&token) {
+        log::warn!("Failed to install signal handlers: {err}");
+    }
+    let opts = ScanOptions {
+        basis,
+        excludes: args.excludes.clone(),
+        cancellation: Some(token.clone()),
+        ..ScanOptions::default()
+    };
+
+    eprintln!("Scanning: {}", args.path);
+    let window = Duration::from_secs(args.window_secs);
+    let mut watch = match Watch::start(Path::new(&args.path), &opts, window) {
+        Ok(watch) => watch,
+        Err(dua::Error::Interrupted { .. }) => {
+            eprintln!("Interrupted before the initial scan finished");
+            return interrupt::EXIT_INTERRUPTED;
+        }
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return match e {
+                dua::Error::InvalidInput(_) => 2,
+                _ => 4,
+            };
+        }
+    };
+    eprintln!(
+        "Watching {} ({} entries, {}); press Ctrl-C to stop",
+        args.path,
+        watch.tree().len(),
+        format_size(watch.tree().total_size())
+    );
+    if watch.unwatched_dirs() > 0 {
+        eprintln!(
+            "Warning: {} directories could not be watched (see fs.inotify.max_user_watches)",
+            watch.unwatched_dirs()
+        );
+    }
+
+    let checkpoint = |watch: &Watch| {
+        if let Some(snapshot) = &args.snapshot
+            && let Err(e) = watch.checkpoint(snapshot)
+        {
+            eprintln!("Error writing checkpoint {snapshot}: {e}");
+        }
+    };
+    checkpoint(&watch);
+
+    let started = Instant::now();
+    let refresh = Duration::from_secs(args.refresh_secs);
+    let checkpoint_every = Duration::from_secs(args.checkpoint_secs);
+    let mut next_report = started + refresh;
+    let mut next_checkpoint = started + checkpoint_every;
+    let mut exit_code = 0;
+
+    while !token.is_cancelled() {
+        let wait = next_report
+            .min(next_checkpoint)
+            .saturating_duration_since(Instant::now());
+        if let Err(e) = watch.poll(wait.min(Duration::from_millis(500))) {
+            eprintln!("Error: {e}");
+            exit_code = 4;
+            break;
+        }
+
+        let now = Instant::now();
+        if now >= next_report {
+            print_growth(&mut watch, args.top, now.duration_since(started));
+            next_report = now + refresh;
+        }
+        if now >= next_checkpoint {
+            checkpoint(&watch);
+            next_checkpoint = now + checkpoint_every;
+        }
+    }
+
+    checkpoint(&watch);
+    if let Some(snapshot) = &args.snapshot {
+        eprintln!("Snapshot saved: {snapshot}");
+    }
+    exit_code
+}
+
+fn print_growth(watch: &mut Watch, top: usize, elapsed: Duration) {
+    let window = watch.window().as_secs();
+    println!(
+        "[{:6}s] {}: {}",
+        elapsed.as_secs(),
+        watch.tree().root(),
+        format_size(watch.tree().total_size())
+    );
+    let growth = watch.fastest_growing(top);
+    if growth.is_empty() {
+        println!("    no directory grew in the last {window}s");
+    }
+    for dir in growth {
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let rate = format_size(dir.bytes_per_sec as u64);
+        println!(
+            "    {:>12}/s {:>12}  {}",
+            rate,
+            format_delta(dir.delta_bytes),
+            dir.path
+        );
+    }
+}
+
+fn handle_snapshot_upgrade(args: &dua::cli::args::UpgradeArgs) -> i32 {
+    let mut failed = 0usize;
+
+    for snapshot in &args.snapshots {
+        match dua::io::snapshot::upgrade_snapshot(snapshot, args.output.as_deref()) {
+            Ok(upgrade) if upgrade.rewritten() => {
+                let target = args.output.as_deref().unwrap_or(snapshot);
+                println!(
+                    "{snapshot}: upgraded schema v{} -> v{} ({target})",
+                    upgrade.from_version, upgrade.to_version
+                );
+            }
+            Ok(upgrade) => {
+                println!("{snapshot}: already at schema v{}", upgrade.to_version);
+            }
+            Err(e) => {
+                eprintln!("Error upgrading snapshot {snapshot}: {e}");
+                failed += 1;
+            }
+        }
+    }
+
+    match failed {
+        0 => 0,
+        n if n == args.snapshots.len() => 4,
+        _ => 3,
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 fn print_help() {
     println!("Disk Usage CLI (dua) - Analyze disk usage for directory trees");
     println!();
     println!("USAGE:");
     println!("    dua scan <PATH> --snapshot <FILE> [OPTIONS]");
     println!("    dua view <SNAPSHOT> [OPTIONS]");
+    println!("    dua diff <OLD_SNAPSHOT> <NEW_SNAPSHOT> [OPTIONS]");
+    println!("    dua tui <SNAPSHOT> [OPTIONS]");
+    println!("    dua watch <PATH> [--snapshot <FILE>] [OPTIONS]");
+    println!("    dua hardlinks <SNAPSHOT> [OPTIONS]");
+    println!("    dua snapshot upgrade <SNAPSHOT>... [--output <FILE>]");
     println!();
     println!("COMMANDS:");
     println!("    scan      Traverse a path, aggregate usage, and persist a snapshot");
     println!("    view      Read a snapshot and display aggregates instantly");
+    println!("    diff      Compare two snapshots and rank changes by byte delta");
+    println!("    tui       Browse a snapshot interactively in the terminal");
+    println!(
+        "    watch     Keep a live usage tree updated and report the fastest growing directories"
+    );
+    println!("    hardlinks List hardlinked files and the bytes directories share through them");
+    println!("    snapshot  Maintain snapshot files (upgrade: rewrite in the current schema)");
     println!();
     println!("GLOBAL OPTIONS:");
     println!("    -h, --help                 Show this help message");
@@ -242,13 +634,42 @@ fn print_help() {
     println!();
     println!("SCAN OPTIONS:");
     println!("    --snapshot <FILE>         Save results to a Parquet snapshot (required)");
+    println!(
+        "    --resume <SNAPSHOT>       Continue an interrupted scan, rescanning only unfinished"
+    );
+    println!("                              directories (defaults --snapshot to SNAPSHOT)");
+    println!("    --baseline <SNAPSHOT>     Reuse directories unchanged since an earlier snapshot");
+    println!("                              (matched by inode and mtime; Unix only). Files that");
+    println!("                              change in place keep their baseline size until their");
+    println!("                              directory's mtime changes");
     println!("    --basis <TYPE>            Size basis: physical (default) or logical");
+    println!(
+        "    --hardlinks <MODE>        Hardlinked files: dedupe (count once, default) or count"
+    );
+    println!("    --follow-symlinks         Measure symlink targets instead of skipping symlinks");
     println!("    --max-depth <N>           Limit traversal depth (default: unlimited)");
     println!("    --legacy-traversal        Force the legacy traversal backend");
     println!(
-        "    --strategy <NAME>         Override strategy: windows|posix|legacy (aliases: ntfs, unix)"
+        "    --strategy <NAME>         Override strategy: windows|posix|io_uring|legacy (aliases: ntfs, unix, uring)"
+    );
+    println!("    --threads <N>             Traversal worker threads (default: one per CPU)");
+    println!(
+        "    --max-open-fds <N>        Directory fds held open while walking (default: half of RLIMIT_NOFILE)"
     );
+    println!("    --nice                    Lower the CPU priority of traversal workers");
+    println!(
+        "    --ionice <CLASS>          I/O scheduling class: normal (default) or idle (Linux)"
+    );
+    println!("    --max-ops-per-sec <N>     Cap filesystem operations (stats, opens) per second");
     println!("    --progress-interval <S>   Emit progress updates every S seconds (default: 2)");
+    println!("    --one-file-system         Stay on the filesystem of PATH (default)");
+    println!("    --cross-mounts            Descend into mount points on other filesystems");
+    println!("    --exclude <GLOB>          Skip entries matching GLOB (repeatable)");
+    println!("    --exclude-from <FILE>     Read exclude patterns from FILE, one per line");
+    println!("    --respect-ignore-files    Skip paths matched by .gitignore/.ignore/.duaignore");
+    println!(
+        "    --collapse-ignored        Record ignored directories as single collapsed entries"
+    );
     println!("    --quiet                   Suppress non-error output");
     println!();
     println!("VIEW OPTIONS:");
@@ -257,15 +678,56 @@ fn print_help() {
     println!("    --sort <FIELD>            Sort by size|files|dirs (default: size)");
     println!("    --json                    Emit machine-readable output");
     println!();
+    println!("DIFF OPTIONS:");
+    println!("    --path <SUBDIR>           Compare children of a path (default: scan root)");
+    println!("    --top <K>                 Show top K changes (default: 10)");
+    println!("    --json                    Emit machine-readable output");
+    println!();
+    println!("TUI OPTIONS:");
+    println!("    --path <SUBDIR>           Start browsing at a path (default: scan root)");
+    println!("    --sort <FIELD>            Initial sort: size|files|dirs (default: size)");
+    println!("    Keys: ↑/↓ move, Enter/→ open, ←/Backspace up, s/f/d sort, q quit");
+    println!();
+    println!("WATCH OPTIONS (Linux):");
+    println!(
+        "    --snapshot <FILE>         Checkpoint the live tree to FILE periodically and on exit"
+    );
+    println!("    --checkpoint-every <S>    Seconds between checkpoints (default: 300)");
+    println!("    --refresh <S>             Seconds between growth reports (default: 5)");
+    println!("    --window <S>              Rank growth over the last S seconds (default: 60)");
+    println!("    --top <K>                 Show the K fastest growing directories (default: 10)");
+    println!("    --basis <TYPE>            Size basis: physical (default) or logical");
+    println!("    --exclude <GLOB>          Skip entries matching GLOB (repeatable)");
+    println!();
+    println!("HARDLINKS OPTIONS:");
+    println!(
+        "    --top <K>                 Show the K largest groups and directories (default: 10)"
+    );
+    println!("    --json                    Emit machine-readable output");
+    println!();
+    println!("SNAPSHOT UPGRADE OPTIONS:");
+    println!("    --output <FILE>           Write the upgraded copy to FILE instead of in place");
+    println!();
+    println!("EXIT CODES:");
+    println!("    0                         Success");
+    println!("    2                         Invalid input");
+    println!("    3                         Completed with per-entry errors");
+    println!("    4                         Failure");
+    println!("    130, 143                  Interrupted by SIGINT/SIGTERM; partial snapshot saved");
+    println!();
     println!("WORKFLOW:");
     println!("    1. Capture snapshot:  dua scan /usr --snapshot /tmp/usr.parquet");
     println!("    2. Inspect quickly:   dua view /tmp/usr.parquet --sort files");
     println!("    3. Deep dive:         dua view /tmp/usr.parquet --path /usr/share --top 20");
+    println!("    4. Explore:           dua tui /tmp/usr.parquet");
     println!();
     println!("EXAMPLES:");
     println!("    dua scan /home --progress-interval 1 --snapshot home.parquet");
     println!("    dua scan /data --strategy posix --snapshot data.parquet");
+    println!("    dua scan ~/src --exclude .git --exclude node_modules --snapshot src.parquet");
     println!("    dua view home.parquet --path /home/user/Downloads --json");
+    println!("    dua diff yesterday.parquet today.parquet --path /var --top 20");
+    println!("    dua snapshot upgrade archive/*.parquet");
 }
 
 fn print_version() {
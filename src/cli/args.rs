@@ -9,18 +9,39 @@ pub struct CliArgs {
 pub enum Command {
     Scan(ScanArgs),
     View(ViewArgs),
+    Diff(DiffArgs),
+    Tui(TuiArgs),
+    Watch(WatchArgs),
+    Hardlinks(HardlinksArgs),
+    Snapshot(SnapshotCommand),
 }
 
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct ScanArgs {
     pub path: String,
     pub basis: String,
+    pub hardlinks: String,
+    pub follow_symlinks: bool,
     pub snapshot: Option<String>,
+    pub resume: Option<String>,
+    pub baseline: Option<String>,
     pub max_depth: Option<u16>,
     pub quiet: bool,
     pub legacy_traversal: bool,
     pub strategy_override: Option<String>,
+    pub threads: Option<usize>,
+    pub max_open_fds: Option<usize>,
+    pub nice: bool,
+    pub ionice: Option<String>,
+    pub max_ops_per_sec: Option<u32>,
     pub progress_interval_secs: Option<u64>,
+    pub excludes: Vec<String>,
+    pub exclude_from: Vec<String>,
+    pub respect_ignore_files: bool,
+    pub collapse_ignored: bool,
+    /// Descend into mount points on other filesystems.
+    pub cross_mounts: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -32,17 +53,77 @@ pub struct ViewArgs {
     pub json: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct DiffArgs {
+    pub old_snapshot: String,
+    pub new_snapshot: String,
+    pub path: Option<String>,
+    pub top: usize,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct TuiArgs {
+    pub from_snapshot: String,
+    pub path: Option<String>,
+    pub sort: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchArgs {
+    pub path: String,
+    pub snapshot: Option<String>,
+    pub basis: String,
+    pub top: usize,
+    pub refresh_secs: u64,
+    pub window_secs: u64,
+    pub checkpoint_secs: u64,
+    pub excludes: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HardlinksArgs {
+    pub snapshot: String,
+    pub top: usize,
+    pub json: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum SnapshotCommand {
+    Upgrade(UpgradeArgs),
+}
+
+#[derive(Debug, Clone)]
+pub struct UpgradeArgs {
+    pub snapshots: Vec<String>,
+    pub output: Option<String>,
+}
+
 impl Default for ScanArgs {
     fn default() -> Self {
         Self {
             path: String::new(),
             basis: "physical".to_string(),
+            hardlinks: "dedupe".to_string(),
+            follow_symlinks: false,
             snapshot: None,
+            resume: None,
+            baseline: None,
             max_depth: None,
             quiet: false,
             legacy_traversal: false,
             strategy_override: None,
+            threads: None,
+            max_open_fds: None,
+            nice: false,
+            ionice: None,
+            max_ops_per_sec: None,
             progress_interval_secs: None,
+            excludes: Vec::new(),
+            exclude_from: Vec::new(),
+            respect_ignore_files: false,
+            collapse_ignored: false,
+            cross_mounts: false,
         }
     }
 }
@@ -62,12 +143,33 @@ pub fn parse_args(args: &[String]) -> Result<CliArgs, String> {
             let view_args = parse_view_args(&args[2..])?;
             Command::View(view_args)
         }
+        "diff" => {
+            let diff_args = parse_diff_args(&args[2..])?;
+            Command::Diff(diff_args)
+        }
+        "tui" => {
+            let tui_args = parse_tui_args(&args[2..])?;
+            Command::Tui(tui_args)
+        }
+        "watch" => {
+            let watch_args = parse_watch_args(&args[2..])?;
+            Command::Watch(watch_args)
+        }
+        "hardlinks" => {
+            let hardlinks_args = parse_hardlinks_args(&args[2..])?;
+            Command::Hardlinks(hardlinks_args)
+        }
+        "snapshot" => {
+            let snapshot_command = parse_snapshot_command(&args[2..])?;
+            Command::Snapshot(snapshot_command)
+        }
         _ => return Err(format!("Unknown command: {}", args[1])),
     };
 
     Ok(CliArgs { command })
 }
 
+#[allow(clippy::too_many_lines)]
 fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
     let mut scan_args = ScanArgs::default();
     let mut i = 0;
@@ -81,6 +183,16 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
                 }
                 scan_args.basis.clone_from(&args[i]);
             }
+            "--hardlinks" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--hardlinks requires a value".to_string());
+                }
+                scan_args.hardlinks.clone_from(&args[i]);
+            }
+            "--follow-symlinks" => {
+                scan_args.follow_symlinks = true;
+            }
             "--snapshot" => {
                 i += 1;
                 if i >= args.len() {
@@ -88,6 +200,20 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
                 }
                 scan_args.snapshot = Some(args[i].clone());
             }
+            "--resume" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--resume requires a snapshot path".to_string());
+                }
+                scan_args.resume = Some(args[i].clone());
+            }
+            "--baseline" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--baseline requires a snapshot path".to_string());
+                }
+                scan_args.baseline = Some(args[i].clone());
+            }
             "--max-depth" => {
                 i += 1;
                 if i >= args.len() {
@@ -102,6 +228,19 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
             "--quiet" => {
                 scan_args.quiet = true;
             }
+            "--respect-ignore-files" => {
+                scan_args.respect_ignore_files = true;
+            }
+            "--collapse-ignored" => {
+                scan_args.respect_ignore_files = true;
+                scan_args.collapse_ignored = true;
+            }
+            "--one-file-system" => {
+                scan_args.cross_mounts = false;
+            }
+            "--cross-mounts" => {
+                scan_args.cross_mounts = true;
+            }
             "--legacy-traversal" => {
                 scan_args.legacy_traversal = true;
             }
@@ -112,6 +251,37 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
                 }
                 scan_args.strategy_override = Some(args[i].clone());
             }
+            "--threads" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--threads requires a value".to_string());
+                }
+                scan_args.threads = Some(parse_positive(&args[i], "--threads")?);
+            }
+            "--max-open-fds" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--max-open-fds requires a value".to_string());
+                }
+                scan_args.max_open_fds = Some(parse_positive(&args[i], "--max-open-fds")?);
+            }
+            "--nice" => {
+                scan_args.nice = true;
+            }
+            "--ionice" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--ionice requires a class".to_string());
+                }
+                scan_args.ionice = Some(args[i].clone());
+            }
+            "--max-ops-per-sec" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--max-ops-per-sec requires a value".to_string());
+                }
+                scan_args.max_ops_per_sec = Some(parse_positive(&args[i], "--max-ops-per-sec")?);
+            }
             "--progress-interval" => {
                 i += 1;
                 if i >= args.len() {
@@ -125,6 +295,20 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
                 }
                 scan_args.progress_interval_secs = Some(secs);
             }
+            "--exclude" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--exclude requires a pattern".to_string());
+                }
+                scan_args.excludes.push(args[i].clone());
+            }
+            "--exclude-from" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--exclude-from requires a file path".to_string());
+                }
+                scan_args.exclude_from.push(args[i].clone());
+            }
             arg if !arg.starts_with("--") => {
                 if scan_args.path.is_empty() {
                     scan_args.path = arg.to_string();
@@ -144,6 +328,18 @@ fn parse_scan_args(args: &[String]) -> Result<ScanArgs, String> {
     Ok(scan_args)
 }
 
+/// Parse a count that must be at least one.
+fn parse_positive<T>(value: &str, flag: &str) -> Result<T, String>
+where
+    T: std::str::FromStr + Default + PartialEq,
+{
+    match value.parse::<T>() {
+        Ok(n) if n == T::default() => Err(format!("{flag} must be greater than zero")),
+        Ok(n) => Ok(n),
+        Err(_) => Err(format!("{flag} must be a positive integer")),
+    }
+}
+
 fn parse_view_args(args: &[String]) -> Result<ViewArgs, String> {
     let mut from_snapshot = String::new();
     let mut path = None;
@@ -204,3 +400,268 @@ fn parse_view_args(args: &[String]) -> Result<ViewArgs, String> {
         json,
     })
 }
+
+fn parse_diff_args(args: &[String]) -> Result<DiffArgs, String> {
+    let mut snapshots: Vec<String> = Vec::new();
+    let mut path = None;
+    let mut top = 10;
+    let mut json = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--path" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--path requires a value".to_string());
+                }
+                path = Some(args[i].clone());
+            }
+            "--top" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--top requires a value".to_string());
+                }
+                top = args[i]
+                    .parse()
+                    .map_err(|_| "--top must be a number".to_string())?;
+            }
+            "--json" => {
+                json = true;
+            }
+            arg if !arg.starts_with("--") => {
+                if snapshots.len() < 2 {
+                    snapshots.push(arg.to_string());
+                } else {
+                    return Err(format!("Unexpected argument: {arg}"));
+                }
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 1;
+    }
+
+    let mut snapshots = snapshots.into_iter();
+    let (Some(old_snapshot), Some(new_snapshot)) = (snapshots.next(), snapshots.next()) else {
+        return Err("Missing required arguments: OLD_SNAPSHOT NEW_SNAPSHOT".to_string());
+    };
+
+    Ok(DiffArgs {
+        old_snapshot,
+        new_snapshot,
+        path,
+        top,
+        json,
+    })
+}
+
+fn parse_hardlinks_args(args: &[String]) -> Result<HardlinksArgs, String> {
+    let mut snapshot = None;
+    let mut top = 10;
+    let mut json = false;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--top" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--top requires a value".to_string());
+                }
+                top = args[i]
+                    .parse()
+                    .map_err(|_| "--top must be a number".to_string())?;
+            }
+            "--json" => {
+                json = true;
+            }
+            arg if !arg.starts_with("--") && snapshot.is_none() => {
+                snapshot = Some(arg.to_string());
+            }
+            arg if !arg.starts_with("--") => {
+                return Err(format!("Unexpected argument: {arg}"));
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 1;
+    }
+
+    let Some(snapshot) = snapshot else {
+        return Err("Missing required argument: SNAPSHOT_FILE".to_string());
+    };
+
+    Ok(HardlinksArgs {
+        snapshot,
+        top,
+        json,
+    })
+}
+
+fn parse_tui_args(args: &[String]) -> Result<TuiArgs, String> {
+    let mut from_snapshot = String::new();
+    let mut path = None;
+    let mut sort = "size".to_string();
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--path" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--path requires a value".to_string());
+                }
+                path = Some(args[i].clone());
+            }
+            "--sort" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--sort requires a value".to_string());
+                }
+                sort.clone_from(&args[i]);
+            }
+            arg if !arg.starts_with("--") => {
+                if from_snapshot.is_empty() {
+                    from_snapshot = arg.to_string();
+                } else {
+                    return Err(format!("Unexpected argument: {arg}"));
+                }
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 1;
+    }
+
+    if from_snapshot.is_empty() {
+        return Err("Missing required argument: SNAPSHOT_FILE".to_string());
+    }
+
+    Ok(TuiArgs {
+        from_snapshot,
+        path,
+        sort,
+    })
+}
+
+fn parse_watch_args(args: &[String]) -> Result<WatchArgs, String> {
+    let mut watch_args = WatchArgs {
+        path: String::new(),
+        snapshot: None,
+        basis: "physical".to_string(),
+        top: 10,
+        refresh_secs: 5,
+        window_secs: 60,
+        checkpoint_secs: 300,
+        excludes: Vec::new(),
+    };
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--snapshot" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--snapshot requires a file path".to_string());
+                }
+                watch_args.snapshot = Some(args[i].clone());
+            }
+            "--basis" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--basis requires a value".to_string());
+                }
+                watch_args.basis.clone_from(&args[i]);
+            }
+            "--top" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--top requires a value".to_string());
+                }
+                watch_args.top = args[i]
+                    .parse()
+                    .map_err(|_| "--top must be a number".to_string())?;
+            }
+            "--refresh" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--refresh requires a value in seconds".to_string());
+                }
+                watch_args.refresh_secs = parse_positive(&args[i], "--refresh")?;
+            }
+            "--window" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--window requires a value in seconds".to_string());
+                }
+                watch_args.window_secs = parse_positive(&args[i], "--window")?;
+            }
+            "--checkpoint-every" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--checkpoint-every requires a value in seconds".to_string());
+                }
+                watch_args.checkpoint_secs = parse_positive(&args[i], "--checkpoint-every")?;
+            }
+            "--exclude" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--exclude requires a pattern".to_string());
+                }
+                watch_args.excludes.push(args[i].clone());
+            }
+            arg if !arg.starts_with("--") => {
+                if watch_args.path.is_empty() {
+                    watch_args.path = arg.to_string();
+                } else {
+                    return Err(format!("Unexpected argument: {arg}"));
+                }
+            }
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 1;
+    }
+
+    if watch_args.path.is_empty() {
+        return Err("Missing required argument: PATH".to_string());
+    }
+
+    Ok(watch_args)
+}
+
+fn parse_snapshot_command(args: &[String]) -> Result<SnapshotCommand, String> {
+    match args.first().map(String::as_str) {
+        Some("upgrade") => Ok(SnapshotCommand::Upgrade(parse_upgrade_args(&args[1..])?)),
+        Some(other) => Err(format!("Unknown snapshot subcommand: {other}")),
+        None => Err("Missing snapshot subcommand (expected: upgrade)".to_string()),
+    }
+}
+
+fn parse_upgrade_args(args: &[String]) -> Result<UpgradeArgs, String> {
+    let mut snapshots = Vec::new();
+    let mut output = None;
+    let mut i = 0;
+
+    while i < args.len() {
+        match args[i].as_str() {
+            "--output" => {
+                i += 1;
+                if i >= args.len() {
+                    return Err("--output requires a value".to_string());
+                }
+                output = Some(args[i].clone());
+            }
+            arg if !arg.starts_with("--") => snapshots.push(arg.to_string()),
+            _ => return Err(format!("Unknown option: {}", args[i])),
+        }
+        i += 1;
+    }
+
+    if snapshots.is_empty() {
+        return Err("Missing required argument: SNAPSHOT_FILE".to_string());
+    }
+
+    if output.is_some() && snapshots.len() > 1 {
+        return Err("--output can only be used with a single snapshot".to_string());
+    }
+
+    Ok(UpgradeArgs { snapshots, output })
+}
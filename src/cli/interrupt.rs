@@ -0,0 +1,67 @@
+//! Turning SIGINT/SIGTERM into a cooperative scan cancellation.
+//!
+//! The first signal cancels the scan's [`CancellationToken`] so traversal
+//! unwinds and the snapshot is finalized with what was collected. A second
+//! signal exits immediately for users who do not want to wait.
+
+use crate::CancellationToken;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI32, Ordering};
+
+/// Exit status for a scan stopped without a recorded signal (as if by SIGINT).
+pub const EXIT_INTERRUPTED: i32 = 128 + SIGINT;
+
+/// Records which termination signal, if any, interrupted the scan.
+#[derive(Debug)]
+pub struct InterruptHandler {
+    received: Arc<AtomicI32>,
+}
+
+impl InterruptHandler {
+    /// Route SIGINT and SIGTERM to `token` for the rest of the process lifetime.
+    pub fn install(token: &CancellationToken) -> io::Result<Self> {
+        let received = Arc::new(AtomicI32::new(0));
+        for signal in [SIGINT, SIGTERM] {
+            let token = token.clone();
+            let received = Arc::clone(&received);
+            // SAFETY: the action only performs atomic operations and `_exit`,
+            // all of which are async-signal-safe.
+            unsafe {
+                signal_hook::low_level::register(signal, move || {
+                    if received.swap(signal, Ordering::SeqCst) != 0 {
+                        signal_hook::low_level::exit(exit_code(signal));
+                    }
+                    token.cancel();
+                })?;
+            }
+        }
+        Ok(Self { received })
+    }
+
+    /// The first signal received, if any.
+    #[must_use]
+    pub fn received(&self) -> Option<i32> {
+        match self.received.load(Ordering::SeqCst) {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+}
+
+/// Conventional shell exit status for a process stopped by `signal`.
+#[must_use]
+pub fn exit_code(signal: i32) -> i32 {
+    128 + signal
+}
+
+/// Human-readable name of the signals handled here.
+#[must_use]
+pub fn signal_name(signal: i32) -> &'static str {
+    match signal {
+        SIGINT => "SIGINT",
+        SIGTERM => "SIGTERM",
+        _ => "signal",
+    }
+}
@@ -1,4 +1,6 @@
 //! CLI argument parsing and presentation module
 
 pub mod args;
+pub mod interrupt;
 pub mod output;
+pub mod tui;
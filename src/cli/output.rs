@@ -1,12 +1,17 @@
 //! Output formatting for CLI
 
 use crate::Summary;
-use crate::models::DirectoryEntry;
-use crate::services::format::format_size;
+use crate::models::{DirectoryEntry, SkippedMount};
+use crate::services::diff::{DiffStatus, SnapshotDiff};
+use crate::services::format::{format_delta, format_size};
+use crate::services::hardlinks::HardlinkReport;
+
+/// Marker printed next to mount points a scan did not descend into
+pub const MOUNT_MARKER: &str = "[mount, not scanned]";
 
 /// Calculate size ratio for display purposes (intentionally allows precision loss for large sizes)
 #[allow(clippy::cast_precision_loss)]
-fn size_ratio(size: u64, total: u64) -> f64 {
+pub(crate) fn size_ratio(size: u64, total: u64) -> f64 {
     if total == 0 {
         0.0
     } else {
@@ -137,7 +142,7 @@ pub fn format_text_with_all_entries(
     all_entries: &[DirectoryEntry],
     strategy: &dyn PreviewStrategy,
 ) {
-    if entries.is_empty() {
+    if entries.is_empty() && summary.skipped_mounts.is_empty() {
         println!("No entries found.");
         return;
     }
@@ -152,7 +157,17 @@ pub fn format_text_with_all_entries(
     println!("{}", "─".repeat(88));
 
     // Print entries with hierarchical preview
-    print_entries_recursive(entries, all_entries, strategy, root_size, root_size, 0);
+    let mounts = &summary.skipped_mounts;
+    print_entries_recursive(
+        entries,
+        all_entries,
+        mounts,
+        strategy,
+        root_size,
+        root_size,
+        0,
+    );
+    print_skipped_mounts(mounts, &summary.root);
 
     // Print errors if any
     if !summary.errors.is_empty() {
@@ -190,7 +205,7 @@ pub fn format_text_with_strategy(
     println!();
 
     // Print entries with hierarchical preview
-    print_entries_recursive(entries, &[], strategy, root_size, root_size, 0);
+    print_entries_recursive(entries, &[], &[], strategy, root_size, root_size, 0);
 
     // Print errors if any
     if !summary.errors.is_empty() {
@@ -209,26 +224,60 @@ pub fn format_text_with_strategy(
     }
 }
 
-/// Get ANSI color code based on percentage
-fn get_color_for_percentage(pct: f64) -> &'static str {
+/// Usage severity buckets shared by text output and the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageLevel {
+    /// >= 30% of the root
+    Critical,
+    /// >= 15% of the root
+    High,
+    /// >= 5% of the root
+    Moderate,
+    /// < 5% of the root
+    Low,
+}
+
+impl UsageLevel {
+    /// ANSI color code used when rendering this level
+    #[must_use]
+    pub fn ansi_color(self) -> &'static str {
+        match self {
+            UsageLevel::Critical => "\x1b[31m", // Red
+            UsageLevel::High => "\x1b[33m",     // Yellow
+            UsageLevel::Moderate => "\x1b[36m", // Cyan
+            UsageLevel::Low => "\x1b[90m",      // Gray
+        }
+    }
+}
+
+/// Classify a percentage of the root size into a usage level
+#[must_use]
+pub fn usage_level(pct: f64) -> UsageLevel {
     if pct >= 30.0 {
-        "\x1b[31m" // Red for >= 30%
+        UsageLevel::Critical
     } else if pct >= 15.0 {
-        "\x1b[33m" // Yellow for >= 15%
+        UsageLevel::High
     } else if pct >= 5.0 {
-        "\x1b[36m" // Cyan for >= 5%
+        UsageLevel::Moderate
     } else {
-        "\x1b[90m" // Gray for < 5%
+        UsageLevel::Low
     }
 }
 
+/// Get ANSI color code based on percentage
+fn get_color_for_percentage(pct: f64) -> &'static str {
+    usage_level(pct).ansi_color()
+}
+
 /// Reset ANSI color
 const COLOR_RESET: &str = "\x1b[0m";
 
-/// Recursively print entries with preview
+/// Recursively print entries with preview, followed at each previewed level
+/// by the unscanned mount points among the children
 fn print_entries_recursive(
     current_entries: &[DirectoryEntry],
     all_entries: &[DirectoryEntry],
+    mounts: &[SkippedMount],
     strategy: &dyn PreviewStrategy,
     parent_size: u64,
     root_size: u64,
@@ -243,9 +292,7 @@ fn print_entries_recursive(
         // Use full path
         let path = &entry.path;
 
-        // Determine if this is a directory
-        let is_dir = entry.dir_count > 0 || entry.file_count > 0;
-        let path_display = if is_dir && !path.ends_with('/') {
+        let path_display = if entry.kind.is_dir() && !path.ends_with('/') {
             format!("{path}/")
         } else {
             path.clone()
@@ -282,16 +329,36 @@ fn print_entries_recursive(
                 print_entries_recursive(
                     children_to_show,
                     all_entries,
+                    mounts,
                     strategy,
                     entry.size_bytes,
                     root_size,
                     current_depth + 1,
                 );
             }
+            print_skipped_mounts(mounts, &entry.path);
         }
     }
 }
 
+/// List the mount points directly inside `parent` that the scan stayed out
+/// of, whose usage is not counted
+fn print_skipped_mounts(mounts: &[SkippedMount], parent: &str) {
+    let parent = parent.trim_end_matches('/');
+    for mount in mounts.iter().filter(|mount| {
+        mount
+            .path
+            .rsplit_once('/')
+            .is_some_and(|(mount_parent, _)| mount_parent == parent)
+    }) {
+        println!(
+            "{:<70} {MOUNT_MARKER} ({})",
+            format!("{}/", mount.path.trim_end_matches('/')),
+            mount.fs_type
+        );
+    }
+}
+
 /// Get immediate children of a directory from all entries
 fn get_children_from_all(
     all_entries: &[DirectoryEntry],
@@ -312,6 +379,7 @@ pub fn format_json(summary: &Summary, entries: &[DirectoryEntry]) -> String {
         "strategy": summary.strategy.to_string(),
         "entries": entries,
         "progress": summary.progress,
+        "skipped_mounts": summary.skipped_mounts,
         "error_count": summary.errors.len(),
         "errors": if summary.errors.is_empty() {
             serde_json::Value::Null
@@ -322,3 +390,132 @@ pub fn format_json(summary: &Summary, entries: &[DirectoryEntry]) -> String {
 
     serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
 }
+
+/// Format a snapshot diff as human-readable text
+pub fn format_diff_text(diff: &SnapshotDiff) {
+    let root = &diff.root;
+    println!(
+        "{} ({} -> {}, {})",
+        root.path,
+        format_size(root.old_size.unwrap_or(0)),
+        format_size(root.new_size.unwrap_or(0)),
+        format_delta(root.delta_bytes)
+    );
+    println!();
+
+    if diff.entries.is_empty() {
+        println!("No changes found.");
+        return;
+    }
+
+    println!(
+        "{:<60} {:>12} {:>10} {:>10} {:>8}",
+        "Path", "Change", "Old", "New", "Status"
+    );
+    println!("{}", "─".repeat(104));
+
+    // Entries nest, so colour each change relative to the largest one.
+    let largest_change = diff.entries[0].delta_bytes.unsigned_abs();
+
+    for entry in &diff.entries {
+        let pct = size_ratio(entry.delta_bytes.unsigned_abs(), largest_change) * 100.0;
+        let color = match entry.status {
+            DiffStatus::Shrunk | DiffStatus::Removed => "\x1b[32m",
+            _ => get_color_for_percentage(pct),
+        };
+        let old = entry.old_size.map_or_else(|| "-".to_string(), format_size);
+        let new = entry.new_size.map_or_else(|| "-".to_string(), format_size);
+
+        println!(
+            "{}{:<60}{} {:>12} {:>10} {:>10} {:>8}",
+            color,
+            entry.path,
+            COLOR_RESET,
+            format_delta(entry.delta_bytes),
+            old,
+            new,
+            entry.status.as_str()
+        );
+    }
+}
+
+/// Format a snapshot diff as JSON
+#[must_use]
+pub fn format_diff_json(diff: &SnapshotDiff) -> String {
+    let output = serde_json::json!({
+        "root": diff.root.path,
+        "old_size": diff.root.old_size,
+        "new_size": diff.root.new_size,
+        "delta_bytes": diff.root.delta_bytes,
+        "entries": diff.entries,
+    });
+
+    serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+}
+
+/// Format a hardlink report as human-readable text, showing the `top` largest
+/// groups and directories
+pub fn format_hardlinks_text(report: &HardlinkReport, top: usize) {
+    if report.groups.is_empty() {
+        println!("No hardlinked files found.");
+        return;
+    }
+
+    println!(
+        "{} hardlink groups: {} unique, {} across all links",
+        report.groups.len(),
+        format_size(report.unique_bytes),
+        format_size(report.apparent_bytes)
+    );
+    println!();
+    println!("{:>10} {:>7} {:>5}  Paths", "Size", "Links", "Dirs");
+    println!("{}", "─".repeat(88));
+    for group in report.groups.iter().take(top) {
+        let links = format!("{}/{}", group.paths.len(), group.nlink);
+        for (index, path) in group.paths.iter().enumerate() {
+            if index == 0 {
+                println!(
+                    "{:>10} {:>7} {:>5}  {path}",
+                    format_size(group.size_bytes),
+                    links,
+                    group.directories
+                );
+            } else {
+                println!("{:>24}  {path}", "");
+            }
+        }
+    }
+
+    if report.directories.is_empty() {
+        return;
+    }
+    println!();
+    println!(
+        "{:<60} {:>10} {:>10} {:>6}",
+        "Directory", "Shared", "Charged", "Files"
+    );
+    println!("{}", "─".repeat(89));
+    for dir in report.directories.iter().take(top) {
+        println!(
+            "{:<60} {:>10} {:>10} {:>6}",
+            dir.path,
+            format_size(dir.shared_bytes),
+            format_size(dir.charged_bytes),
+            dir.linked_files
+        );
+    }
+}
+
+/// Format a hardlink report as JSON, keeping the `top` largest groups and directories
+#[must_use]
+pub fn format_hardlinks_json(report: &HardlinkReport, top: usize) -> String {
+    let output = serde_json::json!({
+        "group_count": report.groups.len(),
+        "unique_bytes": report.unique_bytes,
+        "apparent_bytes": report.apparent_bytes,
+        "groups": &report.groups[..top.min(report.groups.len())],
+        "directories": &report.directories[..top.min(report.directories.len())],
+    });
+
+    serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+}
@@ -0,0 +1,400 @@
+//! Interactive terminal UI for drilling through a snapshot.
+//!
+//! Navigation state lives in [`TuiState`], which is independent of the
+//! terminal so it can be exercised without a TTY. [`run`] wires the state to
+//! `crossterm` for rendering and keyboard input.
+
+use crate::cli::output::{UsageLevel, size_ratio, usage_level};
+use crate::models::DirectoryEntry;
+use crate::services::aggregate::{SortBy, get_immediate_children, sort_and_limit};
+use crate::services::format::format_size;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Color, Print, ResetColor, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue, style};
+use std::io::{self, Write};
+
+/// Width of the usage bar in columns.
+const BAR_WIDTH: usize = 20;
+/// Rows reserved for the header and footer.
+const CHROME_ROWS: u16 = 4;
+
+/// A directory level on the navigation stack.
+#[derive(Debug, Clone)]
+struct Frame {
+    path: String,
+    depth: u16,
+    size_bytes: u64,
+    selected: usize,
+}
+
+/// Navigation state for the snapshot browser.
+#[derive(Debug)]
+pub struct TuiState {
+    entries: Vec<DirectoryEntry>,
+    ancestors: Vec<Frame>,
+    current: Frame,
+    children: Vec<DirectoryEntry>,
+    sort_by: SortBy,
+}
+
+impl TuiState {
+    /// Create a browser positioned at `start_path`.
+    ///
+    /// Returns `None` when `start_path` is not present in `entries`.
+    #[must_use]
+    pub fn new(entries: Vec<DirectoryEntry>, start_path: &str, sort_by: SortBy) -> Option<Self> {
+        let start = entries.iter().find(|e| e.path == start_path)?;
+        let current = Frame {
+            path: start.path.clone(),
+            depth: start.depth,
+            size_bytes: start.size_bytes,
+            selected: 0,
+        };
+
+        let mut state = Self {
+            entries,
+            ancestors: Vec::new(),
+            current,
+            children: Vec::new(),
+            sort_by,
+        };
+        state.reload_children();
+        Some(state)
+    }
+
+    /// Path of the directory currently displayed.
+    #[must_use]
+    pub fn current_path(&self) -> &str {
+        &self.current.path
+    }
+
+    /// Total size of the directory currently displayed.
+    #[must_use]
+    pub fn current_size(&self) -> u64 {
+        self.current.size_bytes
+    }
+
+    /// Immediate children of the current directory in display order.
+    #[must_use]
+    pub fn children(&self) -> &[DirectoryEntry] {
+        &self.children
+    }
+
+    /// Index of the highlighted child.
+    #[must_use]
+    pub fn selected(&self) -> usize {
+        self.current.selected
+    }
+
+    /// The highlighted child, if any.
+    #[must_use]
+    pub fn selected_entry(&self) -> Option<&DirectoryEntry> {
+        self.children.get(self.current.selected)
+    }
+
+    /// Active sort order.
+    #[must_use]
+    pub fn sort_by(&self) -> SortBy {
+        self.sort_by
+    }
+
+    /// Number of levels above the current directory.
+    #[must_use]
+    pub fn level(&self) -> usize {
+        self.ancestors.len()
+    }
+
+    /// Move the selection by `delta` rows, clamping at both ends.
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.children.is_empty() {
+            return;
+        }
+        let last = self.children.len() - 1;
+        self.current.selected = self.current.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Select the first child.
+    pub fn select_first(&mut self) {
+        self.current.selected = 0;
+    }
+
+    /// Select the last child.
+    pub fn select_last(&mut self) {
+        self.current.selected = self.children.len().saturating_sub(1);
+    }
+
+    /// Descend into the selected child. Returns `false` when it has no children.
+    pub fn enter_selected(&mut self) -> bool {
+        let Some(entry) = self.selected_entry() else {
+            return false;
+        };
+
+        if get_immediate_children(&self.entries, &entry.path, entry.depth).is_empty() {
+            return false;
+        }
+
+        let next = Frame {
+            path: entry.path.clone(),
+            depth: entry.depth,
+            size_bytes: entry.size_bytes,
+            selected: 0,
+        };
+        let previous = std::mem::replace(&mut self.current, next);
+        self.ancestors.push(previous);
+        self.reload_children();
+        true
+    }
+
+    /// Return to the parent directory. Returns `false` at the starting level.
+    pub fn leave(&mut self) -> bool {
+        let Some(parent) = self.ancestors.pop() else {
+            return false;
+        };
+
+        self.current = parent;
+        self.reload_children();
+        true
+    }
+
+    /// Change the sort order, keeping the selected entry highlighted.
+    pub fn set_sort(&mut self, sort_by: SortBy) {
+        if self.sort_by == sort_by {
+            return;
+        }
+
+        let selected_path = self.selected_entry().map(|e| e.path.clone());
+        self.sort_by = sort_by;
+        self.reload_children();
+
+        if let Some(path) = selected_path {
+            self.current.selected = self
+                .children
+                .iter()
+                .position(|e| e.path == path)
+                .unwrap_or(0);
+        }
+    }
+
+    fn reload_children(&mut self) {
+        let children =
+            get_immediate_children(&self.entries, &self.current.path, self.current.depth);
+        self.children = sort_and_limit(children, self.sort_by, None);
+        self.current.selected = self
+            .current
+            .selected
+            .min(self.children.len().saturating_sub(1));
+    }
+}
+
+/// Restores the terminal when dropped, even if rendering fails midway.
+struct TerminalGuard;
+
+impl TerminalGuard {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        if let Err(err) = execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide) {
+            let _ = terminal::disable_raw_mode();
+            return Err(err);
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Run the interactive browser until the user quits.
+pub fn run(mut state: TuiState) -> io::Result<()> {
+    let _guard = TerminalGuard::enter()?;
+    let mut stdout = io::stdout();
+
+    loop {
+        let (width, height) = terminal::size()?;
+        render(&mut stdout, &state, width, height)?;
+
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => {
+                if !handle_key(&mut state, key, height) {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply a key press to the state. Returns `false` when the user quits.
+fn handle_key(state: &mut TuiState, key: KeyEvent, height: u16) -> bool {
+    let page = isize::try_from(height.saturating_sub(CHROME_ROWS).max(1)).unwrap_or(1);
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => return false,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+        KeyCode::Up | KeyCode::Char('k') => state.move_selection(-1),
+        KeyCode::Down | KeyCode::Char('j') => state.move_selection(1),
+        KeyCode::PageUp => state.move_selection(-page),
+        KeyCode::PageDown => state.move_selection(page),
+        KeyCode::Home | KeyCode::Char('g') => state.select_first(),
+        KeyCode::End | KeyCode::Char('G') => state.select_last(),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+            state.enter_selected();
+        }
+        KeyCode::Left | KeyCode::Backspace | KeyCode::Char('h') => {
+            state.leave();
+        }
+        KeyCode::Char('s') => state.set_sort(SortBy::Size),
+        KeyCode::Char('f') => state.set_sort(SortBy::Files),
+        KeyCode::Char('d') => state.set_sort(SortBy::Dirs),
+        _ => {}
+    }
+
+    true
+}
+
+fn render<W: Write>(out: &mut W, state: &TuiState, width: u16, height: u16) -> io::Result<()> {
+    let width = usize::from(width);
+    let rows = usize::from(height.saturating_sub(CHROME_ROWS).max(1));
+
+    queue!(
+        out,
+        terminal::Clear(ClearType::All),
+        cursor::MoveTo(0, 0),
+        SetAttribute(style::Attribute::Bold),
+        Print(truncate(
+            &format!(
+                "{} ({})  sort: {}",
+                state.current_path(),
+                format_size(state.current_size()),
+                sort_label(state.sort_by())
+            ),
+            width
+        )),
+        SetAttribute(style::Attribute::Reset),
+        cursor::MoveTo(0, 1),
+        Print(truncate(
+            &format!(
+                "  {:<bar$} {:>6} {:>10} {:>8} {:>7}  Name",
+                "Usage",
+                "%",
+                "Size",
+                "Files",
+                "Dirs",
+                bar = BAR_WIDTH + 2
+            ),
+            width
+        )),
+    )?;
+
+    let children = state.children();
+    let offset = (state.selected() + 1).saturating_sub(rows);
+
+    if children.is_empty() {
+        queue!(out, cursor::MoveTo(0, 2), Print("  (empty)"))?;
+    }
+
+    for (row, entry) in children.iter().enumerate().skip(offset).take(rows) {
+        let ratio = size_ratio(entry.size_bytes, state.current_size());
+        let pct = ratio * 100.0;
+        let is_selected = row == state.selected();
+
+        let line = format!(
+            "{} [{}] {:>5.1}% {:>10} {:>8} {:>7}  {}",
+            if is_selected { '>' } else { ' ' },
+            usage_bar(ratio),
+            pct,
+            format_size(entry.size_bytes),
+            entry.file_count,
+            entry.dir_count,
+            display_name(entry, state.current_path())
+        );
+
+        let y = u16::try_from(row - offset + 2).unwrap_or(u16::MAX);
+        queue!(
+            out,
+            cursor::MoveTo(0, y),
+            SetForegroundColor(level_color(pct))
+        )?;
+        if is_selected {
+            queue!(out, SetAttribute(style::Attribute::Reverse))?;
+        }
+        queue!(
+            out,
+            Print(truncate(&line, width)),
+            SetAttribute(style::Attribute::Reset),
+            ResetColor
+        )?;
+    }
+
+    let footer = format!(
+        "{}/{}  ↑↓ move  →/Enter open  ←/Backspace up  s/f/d sort  q quit",
+        if children.is_empty() {
+            0
+        } else {
+            state.selected() + 1
+        },
+        children.len()
+    );
+    queue!(
+        out,
+        cursor::MoveTo(0, height.saturating_sub(1)),
+        SetForegroundColor(Color::DarkGrey),
+        Print(truncate(&footer, width)),
+        ResetColor
+    )?;
+
+    out.flush()
+}
+
+/// Render a fixed-width bar for the supplied ratio.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn usage_bar(ratio: f64) -> String {
+    let filled = ((ratio.clamp(0.0, 1.0) * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    format!("{}{}", "█".repeat(filled), " ".repeat(BAR_WIDTH - filled))
+}
+
+fn level_color(pct: f64) -> Color {
+    match usage_level(pct) {
+        UsageLevel::Critical => Color::Red,
+        UsageLevel::High => Color::Yellow,
+        UsageLevel::Moderate => Color::Cyan,
+        UsageLevel::Low => Color::DarkGrey,
+    }
+}
+
+fn sort_label(sort_by: SortBy) -> &'static str {
+    match sort_by {
+        SortBy::Size => "size",
+        SortBy::Files => "files",
+        SortBy::Dirs => "dirs",
+    }
+}
+
+fn display_name(entry: &DirectoryEntry, parent: &str) -> String {
+    let name = entry
+        .path
+        .strip_prefix(parent)
+        .map(|rest| rest.trim_start_matches('/'))
+        .filter(|rest| !rest.is_empty())
+        .unwrap_or(&entry.path);
+
+    if entry.kind.is_dir() {
+        format!("{name}/")
+    } else {
+        name.to_string()
+    }
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
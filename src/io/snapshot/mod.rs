@@ -0,0 +1,543 @@
+//! Parquet snapshot read/write operations
+//!
+//! This module provides functionality to save and load directory scan results
+//! using Apache Parquet format for efficient storage and retrieval.
+//!
+//! Every snapshot carries its layout version in the Parquet key-value metadata
+//! under [`SCHEMA_VERSION_KEY`]. Files without the key predate versioning and
+//! are read as version 1. Readers accept any version up to
+//! [`SNAPSHOT_SCHEMA_VERSION`] and refuse newer files rather than misreading them.
+//!
+//! In the current layout, scan metadata lives in the footer under [`META_KEY`].
+//! Entries occupy the leading row groups and errors the trailing ones; the
+//! boundary is recorded under [`ENTRY_ROW_GROUPS_KEY`] so readers can fetch
+//! either side with a column projection and skip the other entirely. The
+//! progress timeline of the scan, when recorded, lives under [`PROGRESS_KEY`].
+
+mod reader;
+mod sentinel;
+mod sorted;
+
+pub use reader::{EntryBatches, EntryFilter, SnapshotReader};
+pub use sorted::{
+    DEFAULT_ROW_GROUP_ROWS, DEFAULT_RUN_CAPACITY, SortedSnapshotWriter, compare_paths,
+    write_sorted_snapshot,
+};
+
+use crate::{DirectoryEntry, ErrorItem, ProgressSnapshot, SnapshotMeta};
+use arrow_array::{
+    ArrayRef, Int64Array, RecordBatch, StringArray, UInt16Array, UInt32Array, UInt64Array,
+};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions};
+use parquet::file::metadata::{FileMetaData, KeyValue};
+use parquet::file::properties::WriterProperties;
+use std::borrow::Borrow;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Layout version written by this build.
+///
+/// - 1: flat sentinel-row layout without a version key.
+/// - 2: adds entry attribute columns and the version key.
+/// - 3: moves metadata into the footer and errors into trailing row groups.
+/// - 4: adds filesystem type and mount source columns for mount roots.
+pub const SNAPSHOT_SCHEMA_VERSION: u32 = 4;
+
+/// Parquet key-value metadata key holding the layout version.
+pub const SCHEMA_VERSION_KEY: &str = "dua.schema_version";
+
+/// Parquet key-value metadata key holding the version of dua that wrote the file.
+pub const WRITER_VERSION_KEY: &str = "dua.writer_version";
+
+/// Parquet key-value metadata key holding the JSON-encoded [`SnapshotMeta`].
+pub const META_KEY: &str = "dua.meta";
+
+/// Parquet key-value metadata key holding the number of leading entry row groups.
+pub const ENTRY_ROW_GROUPS_KEY: &str = "dua.entry_row_groups";
+
+/// Parquet key-value metadata key holding the JSON-encoded progress timeline.
+pub const PROGRESS_KEY: &str = "dua.progress";
+
+/// Columns populated for entry rows.
+pub const ENTRY_COLUMNS: &[&str] = &[
+    "path",
+    "parent_path",
+    "depth",
+    "size_bytes",
+    "file_count",
+    "dir_count",
+    "kind",
+    "mtime_ns",
+    "atime_ns",
+    "inode",
+    "device",
+    "nlink",
+    "fs_type",
+    "mount_source",
+];
+
+/// Entry columns added in version 4, absent from older files.
+const MOUNT_COLUMNS: &[&str] = &["fs_type", "mount_source"];
+
+/// Columns populated for error rows.
+pub const ERROR_COLUMNS: &[&str] = &["error_path", "error_code", "error_message"];
+
+/// Version assumed for files written before the version key existed.
+const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// First version storing metadata in the footer instead of sentinel rows.
+const FOOTER_METADATA_VERSION: u32 = 3;
+
+/// First version with the [`MOUNT_COLUMNS`].
+const MOUNT_COLUMNS_VERSION: u32 = 4;
+
+/// Entry columns present in files of `version`.
+fn entry_columns(version: u32) -> Vec<&'static str> {
+    ENTRY_COLUMNS
+        .iter()
+        .copied()
+        .filter(|column| version >= MOUNT_COLUMNS_VERSION || !MOUNT_COLUMNS.contains(column))
+        .collect()
+}
+
+/// Return the Arrow schema shared by snapshot writers and readers.
+#[must_use]
+pub fn snapshot_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("path", DataType::Utf8, true),
+        Field::new("parent_path", DataType::Utf8, true),
+        Field::new("depth", DataType::UInt16, true),
+        Field::new("size_bytes", DataType::UInt64, true),
+        Field::new("file_count", DataType::UInt32, true),
+        Field::new("dir_count", DataType::UInt32, true),
+        Field::new("kind", DataType::Utf8, true),
+        Field::new("mtime_ns", DataType::Int64, true),
+        Field::new("atime_ns", DataType::Int64, true),
+        Field::new("inode", DataType::UInt64, true),
+        Field::new("device", DataType::UInt64, true),
+        Field::new("nlink", DataType::UInt64, true),
+        Field::new("fs_type", DataType::Utf8, true),
+        Field::new("mount_source", DataType::Utf8, true),
+        Field::new("error_path", DataType::Utf8, true),
+        Field::new("error_code", DataType::Utf8, true),
+        Field::new("error_message", DataType::Utf8, true),
+    ]))
+}
+
+/// Writer properties shared by snapshot writers, stamping the layout version.
+#[must_use]
+pub fn snapshot_writer_properties() -> WriterProperties {
+    WriterProperties::builder()
+        .set_key_value_metadata(Some(vec![
+            KeyValue::new(
+                SCHEMA_VERSION_KEY.to_string(),
+                SNAPSHOT_SCHEMA_VERSION.to_string(),
+            ),
+            KeyValue::new(
+                WRITER_VERSION_KEY.to_string(),
+                env!("CARGO_PKG_VERSION").to_string(),
+            ),
+        ]))
+        .build()
+}
+
+/// Read the layout version of a snapshot without loading its rows.
+pub fn read_schema_version(path: &str) -> Result<u32> {
+    let metadata = load_metadata(&File::open(path)?)?;
+    schema_version(metadata.metadata().file_metadata())
+}
+
+/// Extract the layout version from Parquet file metadata.
+fn schema_version(metadata: &FileMetaData) -> Result<u32> {
+    let Some(value) = footer_value(metadata, SCHEMA_VERSION_KEY) else {
+        return Ok(UNVERSIONED_SCHEMA_VERSION);
+    };
+
+    value.trim().parse().map_err(|_| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid snapshot schema version: {value}"),
+        )
+    })
+}
+
+/// Reject snapshots written with a layout this build does not understand.
+fn ensure_supported_version(metadata: &FileMetaData) -> Result<u32> {
+    let version = schema_version(metadata)?;
+    if version > SNAPSHOT_SCHEMA_VERSION {
+        let writer = footer_value(metadata, WRITER_VERSION_KEY)
+            .map_or_else(String::new, |v| format!(" (written by dua {v})"));
+
+        return Err(Error::new(
+            ErrorKind::Unsupported,
+            format!(
+                "Snapshot schema version {version}{writer} is newer than the highest version \
+                 supported by this build ({SNAPSHOT_SCHEMA_VERSION}); upgrade dua to read it"
+            ),
+        ));
+    }
+    Ok(version)
+}
+
+/// Outcome of [`upgrade_snapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotUpgrade {
+    pub from_version: u32,
+    pub to_version: u32,
+}
+
+impl SnapshotUpgrade {
+    /// Whether the file was rewritten.
+    #[must_use]
+    pub fn rewritten(&self) -> bool {
+        self.from_version != self.to_version
+    }
+}
+
+/// Rewrite a snapshot in the current layout.
+///
+/// When `output` is `None` the file is replaced in place via a temporary file
+/// in the same directory. Snapshots already at the current version are left
+/// untouched unless a distinct `output` is requested.
+pub fn upgrade_snapshot(input: &str, output: Option<&str>) -> Result<SnapshotUpgrade> {
+    let from_version = {
+        let metadata = load_metadata(&File::open(input)?)?;
+        ensure_supported_version(metadata.metadata().file_metadata())?
+    };
+    let upgrade = SnapshotUpgrade {
+        from_version,
+        to_version: SNAPSHOT_SCHEMA_VERSION,
+    };
+
+    let target = output.unwrap_or(input);
+    if !upgrade.rewritten() && target == input {
+        return Ok(upgrade);
+    }
+
+    if target == input {
+        let tmp_path = format!("{input}.upgrade.tmp");
+        if let Err(err) =
+            rewrite_snapshot(input, &tmp_path).and_then(|()| std::fs::rename(&tmp_path, input))
+        {
+            let _ = std::fs::remove_file(&tmp_path);
+            return Err(err);
+        }
+    } else {
+        rewrite_snapshot(input, target)?;
+    }
+
+    Ok(upgrade)
+}
+
+/// Copy a snapshot into the current layout, keeping its progress timeline.
+fn rewrite_snapshot(input: &str, output: &str) -> Result<()> {
+    let reader = SnapshotReader::open(input)?;
+    let entries = reader.read_entries(EntryFilter::new())?;
+    let errors = reader.errors()?;
+    let progress = reader.progress()?;
+
+    write_sorted_snapshot(output, reader.meta(), &entries, &errors, &progress)
+}
+
+/// Incremental writer producing the current snapshot layout.
+///
+/// Entries may be written in any number of calls; errors and metadata are
+/// supplied once when finishing, after the last entry row group is flushed.
+pub struct SnapshotWriter {
+    writer: ArrowWriter<File>,
+    schema: Arc<Schema>,
+}
+
+impl SnapshotWriter {
+    /// Create the snapshot file, including missing parent directories.
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = File::create(path)?;
+        let schema = snapshot_schema();
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(snapshot_writer_properties()))
+            .map_err(Error::other)?;
+
+        Ok(Self { writer, schema })
+    }
+
+    /// Append a batch of entries.
+    pub fn write_entries<E: Borrow<DirectoryEntry>>(&mut self, entries: &[E]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let batch = create_entries_batch(&self.schema, entries)?;
+        self.writer.write(&batch).map_err(Error::other)
+    }
+
+    /// Close the current row group so subsequent entries start a new one.
+    pub fn end_row_group(&mut self) -> Result<()> {
+        self.writer.flush().map_err(Error::other)
+    }
+
+    /// Record the progress timeline in the footer.
+    pub fn set_progress(&mut self, progress: &[ProgressSnapshot]) -> Result<()> {
+        if progress.is_empty() {
+            return Ok(());
+        }
+
+        let json = serde_json::to_string(progress).map_err(Error::other)?;
+        self.writer
+            .append_key_value_metadata(KeyValue::new(PROGRESS_KEY.to_string(), json));
+        Ok(())
+    }
+
+    /// Write the error row group and footer metadata, then close the file.
+    pub fn finish(mut self, meta: &SnapshotMeta, errors: &[ErrorItem]) -> Result<()> {
+        self.writer.flush().map_err(Error::other)?;
+        let entry_row_groups = self.writer.flushed_row_groups().len();
+
+        if !errors.is_empty() {
+            let batch = create_errors_batch(&self.schema, errors)?;
+            self.writer.write(&batch).map_err(Error::other)?;
+        }
+
+        let meta_json = serde_json::to_string(meta).map_err(Error::other)?;
+        self.writer
+            .append_key_value_metadata(KeyValue::new(META_KEY.to_string(), meta_json));
+        self.writer.append_key_value_metadata(KeyValue::new(
+            ENTRY_ROW_GROUPS_KEY.to_string(),
+            entry_row_groups.to_string(),
+        ));
+
+        self.writer.close().map_err(Error::other)?;
+        Ok(())
+    }
+}
+
+/// Write a snapshot to a Parquet file, sorted by path.
+pub fn write_snapshot(
+    path: &str,
+    meta: &SnapshotMeta,
+    entries: &[DirectoryEntry],
+    errors: &[ErrorItem],
+) -> Result<()> {
+    write_sorted_snapshot(path, meta, entries, errors, &[])
+}
+
+/// Read a snapshot from a Parquet file.
+///
+/// Materializes every entry; prefer [`SnapshotReader`] when only part of the
+/// tree is needed.
+pub fn read_snapshot(path: &str) -> Result<(SnapshotMeta, Vec<DirectoryEntry>, Vec<ErrorItem>)> {
+    let reader = SnapshotReader::open(path)?;
+    let entries = reader.read_entries(EntryFilter::new())?;
+    let errors = reader.errors()?;
+    Ok((reader.into_meta(), entries, errors))
+}
+
+/// Read the metadata and entries of a snapshot, skipping its errors.
+///
+/// For the current layout the error row groups are never decoded.
+pub fn read_snapshot_entries(path: &str) -> Result<(SnapshotMeta, Vec<DirectoryEntry>)> {
+    let reader = SnapshotReader::open(path)?;
+    let entries = reader.read_entries(EntryFilter::new())?;
+    Ok((reader.into_meta(), entries))
+}
+
+/// Read only the scan metadata of a snapshot.
+///
+/// For the current layout this touches the footer alone; older layouts
+/// require a full scan to locate the metadata row.
+pub fn read_snapshot_meta(path: &str) -> Result<SnapshotMeta> {
+    SnapshotReader::open(path).map(SnapshotReader::into_meta)
+}
+
+fn load_metadata(file: &File) -> Result<ArrowReaderMetadata> {
+    ArrowReaderMetadata::load(file, ArrowReaderOptions::default())
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+fn footer_value<'a>(metadata: &'a FileMetaData, key: &str) -> Option<&'a str> {
+    metadata
+        .key_value_metadata()
+        .and_then(|pairs| pairs.iter().find(|kv| kv.key == key))
+        .and_then(|kv| kv.value.as_deref())
+}
+
+/// Build a record batch holding entries, with the error columns left null.
+pub fn create_entries_batch<E: Borrow<DirectoryEntry>>(
+    schema: &Arc<Schema>,
+    entries: &[E],
+) -> Result<RecordBatch> {
+    let entries: Vec<&DirectoryEntry> = entries.iter().map(Borrow::borrow).collect();
+    let len = entries.len();
+
+    let paths: ArrayRef = Arc::new(StringArray::from(
+        entries
+            .iter()
+            .map(|e| Some(e.path.as_str()))
+            .collect::<Vec<_>>(),
+    ));
+
+    let parent_paths: ArrayRef = Arc::new(StringArray::from(
+        entries
+            .iter()
+            .map(|e| e.parent_path.as_deref())
+            .collect::<Vec<_>>(),
+    ));
+
+    let depths: ArrayRef = Arc::new(UInt16Array::from(
+        entries.iter().map(|e| Some(e.depth)).collect::<Vec<_>>(),
+    ));
+
+    let sizes: ArrayRef = Arc::new(UInt64Array::from(
+        entries
+            .iter()
+            .map(|e| Some(e.size_bytes))
+            .collect::<Vec<_>>(),
+    ));
+
+    let file_counts: ArrayRef = Arc::new(UInt32Array::from(
+        entries
+            .iter()
+            .map(|e| Some(e.file_count))
+            .collect::<Vec<_>>(),
+    ));
+
+    let dir_counts: ArrayRef = Arc::new(UInt32Array::from(
+        entries
+            .iter()
+            .map(|e| Some(e.dir_count))
+            .collect::<Vec<_>>(),
+    ));
+
+    let kinds: ArrayRef = Arc::new(StringArray::from(
+        entries
+            .iter()
+            .map(|e| Some(e.kind.as_str()))
+            .collect::<Vec<_>>(),
+    ));
+    let mtimes: ArrayRef = Arc::new(Int64Array::from(
+        entries.iter().map(|e| e.mtime_ns).collect::<Vec<_>>(),
+    ));
+    let atimes: ArrayRef = Arc::new(Int64Array::from(
+        entries.iter().map(|e| e.atime_ns).collect::<Vec<_>>(),
+    ));
+    let inodes: ArrayRef = Arc::new(UInt64Array::from(
+        entries.iter().map(|e| e.inode).collect::<Vec<_>>(),
+    ));
+    let devices: ArrayRef = Arc::new(UInt64Array::from(
+        entries.iter().map(|e| e.device).collect::<Vec<_>>(),
+    ));
+    let nlinks: ArrayRef = Arc::new(UInt64Array::from(
+        entries.iter().map(|e| e.nlink).collect::<Vec<_>>(),
+    ));
+    let fs_types: ArrayRef = Arc::new(StringArray::from(
+        entries
+            .iter()
+            .map(|e| e.fs_type.as_deref())
+            .collect::<Vec<_>>(),
+    ));
+    let mount_sources: ArrayRef = Arc::new(StringArray::from(
+        entries
+            .iter()
+            .map(|e| e.mount_source.as_deref())
+            .collect::<Vec<_>>(),
+    ));
+
+    let error_paths: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let error_codes: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let error_messages: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+
+    RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            paths,
+            parent_paths,
+            depths,
+            sizes,
+            file_counts,
+            dir_counts,
+            kinds,
+            mtimes,
+            atimes,
+            inodes,
+            devices,
+            nlinks,
+            fs_types,
+            mount_sources,
+            error_paths,
+            error_codes,
+            error_messages,
+        ],
+    )
+    .map_err(Error::other)
+}
+
+/// Build a record batch holding errors, with the entry columns left null.
+pub fn create_errors_batch(schema: &Arc<Schema>, errors: &[ErrorItem]) -> Result<RecordBatch> {
+    let len = errors.len();
+
+    let paths: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let parent_paths: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let depths: ArrayRef = Arc::new(UInt16Array::from(vec![None::<u16>; len]));
+    let sizes: ArrayRef = Arc::new(UInt64Array::from(vec![None::<u64>; len]));
+    let file_counts: ArrayRef = Arc::new(UInt32Array::from(vec![None::<u32>; len]));
+    let dir_counts: ArrayRef = Arc::new(UInt32Array::from(vec![None::<u32>; len]));
+    let kinds: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let mtimes: ArrayRef = Arc::new(Int64Array::from(vec![None::<i64>; len]));
+    let atimes: ArrayRef = Arc::new(Int64Array::from(vec![None::<i64>; len]));
+    let inodes: ArrayRef = Arc::new(UInt64Array::from(vec![None::<u64>; len]));
+    let devices: ArrayRef = Arc::new(UInt64Array::from(vec![None::<u64>; len]));
+    let nlinks: ArrayRef = Arc::new(UInt64Array::from(vec![None::<u64>; len]));
+    let fs_types: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+    let mount_sources: ArrayRef = Arc::new(StringArray::from(vec![None::<&str>; len]));
+
+    let error_paths: ArrayRef = Arc::new(StringArray::from(
+        errors
+            .iter()
+            .map(|e| Some(e.path.as_str()))
+            .collect::<Vec<_>>(),
+    ));
+    let error_codes: ArrayRef = Arc::new(StringArray::from(
+        errors
+            .iter()
+            .map(|e| Some(e.code.as_str()))
+            .collect::<Vec<_>>(),
+    ));
+    let error_messages: ArrayRef = Arc::new(StringArray::from(
+        errors
+            .iter()
+            .map(|e| Some(e.message.as_str()))
+            .collect::<Vec<_>>(),
+    ));
+
+    RecordBatch::try_new(
+        schema.clone(),
+        vec![
+            paths,
+            parent_paths,
+            depths,
+            sizes,
+            file_counts,
+            dir_counts,
+            kinds,
+            mtimes,
+            atimes,
+            inodes,
+            devices,
+            nlinks,
+            fs_types,
+            mount_sources,
+            error_paths,
+            error_codes,
+            error_messages,
+        ],
+    )
+    .map_err(Error::other)
+}
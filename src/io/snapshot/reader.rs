@@ -0,0 +1,650 @@
+//! Lazy, columnar snapshot reader.
+//!
+//! [`SnapshotReader`] opens a snapshot once, exposes its metadata and streams
+//! entries in batches. An [`EntryFilter`] is first checked against row-group
+//! statistics so groups that cannot match are never decoded, then applied to
+//! each decoded row before any strings are allocated.
+//!
+//! Layouts older than version 3 keep their metadata in sentinel rows, so they
+//! are materialized on open and filtered in memory.
+
+use super::{
+    ENTRY_ROW_GROUPS_KEY, ERROR_COLUMNS, FOOTER_METADATA_VERSION, META_KEY, PROGRESS_KEY,
+    ensure_supported_version, entry_columns, footer_value, load_metadata, sentinel,
+};
+use crate::{DirectoryEntry, EntryKind, ErrorItem, ProgressSnapshot, SnapshotMeta};
+use arrow_array::types::{Int64Type, UInt16Type, UInt32Type, UInt64Type};
+use arrow_array::{
+    Array, ArrowPrimitiveType, Int64Array, PrimitiveArray, RecordBatch, StringArray, UInt16Array,
+    UInt32Array, UInt64Array,
+};
+use parquet::arrow::ProjectionMask;
+use parquet::arrow::arrow_reader::{
+    ArrowReaderMetadata, ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder,
+};
+use parquet::file::metadata::{FileMetaData, RowGroupMetaData};
+use parquet::file::statistics::Statistics;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result};
+use std::path::Path;
+
+/// Row predicate for [`SnapshotReader::entries`].
+///
+/// All configured conditions must hold. The default filter matches every entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFilter {
+    path: Option<String>,
+    parent_path: Option<String>,
+    subtree: Option<String>,
+    max_depth: Option<u16>,
+    min_size: Option<u64>,
+}
+
+impl EntryFilter {
+    /// Create a filter matching every entry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match only the entry whose path equals `path`.
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Match only immediate children of `parent`.
+    #[must_use]
+    pub fn parent_path(mut self, parent: impl Into<String>) -> Self {
+        self.parent_path = Some(parent.into());
+        self
+    }
+
+    /// Match `root` and every entry below it.
+    #[must_use]
+    pub fn subtree(mut self, root: impl Into<String>) -> Self {
+        self.subtree = Some(root.into());
+        self
+    }
+
+    /// Match entries with `depth <= max_depth`.
+    #[must_use]
+    pub fn max_depth(mut self, max_depth: u16) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Match entries with `size_bytes >= min_size`.
+    #[must_use]
+    pub fn min_size(mut self, min_size: u64) -> Self {
+        self.min_size = Some(min_size);
+        self
+    }
+
+    /// Check a decoded entry against every condition.
+    #[must_use]
+    pub fn matches(&self, entry: &DirectoryEntry) -> bool {
+        self.matches_row(
+            &entry.path,
+            entry.parent_path.as_deref(),
+            entry.depth,
+            entry.size_bytes,
+        )
+    }
+
+    fn matches_row(&self, path: &str, parent: Option<&str>, depth: u16, size: u64) -> bool {
+        self.path.as_deref().is_none_or(|p| p == path)
+            && self
+                .parent_path
+                .as_deref()
+                .is_none_or(|p| parent == Some(p))
+            && self
+                .subtree
+                .as_deref()
+                .is_none_or(|root| in_subtree(path, root))
+            && self.max_depth.is_none_or(|max| depth <= max)
+            && self.min_size.is_none_or(|min| size >= min)
+    }
+
+    /// Byte range of `path` values any matching row must fall into.
+    fn path_bounds(&self) -> Option<(&str, Option<String>)> {
+        if let Some(path) = self.path.as_deref() {
+            return Some((path, None));
+        }
+
+        // Children of a directory are part of its subtree, so a parent filter
+        // narrows the path range as well.
+        let root = self.subtree.as_deref().or(self.parent_path.as_deref())?;
+        Some((root, Some(subtree_upper_bound(root))))
+    }
+
+    /// Whether a row group may contain a match according to its statistics.
+    ///
+    /// Missing statistics never exclude a group.
+    fn may_match(&self, group: &RowGroupMetaData, columns: &EntryColumnIndices) -> bool {
+        if let Some((lower, upper)) = self.path_bounds()
+            && let Some((min, max)) = byte_range(group, columns.path)
+        {
+            if max < lower.as_bytes() {
+                return false;
+            }
+            match upper {
+                // Exact match: the value itself must be within [min, max].
+                None if min > lower.as_bytes() => return false,
+                Some(upper) if min >= upper.as_bytes() => return false,
+                _ => {}
+            }
+        }
+
+        if let Some(parent) = self.parent_path.as_deref()
+            && let Some((min, max)) = byte_range(group, columns.parent_path)
+            && (parent.as_bytes() < min || parent.as_bytes() > max)
+        {
+            return false;
+        }
+
+        if let Some(max_depth) = self.max_depth
+            && let Some(Statistics::Int32(stats)) = group.column(columns.depth).statistics()
+            && let Some(min) = stats.min_opt()
+            && i64::from(*min) > i64::from(max_depth)
+        {
+            return false;
+        }
+
+        if let Some(min_size) = self.min_size
+            && let Some(Statistics::Int64(stats)) = group.column(columns.size_bytes).statistics()
+            && let Some(max) = stats.max_opt()
+        {
+            // UInt64 is stored as INT64 with unsigned ordering.
+            #[allow(clippy::cast_sign_loss)]
+            let max = *max as u64;
+            if max < min_size {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Whether `path` is `root` or lies below it.
+fn in_subtree(path: &str, root: &str) -> bool {
+    match path.strip_prefix(root) {
+        Some("") => true,
+        Some(rest) => root.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Smallest string greater than every path below `root`.
+///
+/// Descendants share the prefix `root/`; bumping the separator to the next
+/// byte (`0`) bounds all of them.
+fn subtree_upper_bound(root: &str) -> String {
+    let mut upper = root.strip_suffix('/').unwrap_or(root).to_string();
+    upper.push('0');
+    upper
+}
+
+fn byte_range(group: &RowGroupMetaData, column: usize) -> Option<(&[u8], &[u8])> {
+    let stats = group.column(column).statistics()?;
+    if !matches!(stats, Statistics::ByteArray(_)) {
+        return None;
+    }
+    Some((stats.min_bytes_opt()?, stats.max_bytes_opt()?))
+}
+
+/// Leaf column positions consulted when pruning entry row groups.
+#[derive(Debug, Clone, Copy)]
+struct EntryColumnIndices {
+    path: usize,
+    parent_path: usize,
+    depth: usize,
+    size_bytes: usize,
+}
+
+impl EntryColumnIndices {
+    fn resolve(metadata: &ArrowReaderMetadata) -> Result<Self> {
+        let columns = metadata.metadata().file_metadata().schema_descr().columns();
+        let find = |name: &str| {
+            columns
+                .iter()
+                .position(|c| c.name() == name)
+                .ok_or_else(|| {
+                    Error::new(ErrorKind::InvalidData, format!("Missing column: {name}"))
+                })
+        };
+
+        Ok(Self {
+            path: find("path")?,
+            parent_path: find("parent_path")?,
+            depth: find("depth")?,
+            size_bytes: find("size_bytes")?,
+        })
+    }
+}
+
+/// Open snapshot supporting repeated filtered reads.
+pub struct SnapshotReader {
+    meta: SnapshotMeta,
+    schema_version: u32,
+    source: ReaderSource,
+}
+
+enum ReaderSource {
+    Columnar {
+        file: File,
+        metadata: ArrowReaderMetadata,
+        columns: EntryColumnIndices,
+        entry_groups: usize,
+    },
+    Materialized {
+        entries: Vec<DirectoryEntry>,
+        errors: Vec<ErrorItem>,
+    },
+}
+
+impl SnapshotReader {
+    /// Open a snapshot, reading only its footer for the current layout.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        let metadata = load_metadata(&file)?;
+        let schema_version = ensure_supported_version(metadata.metadata().file_metadata())?;
+
+        if schema_version < FOOTER_METADATA_VERSION {
+            let builder = ParquetRecordBatchReaderBuilder::new_with_metadata(file, metadata);
+            let (meta, entries, errors) = sentinel::read_sentinel_snapshot(builder)?;
+            return Ok(Self {
+                meta,
+                schema_version,
+                source: ReaderSource::Materialized { entries, errors },
+            });
+        }
+
+        let meta = footer_meta(metadata.metadata().file_metadata())?;
+        let entry_groups = entry_row_groups(&metadata)?;
+        let columns = EntryColumnIndices::resolve(&metadata)?;
+
+        Ok(Self {
+            meta,
+            schema_version,
+            source: ReaderSource::Columnar {
+                file,
+                metadata,
+                columns,
+                entry_groups,
+            },
+        })
+    }
+
+    /// Scan metadata recorded in the snapshot.
+    #[must_use]
+    pub fn meta(&self) -> &SnapshotMeta {
+        &self.meta
+    }
+
+    /// Layout version the snapshot was written with.
+    #[must_use]
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Total number of entries, taken from row-group metadata.
+    #[must_use]
+    pub fn entry_count(&self) -> u64 {
+        match &self.source {
+            ReaderSource::Columnar {
+                metadata,
+                entry_groups,
+                ..
+            } => metadata.metadata().row_groups()[..*entry_groups]
+                .iter()
+                .map(|group| u64::try_from(group.num_rows()).unwrap_or(0))
+                .sum(),
+            ReaderSource::Materialized { entries, .. } => entries.len() as u64,
+        }
+    }
+
+    /// Entry row groups whose statistics do not rule out a match for `filter`.
+    ///
+    /// Always empty for materialized (pre-version 3) snapshots.
+    #[must_use]
+    pub fn candidate_row_groups(&self, filter: &EntryFilter) -> Vec<usize> {
+        match &self.source {
+            ReaderSource::Columnar {
+                metadata,
+                columns,
+                entry_groups,
+                ..
+            } => metadata.metadata().row_groups()[..*entry_groups]
+                .iter()
+                .enumerate()
+                .filter(|(_, group)| filter.may_match(group, columns))
+                .map(|(index, _)| index)
+                .collect(),
+            ReaderSource::Materialized { .. } => Vec::new(),
+        }
+    }
+
+    /// Stream entries matching `filter` in batches.
+    pub fn entries(&self, filter: EntryFilter) -> Result<EntryBatches> {
+        let source = match &self.source {
+            ReaderSource::Columnar { file, metadata, .. } => {
+                let row_groups = self.candidate_row_groups(&filter);
+                if row_groups.is_empty() {
+                    BatchSource::Buffered(None)
+                } else {
+                    BatchSource::Parquet(projected_reader(
+                        file,
+                        metadata,
+                        row_groups,
+                        &entry_columns(self.schema_version),
+                    )?)
+                }
+            }
+            ReaderSource::Materialized { entries, .. } => BatchSource::Buffered(Some(
+                entries
+                    .iter()
+                    .filter(|e| filter.matches(e))
+                    .cloned()
+                    .collect(),
+            )),
+        };
+
+        Ok(EntryBatches { source, filter })
+    }
+
+    /// Collect every entry matching `filter`.
+    pub fn read_entries(&self, filter: EntryFilter) -> Result<Vec<DirectoryEntry>> {
+        let mut entries = Vec::new();
+        for batch in self.entries(filter)? {
+            entries.extend(batch?);
+        }
+        Ok(entries)
+    }
+
+    /// Look up a single entry by path.
+    pub fn find_entry(&self, path: &str) -> Result<Option<DirectoryEntry>> {
+        for batch in self.entries(EntryFilter::new().path(path))? {
+            if let Some(entry) = batch?.into_iter().next() {
+                return Ok(Some(entry));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read the errors recorded during the scan.
+    pub fn errors(&self) -> Result<Vec<ErrorItem>> {
+        match &self.source {
+            ReaderSource::Columnar {
+                file,
+                metadata,
+                entry_groups,
+                ..
+            } => read_error_row_groups(file, metadata, *entry_groups),
+            ReaderSource::Materialized { errors, .. } => Ok(errors.clone()),
+        }
+    }
+
+    /// Read the progress timeline captured during the scan.
+    ///
+    /// Empty for snapshots written before progress was recorded.
+    pub fn progress(&self) -> Result<Vec<ProgressSnapshot>> {
+        let ReaderSource::Columnar { metadata, .. } = &self.source else {
+            return Ok(Vec::new());
+        };
+
+        footer_value(metadata.metadata().file_metadata(), PROGRESS_KEY).map_or_else(
+            || Ok(Vec::new()),
+            |json| serde_json::from_str(json).map_err(|e| Error::new(ErrorKind::InvalidData, e)),
+        )
+    }
+
+    /// Consume the reader, returning its metadata.
+    #[must_use]
+    pub fn into_meta(self) -> SnapshotMeta {
+        self.meta
+    }
+}
+
+/// Iterator over filtered entry batches produced by [`SnapshotReader::entries`].
+///
+/// Batches that end up empty after row filtering are skipped.
+pub struct EntryBatches {
+    source: BatchSource,
+    filter: EntryFilter,
+}
+
+enum BatchSource {
+    Parquet(ParquetRecordBatchReader),
+    Buffered(Option<Vec<DirectoryEntry>>),
+}
+
+impl Iterator for EntryBatches {
+    type Item = Result<Vec<DirectoryEntry>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            BatchSource::Parquet(reader) => loop {
+                let batch = match reader.next()? {
+                    Ok(batch) => batch,
+                    Err(e) => return Some(Err(Error::new(ErrorKind::InvalidData, e))),
+                };
+
+                let mut entries = Vec::new();
+                if let Err(e) = entries_from_batch(&batch, &self.filter, &mut entries) {
+                    return Some(Err(e));
+                }
+                if !entries.is_empty() {
+                    return Some(Ok(entries));
+                }
+            },
+            BatchSource::Buffered(entries) => entries.take().filter(|e| !e.is_empty()).map(Ok),
+        }
+    }
+}
+
+fn footer_meta(metadata: &FileMetaData) -> Result<SnapshotMeta> {
+    let json = footer_value(metadata, META_KEY)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "No metadata found"))?;
+    serde_json::from_str(json).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Number of leading row groups holding entries.
+fn entry_row_groups(metadata: &ArrowReaderMetadata) -> Result<usize> {
+    let total = metadata.metadata().num_row_groups();
+    let entry_groups = match footer_value(metadata.metadata().file_metadata(), ENTRY_ROW_GROUPS_KEY)
+    {
+        Some(value) => value.trim().parse::<usize>().map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid entry row group count: {value}"),
+            )
+        })?,
+        None => total,
+    };
+
+    if entry_groups > total {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Entry row group count {entry_groups} exceeds {total} row groups"),
+        ));
+    }
+
+    Ok(entry_groups)
+}
+
+/// Build a reader over `row_groups` that decodes only `columns`.
+fn projected_reader(
+    file: &File,
+    metadata: &ArrowReaderMetadata,
+    row_groups: Vec<usize>,
+    columns: &[&str],
+) -> Result<ParquetRecordBatchReader> {
+    let builder =
+        ParquetRecordBatchReaderBuilder::new_with_metadata(file.try_clone()?, metadata.clone());
+
+    let indices = columns
+        .iter()
+        .map(|name| builder.schema().index_of(name))
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let mask = ProjectionMask::roots(builder.parquet_schema(), indices);
+
+    builder
+        .with_row_groups(row_groups)
+        .with_projection(mask)
+        .build()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+fn read_error_row_groups(
+    file: &File,
+    metadata: &ArrowReaderMetadata,
+    entry_groups: usize,
+) -> Result<Vec<ErrorItem>> {
+    let mut errors = Vec::new();
+    let row_groups: Vec<usize> = (entry_groups..metadata.metadata().num_row_groups()).collect();
+    if row_groups.is_empty() {
+        return Ok(errors);
+    }
+
+    for batch in projected_reader(file, metadata, row_groups, ERROR_COLUMNS)? {
+        let batch = batch.map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let paths = typed_column::<StringArray>(&batch, "error_path")?;
+        let codes = typed_column::<StringArray>(&batch, "error_code")?;
+        let messages = typed_column::<StringArray>(&batch, "error_message")?;
+
+        for row in 0..batch.num_rows() {
+            errors.push(ErrorItem {
+                path: required_string(paths, row, "error_path")?,
+                code: required_string(codes, row, "error_code")?,
+                message: required_string(messages, row, "error_message")?,
+            });
+        }
+    }
+
+    Ok(errors)
+}
+
+/// Decode the rows of an entry batch accepted by `filter`, downcasting each
+/// column once.
+pub(super) fn entries_from_batch(
+    batch: &RecordBatch,
+    filter: &EntryFilter,
+    out: &mut Vec<DirectoryEntry>,
+) -> Result<()> {
+    let paths = typed_column::<StringArray>(batch, "path")?;
+    let parent_paths = typed_column::<StringArray>(batch, "parent_path")?;
+    let depths = typed_column::<UInt16Array>(batch, "depth")?;
+    let sizes = typed_column::<UInt64Array>(batch, "size_bytes")?;
+    let file_counts = typed_column::<UInt32Array>(batch, "file_count")?;
+    let dir_counts = typed_column::<UInt32Array>(batch, "dir_count")?;
+    let kinds = typed_column::<StringArray>(batch, "kind")?;
+    let mtimes = typed_column::<Int64Array>(batch, "mtime_ns")?;
+    let atimes = typed_column::<Int64Array>(batch, "atime_ns")?;
+    let inodes = typed_column::<UInt64Array>(batch, "inode")?;
+    let devices = typed_column::<UInt64Array>(batch, "device")?;
+    let nlinks = typed_column::<UInt64Array>(batch, "nlink")?;
+    let fs_types = optional_column::<StringArray>(batch, "fs_type")?;
+    let mount_sources = optional_column::<StringArray>(batch, "mount_source")?;
+
+    for row in 0..batch.num_rows() {
+        if paths.is_null(row) {
+            return Err(Error::new(ErrorKind::InvalidData, "Missing path"));
+        }
+        let depth = required_value::<UInt16Type>(depths, row, "depth")?;
+        let size_bytes = required_value::<UInt64Type>(sizes, row, "size_bytes")?;
+        let parent = parent_paths.is_valid(row).then(|| parent_paths.value(row));
+
+        if !filter.matches_row(paths.value(row), parent, depth, size_bytes) {
+            continue;
+        }
+
+        let kind = if kinds.is_null(row) {
+            EntryKind::Other
+        } else {
+            let value = kinds.value(row);
+            EntryKind::parse(value).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Invalid entry kind: {value}"),
+                )
+            })?
+        };
+
+        out.push(DirectoryEntry {
+            path: paths.value(row).to_string(),
+            parent_path: parent.map(str::to_string),
+            depth,
+            size_bytes,
+            file_count: required_value::<UInt32Type>(file_counts, row, "file_count")?,
+            dir_count: required_value::<UInt32Type>(dir_counts, row, "dir_count")?,
+            kind,
+            mtime_ns: optional_value::<Int64Type>(mtimes, row),
+            atime_ns: optional_value::<Int64Type>(atimes, row),
+            inode: optional_value::<UInt64Type>(inodes, row),
+            device: optional_value::<UInt64Type>(devices, row),
+            nlink: optional_value::<UInt64Type>(nlinks, row),
+            fs_type: fs_types.and_then(|array| optional_string(array, row)),
+            mount_source: mount_sources.and_then(|array| optional_string(array, row)),
+        });
+    }
+
+    Ok(())
+}
+
+fn typed_column<'a, T: 'static>(batch: &'a RecordBatch, col_name: &str) -> Result<&'a T> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    col.as_any().downcast_ref::<T>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })
+}
+
+/// Like [`typed_column`] for a column that older layouts lack.
+fn optional_column<'a, T: 'static>(
+    batch: &'a RecordBatch,
+    col_name: &str,
+) -> Result<Option<&'a T>> {
+    if batch.column_by_name(col_name).is_none() {
+        return Ok(None);
+    }
+    typed_column(batch, col_name).map(Some)
+}
+
+fn optional_string(array: &StringArray, row: usize) -> Option<String> {
+    array.is_valid(row).then(|| array.value(row).to_string())
+}
+
+fn required_string(array: &StringArray, row: usize, col_name: &str) -> Result<String> {
+    array
+        .is_valid(row)
+        .then(|| array.value(row).to_string())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Missing {col_name}")))
+}
+
+fn optional_value<T: ArrowPrimitiveType>(
+    array: &PrimitiveArray<T>,
+    row: usize,
+) -> Option<T::Native> {
+    array.is_valid(row).then(|| array.value(row))
+}
+
+fn required_value<T: ArrowPrimitiveType>(
+    array: &PrimitiveArray<T>,
+    row: usize,
+    col_name: &str,
+) -> Result<T::Native> {
+    optional_value(array, row)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, format!("Missing {col_name}")))
+}
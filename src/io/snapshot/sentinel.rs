@@ -0,0 +1,291 @@
+//! Reader for the sentinel-row layouts (schema versions 1 and 2).
+//!
+//! These layouts store entries, errors and a single metadata row in one flat
+//! table; each row is classified by which of `path`, `error_path` and
+//! `meta_scan_root` is populated. Optional columns added over time are
+//! detected per batch.
+
+use crate::{DirectoryEntry, EntryKind, ErrorItem, SnapshotMeta};
+use arrow_array::{
+    Array, Int64Array, RecordBatch, StringArray, UInt16Array, UInt32Array, UInt64Array,
+};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result};
+
+/// Read every row of a sentinel-layout snapshot.
+pub(super) fn read_sentinel_snapshot(
+    builder: ParquetRecordBatchReaderBuilder<File>,
+) -> Result<(SnapshotMeta, Vec<DirectoryEntry>, Vec<ErrorItem>)> {
+    let mut reader = builder
+        .build()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let mut entries = Vec::new();
+    let mut errors = Vec::new();
+    let mut meta: Option<SnapshotMeta> = None;
+
+    for batch_result in &mut reader {
+        let batch = batch_result.map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        for row_idx in 0..batch.num_rows() {
+            let meta_value = get_string_value(&batch, "meta_scan_root", row_idx)?;
+            let path_value = get_string_value(&batch, "path", row_idx)?;
+            let error_path_value = get_string_value(&batch, "error_path", row_idx)?;
+
+            if meta.is_none() && meta_value.is_some() {
+                meta = Some(extract_metadata(&batch, row_idx)?);
+
+                if path_value.is_none() && error_path_value.is_none() {
+                    continue;
+                }
+            }
+
+            if error_path_value.is_some() {
+                let error = extract_error(&batch, row_idx)?;
+                errors.push(error);
+                continue;
+            }
+
+            if let Some(path) = path_value
+                && !path.is_empty()
+            {
+                let entry = extract_entry(&batch, row_idx)?;
+                entries.push(entry);
+            }
+        }
+    }
+
+    let meta = meta.ok_or_else(|| Error::new(ErrorKind::InvalidData, "No metadata found"))?;
+
+    Ok((meta, entries, errors))
+}
+
+fn extract_metadata(batch: &RecordBatch, row: usize) -> Result<SnapshotMeta> {
+    let scan_root = get_string_value(batch, "meta_scan_root", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing scan_root"))?;
+    let started_at = get_string_value(batch, "meta_started_at", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing started_at"))?;
+    let finished_at = get_string_value(batch, "meta_finished_at", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing finished_at"))?;
+    let size_basis = get_string_value(batch, "meta_size_basis", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing size_basis"))?;
+    let hardlink_policy = get_string_value(batch, "meta_hardlink_policy", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing hardlink_policy"))?;
+    let strategy =
+        get_string_value(batch, "meta_strategy", row)?.unwrap_or_else(|| "legacy".to_string());
+    // Snapshots written before exclusion support lack this column entirely.
+    let excludes = if batch.column_by_name("meta_excludes").is_some() {
+        match get_string_value(batch, "meta_excludes", row)? {
+            Some(json) => {
+                serde_json::from_str(&json).map_err(|e| Error::new(ErrorKind::InvalidData, e))?
+            }
+            None => Vec::new(),
+        }
+    } else {
+        Vec::new()
+    };
+
+    Ok(SnapshotMeta {
+        scan_root,
+        started_at,
+        finished_at,
+        size_basis,
+        hardlink_policy,
+        excludes,
+        ignore_files: "disabled".to_string(),
+        max_depth: None,
+        follow_symlinks: false,
+        cross_filesystem: false,
+        strategy,
+        incomplete: false,
+        incomplete_reason: None,
+        incomplete_dirs: Vec::new(),
+        skipped_mounts: Vec::new(),
+    })
+}
+
+fn extract_entry(batch: &RecordBatch, row: usize) -> Result<DirectoryEntry> {
+    let path = get_string_value(batch, "path", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing path"))?;
+    let parent_path = get_string_value(batch, "parent_path", row)?;
+    let depth = get_u16_value(batch, "depth", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing depth"))?;
+    let size_bytes = get_u64_value(batch, "size_bytes", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing size_bytes"))?;
+    let file_count = get_u32_value(batch, "file_count", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing file_count"))?;
+    let dir_count = get_u32_value(batch, "dir_count", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing dir_count"))?;
+
+    // Snapshots written before entry attributes were recorded lack these
+    // columns; fall back to the historical count-based guess for the kind.
+    if batch.column_by_name("kind").is_none() {
+        let kind = if file_count > 0 || dir_count > 0 {
+            EntryKind::Directory
+        } else {
+            EntryKind::File
+        };
+        return Ok(DirectoryEntry {
+            path,
+            parent_path,
+            depth,
+            size_bytes,
+            file_count,
+            dir_count,
+            kind,
+            ..DirectoryEntry::default()
+        });
+    }
+
+    let kind = match get_string_value(batch, "kind", row)? {
+        Some(value) => EntryKind::parse(&value).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid entry kind: {value}"),
+            )
+        })?,
+        None => EntryKind::Other,
+    };
+
+    Ok(DirectoryEntry {
+        path,
+        parent_path,
+        depth,
+        size_bytes,
+        file_count,
+        dir_count,
+        kind,
+        mtime_ns: get_i64_value(batch, "mtime_ns", row)?,
+        atime_ns: get_i64_value(batch, "atime_ns", row)?,
+        inode: get_u64_value(batch, "inode", row)?,
+        device: get_u64_value(batch, "device", row)?,
+        nlink: get_u64_value(batch, "nlink", row)?,
+        ..DirectoryEntry::default()
+    })
+}
+
+fn extract_error(batch: &RecordBatch, row: usize) -> Result<ErrorItem> {
+    let path = get_string_value(batch, "error_path", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing error_path"))?;
+    let code = get_string_value(batch, "error_code", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing error_code"))?;
+    let message = get_string_value(batch, "error_message", row)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Missing error_message"))?;
+
+    Ok(ErrorItem {
+        path,
+        code,
+        message,
+    })
+}
+
+fn get_string_value(batch: &RecordBatch, col_name: &str, row: usize) -> Result<Option<String>> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    let array = col.as_any().downcast_ref::<StringArray>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })?;
+
+    if array.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(array.value(row).to_string()))
+    }
+}
+
+fn get_u16_value(batch: &RecordBatch, col_name: &str, row: usize) -> Result<Option<u16>> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    let array = col.as_any().downcast_ref::<UInt16Array>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })?;
+
+    if array.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(array.value(row)))
+    }
+}
+
+fn get_u32_value(batch: &RecordBatch, col_name: &str, row: usize) -> Result<Option<u32>> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    let array = col.as_any().downcast_ref::<UInt32Array>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })?;
+
+    if array.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(array.value(row)))
+    }
+}
+
+fn get_u64_value(batch: &RecordBatch, col_name: &str, row: usize) -> Result<Option<u64>> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    let array = col.as_any().downcast_ref::<UInt64Array>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })?;
+
+    if array.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(array.value(row)))
+    }
+}
+
+fn get_i64_value(batch: &RecordBatch, col_name: &str, row: usize) -> Result<Option<i64>> {
+    let col = batch.column_by_name(col_name).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Missing column: {col_name}"),
+        )
+    })?;
+
+    let array = col.as_any().downcast_ref::<Int64Array>().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Invalid type for: {col_name}"),
+        )
+    })?;
+
+    if array.is_null(row) {
+        Ok(None)
+    } else {
+        Ok(Some(array.value(row)))
+    }
+}
@@ -0,0 +1,374 @@
+//! Path-clustered snapshot output.
+//!
+//! Traversal emits entries in post-order, interleaved across rayon workers.
+//! [`SortedSnapshotWriter`] accepts them in any order and writes the snapshot
+//! sorted by path so that each row group covers a contiguous slice of the tree
+//! and its `path` statistics let readers skip unrelated subtrees.
+//!
+//! Entries are buffered up to a run capacity, sorted and spilled to temporary
+//! run files next to the output. Finishing merges the runs; when nothing was
+//! spilled the buffer is written directly. Entries already held in memory are
+//! written with [`write_sorted_snapshot`], which sorts references to them and
+//! never spills.
+
+use super::reader::entries_from_batch;
+use super::{EntryFilter, SnapshotWriter, create_entries_batch, snapshot_schema};
+use crate::{DirectoryEntry, ErrorItem, ProgressSnapshot, SnapshotMeta};
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::File;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+/// Default number of entries held in memory before a sorted run is spilled.
+pub const DEFAULT_RUN_CAPACITY: usize = 262_144;
+
+/// Default number of rows per output row group.
+pub const DEFAULT_ROW_GROUP_ROWS: usize = 65_536;
+
+/// Rows per batch handed to the Parquet writers.
+const WRITE_BATCH_ROWS: usize = 8_192;
+
+/// Order paths component by component.
+///
+/// The separator sorts before every other byte, so a directory is immediately
+/// followed by its whole subtree (`/a`, `/a/x`, `/a-b` rather than `/a`,
+/// `/a-b`, `/a/x`).
+#[must_use]
+pub fn compare_paths(a: &str, b: &str) -> Ordering {
+    let key = |byte: u8| if byte == b'/' { 0 } else { byte };
+    a.bytes().map(key).cmp(b.bytes().map(key))
+}
+
+/// Snapshot writer producing path-sorted, subtree-clustered row groups.
+pub struct SortedSnapshotWriter {
+    output: Option<ClusteredOutput>,
+    buffer: Vec<DirectoryEntry>,
+    run_capacity: usize,
+    spill_dir: PathBuf,
+    runs: Vec<PathBuf>,
+}
+
+impl SortedSnapshotWriter {
+    /// Create the snapshot file, spilling a sorted run every `run_capacity` entries.
+    pub fn create<P: AsRef<Path>>(path: P, run_capacity: usize) -> Result<Self> {
+        let path = path.as_ref();
+        let writer = SnapshotWriter::create(path)?;
+
+        let file_name = path
+            .file_name()
+            .map_or_else(|| "snapshot".into(), |name| name.to_string_lossy());
+        let spill_dir = path.with_file_name(format!(".{file_name}.runs"));
+
+        Ok(Self {
+            output: Some(ClusteredOutput::new(writer, DEFAULT_ROW_GROUP_ROWS)),
+            buffer: Vec::new(),
+            run_capacity: run_capacity.max(1),
+            spill_dir,
+            runs: Vec::new(),
+        })
+    }
+
+    /// Override the target number of rows per row group.
+    #[must_use]
+    pub fn with_row_group_rows(mut self, rows: usize) -> Self {
+        if let Some(output) = self.output.as_mut() {
+            output.groups.target_rows = rows.max(1);
+        }
+        self
+    }
+
+    /// Number of sorted runs spilled to disk so far.
+    #[must_use]
+    pub fn spilled_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Record the progress timeline in the footer.
+    pub fn set_progress(&mut self, progress: &[ProgressSnapshot]) -> Result<()> {
+        match self.output.as_mut() {
+            Some(output) => output.writer.set_progress(progress),
+            None => Err(Error::other("sorted snapshot writer already finished")),
+        }
+    }
+
+    /// Shift the sizes of entries by the delta given for their path as they
+    /// are written out.
+    pub fn adjust_sizes(&mut self, deltas: &HashMap<String, i64>) {
+        if let Some(output) = self.output.as_mut() {
+            for (path, delta) in deltas {
+                *output.adjustments.entry(path.clone()).or_default() += delta;
+            }
+        }
+    }
+
+    /// Add an entry, spilling a sorted run when the buffer is full.
+    pub fn push(&mut self, entry: DirectoryEntry) -> Result<()> {
+        self.buffer.push(entry);
+        if self.buffer.len() >= self.run_capacity {
+            self.spill_run()?;
+        }
+        Ok(())
+    }
+
+    /// Merge all runs into the output and write the footer.
+    pub fn finish(mut self, meta: &SnapshotMeta, errors: &[ErrorItem]) -> Result<()> {
+        let mut output = self
+            .output
+            .take()
+            .ok_or_else(|| Error::other("sorted snapshot writer already finished"))?;
+
+        if self.runs.is_empty() {
+            self.buffer
+                .sort_unstable_by(|a, b| compare_paths(&a.path, &b.path));
+            for entry in std::mem::take(&mut self.buffer) {
+                output.push(entry)?;
+            }
+        } else {
+            if !self.buffer.is_empty() {
+                self.spill_run()?;
+            }
+            self.merge_runs(&mut output)?;
+        }
+
+        output.finish(meta, errors)
+    }
+
+    fn spill_run(&mut self) -> Result<()> {
+        self.buffer
+            .sort_unstable_by(|a, b| compare_paths(&a.path, &b.path));
+
+        std::fs::create_dir_all(&self.spill_dir)?;
+        let run_path = self
+            .spill_dir
+            .join(format!("run-{:05}.parquet", self.runs.len()));
+
+        let schema = snapshot_schema();
+        let mut writer = ArrowWriter::try_new(File::create(&run_path)?, schema.clone(), None)
+            .map_err(Error::other)?;
+        for chunk in self.buffer.chunks(WRITE_BATCH_ROWS) {
+            let batch = create_entries_batch(&schema, chunk)?;
+            writer.write(&batch).map_err(Error::other)?;
+        }
+        writer.close().map_err(Error::other)?;
+
+        log::debug!(
+            "spilled sorted run {} with {} entries",
+            run_path.display(),
+            self.buffer.len()
+        );
+        self.runs.push(run_path);
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn merge_runs(&self, output: &mut ClusteredOutput) -> Result<()> {
+        let mut cursors = self
+            .runs
+            .iter()
+            .map(|path| RunCursor::open(path))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut heap = BinaryHeap::with_capacity(cursors.len());
+        for (run, cursor) in cursors.iter_mut().enumerate() {
+            if let Some(entry) = cursor.next_entry()? {
+                heap.push(HeapEntry { entry, run });
+            }
+        }
+
+        while let Some(HeapEntry { entry, run }) = heap.pop() {
+            output.push(entry)?;
+            if let Some(next) = cursors[run].next_entry()? {
+                heap.push(HeapEntry { entry: next, run });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SortedSnapshotWriter {
+    fn drop(&mut self) {
+        if !self.runs.is_empty() {
+            let _ = std::fs::remove_dir_all(&self.spill_dir);
+        }
+    }
+}
+
+/// Write in-memory `entries` sorted by path, with the same row group layout
+/// as [`SortedSnapshotWriter`] but without copying the entries or spilling
+/// runs.
+pub fn write_sorted_snapshot<P: AsRef<Path>, E: Borrow<DirectoryEntry>>(
+    path: P,
+    meta: &SnapshotMeta,
+    entries: &[E],
+    errors: &[ErrorItem],
+    progress: &[ProgressSnapshot],
+) -> Result<()> {
+    let mut sorted: Vec<&DirectoryEntry> = entries.iter().map(Borrow::borrow).collect();
+    sorted.sort_unstable_by(|a, b| compare_paths(&a.path, &b.path));
+
+    let mut writer = SnapshotWriter::create(path)?;
+    writer.set_progress(progress)?;
+    let mut groups = RowGroupCutter::new(DEFAULT_ROW_GROUP_ROWS);
+    let mut start = 0;
+    for (index, entry) in sorted.iter().enumerate() {
+        if groups.cut_before(entry.depth) {
+            for chunk in sorted[start..index].chunks(WRITE_BATCH_ROWS) {
+                writer.write_entries(chunk)?;
+            }
+            writer.end_row_group()?;
+            start = index;
+        }
+    }
+    for chunk in sorted[start..].chunks(WRITE_BATCH_ROWS) {
+        writer.write_entries(chunk)?;
+    }
+    writer.finish(meta, errors)
+}
+
+/// Decides where row groups end in a path-sorted stream.
+///
+/// Once a group reaches the target size it ends where the sorted stream leaves
+/// a directory (the next entry is shallower than the previous one), so groups
+/// close whole subtrees; groups are cut unconditionally at twice the target.
+struct RowGroupCutter {
+    target_rows: usize,
+    group_rows: usize,
+    last_depth: u16,
+}
+
+impl RowGroupCutter {
+    fn new(target_rows: usize) -> Self {
+        Self {
+            target_rows,
+            group_rows: 0,
+            last_depth: 0,
+        }
+    }
+
+    /// Whether a new row group starts with the next entry, at `depth`.
+    fn cut_before(&mut self, depth: u16) -> bool {
+        let cut = self.group_rows >= self.target_rows
+            && (depth < self.last_depth || self.group_rows >= self.target_rows.saturating_mul(2));
+        if cut {
+            self.group_rows = 0;
+        }
+        self.last_depth = depth;
+        self.group_rows += 1;
+        cut
+    }
+}
+
+/// Final writer that cuts row groups at subtree boundaries where possible.
+struct ClusteredOutput {
+    writer: SnapshotWriter,
+    pending: Vec<DirectoryEntry>,
+    groups: RowGroupCutter,
+    /// Size deltas applied to entries by path.
+    adjustments: HashMap<String, i64>,
+}
+
+impl ClusteredOutput {
+    fn new(writer: SnapshotWriter, target_rows: usize) -> Self {
+        Self {
+            writer,
+            pending: Vec::new(),
+            groups: RowGroupCutter::new(target_rows),
+            adjustments: HashMap::new(),
+        }
+    }
+
+    fn push(&mut self, mut entry: DirectoryEntry) -> Result<()> {
+        if let Some(delta) = self.adjustments.get(&entry.path) {
+            entry.size_bytes = entry.size_bytes.saturating_add_signed(*delta);
+        }
+        if self.groups.cut_before(entry.depth) {
+            self.write_pending()?;
+            self.writer.end_row_group()?;
+        }
+
+        self.pending.push(entry);
+
+        if self.pending.len() >= WRITE_BATCH_ROWS {
+            self.write_pending()?;
+        }
+        Ok(())
+    }
+
+    fn write_pending(&mut self) -> Result<()> {
+        self.writer.write_entries(&self.pending)?;
+        self.pending.clear();
+        Ok(())
+    }
+
+    fn finish(mut self, meta: &SnapshotMeta, errors: &[ErrorItem]) -> Result<()> {
+        self.write_pending()?;
+        self.writer.finish(meta, errors)
+    }
+}
+
+/// Sequential reader over one spilled run.
+struct RunCursor {
+    reader: ParquetRecordBatchReader,
+    pending: std::vec::IntoIter<DirectoryEntry>,
+}
+
+impl RunCursor {
+    fn open(path: &Path) -> Result<Self> {
+        let reader = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)
+            .and_then(parquet::arrow::arrow_reader::ArrowReaderBuilder::build)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        Ok(Self {
+            reader,
+            pending: Vec::new().into_iter(),
+        })
+    }
+
+    fn next_entry(&mut self) -> Result<Option<DirectoryEntry>> {
+        loop {
+            if let Some(entry) = self.pending.next() {
+                return Ok(Some(entry));
+            }
+
+            let Some(batch) = self.reader.next() else {
+                return Ok(None);
+            };
+            let batch = batch.map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+            let mut entries = Vec::with_capacity(batch.num_rows());
+            entries_from_batch(&batch, &EntryFilter::new(), &mut entries)?;
+            self.pending = entries.into_iter();
+        }
+    }
+}
+
+/// Merge heap item ordered so the smallest path is popped first.
+struct HeapEntry {
+    entry: DirectoryEntry,
+    run: usize,
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_paths(&other.entry.path, &self.entry.path).then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
@@ -9,29 +9,48 @@ pub mod io;
 pub mod models;
 pub mod services;
 
-pub use models::{DirectoryEntry, ErrorItem, ProgressSnapshot, SnapshotMeta};
+pub use models::{
+    DirectoryEntry, EntryAttributes, EntryKind, ErrorItem, ProgressSnapshot, SkippedMount,
+    SnapshotMeta,
+};
+pub use services::traverse::cancel::{CancellationToken, StopReason};
 pub use services::traverse::progress::ProgressThrottler;
 pub use services::traverse::strategy::{StrategyRegistry, TraversalStrategy};
 pub use services::traverse::{StrategyKind, TraversalContext, TraversalDispatcher};
 
+use crate::services::baseline::BaselinePlan;
+use crate::services::resume::ResumePlan;
 use crate::services::sink::SinkFinish;
 use crate::services::sink::parquet::ParquetStreamSink;
 use crate::services::traverse::progress::DEFAULT_BYTE_TRIGGER;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::result;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// Shared notifier type used for reporting traversal progress snapshots.
 pub type ProgressNotifier = Arc<dyn Fn(&ProgressSnapshot) + Send + Sync + 'static>;
 
+/// Callback told about each directory a scan is about to list.
+pub type DirectoryNotifier = Arc<dyn Fn(&Path) + Send + Sync + 'static>;
+
 /// Custom error type for the library
 #[derive(Debug)]
 pub enum Error {
     Io(std::io::Error),
     InvalidInput(String),
-    PartialFailure { completed: usize, failed: usize },
+    PartialFailure {
+        completed: usize,
+        failed: usize,
+    },
     System(String),
+    /// The scan was cancelled or ran past its deadline. `summary` holds what
+    /// was collected before stopping; snapshots are still written and flagged
+    /// incomplete.
+    Interrupted {
+        reason: StopReason,
+        summary: Box<Summary>,
+    },
 }
 
 impl std::fmt::Display for Error {
@@ -43,6 +62,17 @@ impl std::fmt::Display for Error {
                 write!(f, "Partial failure: {completed} completed, {failed} failed")
             }
             Error::System(msg) => write!(f, "System error: {msg}"),
+            Error::Interrupted { reason, summary } => {
+                let what = match reason {
+                    StopReason::Cancelled => "cancelled",
+                    StopReason::DeadlineExceeded => "deadline exceeded",
+                };
+                write!(
+                    f,
+                    "Scan {what} after {} entries; results are incomplete",
+                    summary.entry_count
+                )
+            }
         }
     }
 }
@@ -65,10 +95,43 @@ pub struct ScanOptions {
     pub hardlink_policy: HardlinkPolicy,
     pub follow_symlinks: bool,
     pub cross_filesystem: bool,
+    /// Glob patterns for entries to skip (see `services::filter`).
+    pub excludes: Vec<String>,
+    /// How `.gitignore`, `.ignore` and `.duaignore` files affect traversal.
+    pub ignore_files: IgnoreFileMode,
     pub strategy_override: Option<StrategyKind>,
+    /// Traversal backends to choose from; `None` uses
+    /// [`StrategyRegistry::with_defaults`].
+    pub strategies: Option<Arc<StrategyRegistry>>,
+    /// Worker threads in the traversal pool; `None` uses one per CPU.
+    pub threads: Option<usize>,
+    /// Directory descriptors that may be held open awaiting traversal;
+    /// `None` derives the budget from `RLIMIT_NOFILE`.
+    pub max_open_fds: Option<usize>,
+    /// Lower the CPU scheduling priority of traversal workers. Only honoured
+    /// on Linux, where each worker thread is niced and the change ends with
+    /// the scan; elsewhere the `dua` binary renices its own process instead.
+    pub nice: bool,
+    /// I/O scheduling class for traversal workers.
+    pub io_priority: IoPriority,
+    /// Cap on filesystem operations (stats, opens, directory reads) per second.
+    pub max_ops_per_sec: Option<u32>,
+    /// Token checked by every backend; cancelling it stops the scan early.
+    pub cancellation: Option<CancellationToken>,
+    /// Point in time after which the scan stops early.
+    pub deadline: Option<Instant>,
+    /// Snapshot of an interrupted scan of the same root whose completed
+    /// directories are reused instead of walked again.
+    pub resume_from: Option<PathBuf>,
+    /// Snapshot of an earlier complete scan whose unchanged directories are
+    /// reused instead of listed again.
+    pub baseline: Option<PathBuf>,
     pub progress_interval: Duration,
     pub progress_notifier: Option<ProgressNotifier>,
     pub progress_byte_trigger: u64,
+    /// Called with each directory before its children are listed, from the
+    /// traversal workers.
+    pub directory_notifier: Option<DirectoryNotifier>,
 }
 
 impl Default for ScanOptions {
@@ -79,10 +142,23 @@ impl Default for ScanOptions {
             hardlink_policy: HardlinkPolicy::Dedupe,
             follow_symlinks: false,
             cross_filesystem: false,
+            excludes: Vec::new(),
+            ignore_files: IgnoreFileMode::Disabled,
             strategy_override: None,
+            strategies: None,
+            threads: None,
+            max_open_fds: None,
+            nice: false,
+            io_priority: IoPriority::Normal,
+            max_ops_per_sec: None,
+            cancellation: None,
+            deadline: None,
+            resume_from: None,
+            baseline: None,
             progress_interval: Duration::from_secs(2),
             progress_notifier: None,
             progress_byte_trigger: DEFAULT_BYTE_TRIGGER,
+            directory_notifier: None,
         }
     }
 }
@@ -95,13 +171,29 @@ impl std::fmt::Debug for ScanOptions {
             .field("hardlink_policy", &self.hardlink_policy)
             .field("follow_symlinks", &self.follow_symlinks)
             .field("cross_filesystem", &self.cross_filesystem)
+            .field("excludes", &self.excludes)
+            .field("ignore_files", &self.ignore_files)
             .field("strategy_override", &self.strategy_override)
+            .field("strategies", &self.strategies)
+            .field("threads", &self.threads)
+            .field("max_open_fds", &self.max_open_fds)
+            .field("nice", &self.nice)
+            .field("io_priority", &self.io_priority)
+            .field("max_ops_per_sec", &self.max_ops_per_sec)
+            .field("cancellation", &self.cancellation)
+            .field("deadline", &self.deadline)
+            .field("resume_from", &self.resume_from)
+            .field("baseline", &self.baseline)
             .field("progress_interval", &self.progress_interval)
             .field(
                 "progress_notifier",
                 &self.progress_notifier.as_ref().map(|_| "<configured>"),
             )
             .field("progress_byte_trigger", &self.progress_byte_trigger)
+            .field(
+                "directory_notifier",
+                &self.directory_notifier.as_ref().map(|_| "<configured>"),
+            )
             .finish()
     }
 }
@@ -118,6 +210,40 @@ pub enum HardlinkPolicy {
     Count,
 }
 
+/// Handling of ignore files (`.gitignore`, `.ignore`, `.duaignore`) during traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IgnoreFileMode {
+    /// Ignore files are not consulted.
+    #[default]
+    Disabled,
+    /// Matched paths are skipped entirely.
+    Skip,
+    /// Matched directories are recorded as a single entry carrying their total size.
+    Collapse,
+}
+
+impl IgnoreFileMode {
+    /// Name recorded in snapshot metadata.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Disabled => "disabled",
+            Self::Skip => "skip",
+            Self::Collapse => "collapse",
+        }
+    }
+}
+
+/// I/O scheduling class requested for traversal workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IoPriority {
+    /// Inherit the scheduling class of the process.
+    #[default]
+    Normal,
+    /// Only issue I/O when the disk is otherwise idle (Linux `IOPRIO_CLASS_IDLE`).
+    Idle,
+}
+
 /// Summary result from a scan operation
 #[derive(Debug)]
 pub struct Summary {
@@ -129,6 +255,8 @@ pub struct Summary {
     pub strategy: StrategyKind,
     pub progress: Vec<ProgressSnapshot>,
     pub entry_count: u64,
+    /// Mount points left unscanned because the scan stayed on one filesystem.
+    pub skipped_mounts: Vec<SkippedMount>,
 }
 
 /// Scan a directory and return a summary
@@ -141,30 +269,19 @@ pub struct Summary {
 /// A Summary containing directory entries and any errors encountered
 pub fn scan_summary<P: AsRef<Path>>(root: P, opts: &ScanOptions) -> Result<Summary> {
     let root_path = root.as_ref().to_string_lossy().to_string();
-
-    if !root.as_ref().exists() {
-        return Err(Error::InvalidInput(format!(
-            "Path does not exist: {root_path}"
-        )));
-    }
-
-    if !root.as_ref().is_dir() {
-        return Err(Error::InvalidInput(format!(
-            "Path is not a directory: {root_path}"
-        )));
-    }
+    validate_root(root.as_ref(), &root_path)?;
+    let plans = load_reuse_plans(opts, &root_path)?;
 
     let started_at = std::time::SystemTime::now();
 
     // Create traversal context
     let mut context = services::traverse::TraversalContext::new(opts.clone(), opts.max_depth);
-    let dispatcher = services::traverse::TraversalDispatcher::for_platform(opts);
 
     // Traverse the directory tree
-    let _ = dispatcher.traverse(&root, &mut context)?;
-    context.finalize_progress();
+    let stop_reason = run_traversal(root.as_ref(), opts, &mut context, plans)?;
 
     // Extract entries and errors
+    let skipped_mounts = context.skipped_mounts();
     let (sink_finish, progress, strategy) = context.into_parts()?;
     let SinkFinish {
         entries,
@@ -174,38 +291,57 @@ pub fn scan_summary<P: AsRef<Path>>(root: P, opts: &ScanOptions) -> Result<Summa
 
     let finished_at = std::time::SystemTime::now();
 
-    Ok(Summary {
-        root: root_path,
-        entries,
-        errors,
-        started_at,
-        finished_at,
-        strategy,
-        progress,
-        entry_count,
-    })
+    finish_summary(
+        Summary {
+            root: root_path,
+            entries,
+            errors,
+            started_at,
+            finished_at,
+            strategy,
+            progress,
+            entry_count,
+            skipped_mounts,
+        },
+        stop_reason,
+    )
 }
 
 /// Scan a directory and stream results directly into a Parquet snapshot.
+///
+/// When writing over the snapshot being resumed or used as a baseline, the new
+/// snapshot is written next to it and renamed into place once finished.
 pub fn scan_to_snapshot<P: AsRef<Path>>(
     root: P,
     opts: &ScanOptions,
     snapshot_path: &str,
 ) -> Result<Summary> {
     let root_path = root.as_ref().to_string_lossy().to_string();
+    validate_root(root.as_ref(), &root_path)?;
+    let plans = load_reuse_plans(opts, &root_path)?;
 
-    if !root.as_ref().exists() {
-        return Err(Error::InvalidInput(format!(
-            "Path does not exist: {root_path}"
-        )));
-    }
+    let Some(kind) = plans.reads(Path::new(snapshot_path)) else {
+        return write_scan_snapshot(root.as_ref(), opts, snapshot_path, plans);
+    };
 
-    if !root.as_ref().is_dir() {
-        return Err(Error::InvalidInput(format!(
-            "Path is not a directory: {root_path}"
-        )));
+    let tmp_path = format!("{snapshot_path}.{kind}.tmp");
+    let result = write_scan_snapshot(root.as_ref(), opts, &tmp_path, plans);
+    match &result {
+        Ok(_) | Err(Error::Interrupted { .. }) => std::fs::rename(&tmp_path, snapshot_path)?,
+        Err(_) => {
+            let _ = std::fs::remove_file(&tmp_path);
+        }
     }
+    result
+}
 
+fn write_scan_snapshot(
+    root: &Path,
+    opts: &ScanOptions,
+    snapshot_path: &str,
+    plans: ReusePlans,
+) -> Result<Summary> {
+    let root_path = root.to_string_lossy().to_string();
     let started_at = std::time::SystemTime::now();
 
     let sink = ParquetStreamSink::try_new(snapshot_path, None)?;
@@ -214,10 +350,8 @@ pub fn scan_to_snapshot<P: AsRef<Path>>(
         opts.max_depth,
         Box::new(sink),
     );
-    let dispatcher = services::traverse::TraversalDispatcher::for_platform(opts);
 
-    let _ = dispatcher.traverse(&root, &mut context)?;
-    context.finalize_progress();
+    let stop_reason = run_traversal(root, opts, &mut context, plans)?;
 
     let finished_at = std::time::SystemTime::now();
     let strategy_active = context.strategy();
@@ -234,11 +368,20 @@ pub fn scan_to_snapshot<P: AsRef<Path>>(
             HardlinkPolicy::Dedupe => "dedupe".to_string(),
             HardlinkPolicy::Count => "count".to_string(),
         },
-        excludes: Vec::new(),
+        excludes: opts.excludes.clone(),
+        ignore_files: opts.ignore_files.as_str().to_string(),
+        max_depth: opts.max_depth,
+        follow_symlinks: opts.follow_symlinks,
+        cross_filesystem: opts.cross_filesystem,
         strategy: strategy_active.to_string(),
+        incomplete: stop_reason.is_some(),
+        incomplete_reason: stop_reason.map(|reason| reason.as_str().to_string()),
+        incomplete_dirs: context.truncated_dirs(),
+        skipped_mounts: context.skipped_mounts(),
     };
 
     context.set_sink_metadata(&meta)?;
+    let skipped_mounts = meta.skipped_mounts;
 
     let (sink_finish, progress, strategy) = context.into_parts()?;
     let SinkFinish {
@@ -247,14 +390,123 @@ pub fn scan_to_snapshot<P: AsRef<Path>>(
         entry_count,
     } = sink_finish;
 
-    Ok(Summary {
-        root: root_path,
-        entries,
-        errors,
-        started_at,
-        finished_at,
-        strategy,
-        progress,
-        entry_count,
+    finish_summary(
+        Summary {
+            root: root_path,
+            entries,
+            errors,
+            started_at,
+            finished_at,
+            strategy,
+            progress,
+            entry_count,
+            skipped_mounts,
+        },
+        stop_reason,
+    )
+}
+
+fn validate_root(root: &Path, root_path: &str) -> Result<()> {
+    if !root.exists() {
+        return Err(Error::InvalidInput(format!(
+            "Path does not exist: {root_path}"
+        )));
+    }
+
+    if !root.is_dir() {
+        return Err(Error::InvalidInput(format!(
+            "Path is not a directory: {root_path}"
+        )));
+    }
+
+    Ok(())
+}
+
+/// Earlier snapshots a scan takes entries from instead of the filesystem.
+struct ReusePlans {
+    resume: Option<ResumePlan>,
+    baseline: Option<BaselinePlan>,
+}
+
+impl ReusePlans {
+    /// Which plan, if any, reads from `snapshot`.
+    fn reads(&self, snapshot: &Path) -> Option<&'static str> {
+        if let Some(plan) = &self.resume
+            && same_file(plan.source(), snapshot)
+        {
+            return Some("resume");
+        }
+        if let Some(plan) = &self.baseline
+            && same_file(plan.source(), snapshot)
+        {
+            return Some("baseline");
+        }
+        None
+    }
+}
+
+fn load_reuse_plans(opts: &ScanOptions, root_path: &str) -> Result<ReusePlans> {
+    if opts.resume_from.is_some() && opts.baseline.is_some() {
+        return Err(Error::InvalidInput(
+            "a scan cannot both resume a snapshot and use a baseline".to_string(),
+        ));
+    }
+    Ok(ReusePlans {
+        resume: opts
+            .resume_from
+            .as_deref()
+            .map(|source| ResumePlan::load(source, root_path, opts))
+            .transpose()?,
+        baseline: opts
+            .baseline
+            .as_deref()
+            .map(|source| BaselinePlan::load(source, opts))
+            .transpose()?,
     })
 }
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    matches!(
+        (std::fs::canonicalize(a), std::fs::canonicalize(b)),
+        (Ok(a), Ok(b)) if a == b
+    )
+}
+
+/// Walk `root`, then copy any reused entries and settle hardlinked bytes,
+/// returning why the walk stopped early.
+fn run_traversal(
+    root: &Path,
+    opts: &ScanOptions,
+    context: &mut services::traverse::TraversalContext,
+    plans: ReusePlans,
+) -> Result<Option<StopReason>> {
+    if let Some(plan) = plans.resume {
+        context.set_resume_plan(plan);
+    }
+    if let Some(plan) = plans.baseline {
+        context.set_baseline_plan(plan);
+    }
+
+    let dispatcher = services::traverse::TraversalDispatcher::for_platform(opts);
+    let _ = dispatcher.traverse(root, context)?;
+    context.copy_reused_entries()?;
+    context.settle_hardlinks()?;
+    context.finalize_progress();
+
+    let stop_reason = context.stop_reason();
+    if let Some(reason) = stop_reason {
+        context.record_interruption(root, reason)?;
+    }
+    Ok(stop_reason)
+}
+
+/// Surface an early stop as [`Error::Interrupted`] carrying the partial summary.
+fn finish_summary(summary: Summary, stop_reason: Option<StopReason>) -> Result<Summary> {
+    match stop_reason {
+        Some(reason) => Err(Error::Interrupted {
+            reason,
+            summary: Box::new(summary),
+        }),
+        None => Ok(summary),
+    }
+}
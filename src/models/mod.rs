@@ -17,8 +17,65 @@ pub struct ProgressSnapshot {
     pub recent_throughput_bytes_per_sec: Option<u64>,
 }
 
+/// Filesystem object type of a scanned entry.
+///
+/// Entries whose kind was not recorded, such as rows serialized before the
+/// field existed, decode as `Other` rather than being guessed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntryKind {
+    File,
+    Directory,
+    Symlink,
+    #[default]
+    Other,
+}
+
+impl EntryKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Directory => "directory",
+            EntryKind::Symlink => "symlink",
+            EntryKind::Other => "other",
+        }
+    }
+
+    /// Parse the value stored in the snapshot `kind` column.
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "file" => Some(EntryKind::File),
+            "directory" => Some(EntryKind::Directory),
+            "symlink" => Some(EntryKind::Symlink),
+            "other" => Some(EntryKind::Other),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn is_dir(&self) -> bool {
+        matches!(self, EntryKind::Directory)
+    }
+}
+
+/// Filesystem attributes captured for an entry at scan time.
+///
+/// Timestamps are nanoseconds since the Unix epoch. Identity fields are `None`
+/// when the platform or backend cannot report them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EntryAttributes {
+    pub kind: EntryKind,
+    pub mtime_ns: Option<i64>,
+    pub atime_ns: Option<i64>,
+    pub inode: Option<u64>,
+    pub device: Option<u64>,
+    pub nlink: Option<u64>,
+}
+
 /// Represents a directory entry in the scan results
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DirectoryEntry {
     pub path: String,
     pub parent_path: Option<String>,
@@ -26,6 +83,52 @@ pub struct DirectoryEntry {
     pub size_bytes: u64,
     pub file_count: u32,
     pub dir_count: u32,
+    #[serde(default)]
+    pub kind: EntryKind,
+    #[serde(default)]
+    pub mtime_ns: Option<i64>,
+    #[serde(default)]
+    pub atime_ns: Option<i64>,
+    #[serde(default)]
+    pub inode: Option<u64>,
+    #[serde(default)]
+    pub device: Option<u64>,
+    #[serde(default)]
+    pub nlink: Option<u64>,
+    /// Filesystem type of a mount root, e.g. `ext4`, `nfs4` or `fuse.sshfs`;
+    /// `None` for entries that are not mount roots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fs_type: Option<String>,
+    /// Mount source of a mount root, e.g. `/dev/sda1` or `server:/export`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount_source: Option<String>,
+}
+
+impl DirectoryEntry {
+    /// Copy the captured filesystem attributes onto this entry.
+    #[must_use]
+    pub fn with_attributes(mut self, attrs: EntryAttributes) -> Self {
+        self.kind = attrs.kind;
+        self.mtime_ns = attrs.mtime_ns;
+        self.atime_ns = attrs.atime_ns;
+        self.inode = attrs.inode;
+        self.device = attrs.device;
+        self.nlink = attrs.nlink;
+        self
+    }
+
+    /// The filesystem attributes recorded for this entry.
+    #[must_use]
+    pub fn attributes(&self) -> EntryAttributes {
+        EntryAttributes {
+            kind: self.kind,
+            mtime_ns: self.mtime_ns,
+            atime_ns: self.atime_ns,
+            inode: self.inode,
+            device: self.device,
+            nlink: self.nlink,
+        }
+    }
 }
 
 /// Metadata for a snapshot
@@ -37,7 +140,45 @@ pub struct SnapshotMeta {
     pub size_basis: String,
     pub hardlink_policy: String,
     pub excludes: Vec<String>,
+    /// How ignore files were applied (`disabled`, `skip` or `collapse`).
+    #[serde(default = "default_ignore_files")]
+    pub ignore_files: String,
+    /// Depth limit of the scan, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<u16>,
+    #[serde(default)]
+    pub follow_symlinks: bool,
+    #[serde(default)]
+    pub cross_filesystem: bool,
     pub strategy: String,
+    /// Set when the scan was cancelled or hit its deadline before finishing.
+    /// Stored under the `partial` key; snapshots written before the rename
+    /// used `incomplete`.
+    #[serde(default, rename = "partial", alias = "incomplete")]
+    pub incomplete: bool,
+    /// Why an incomplete scan stopped (`cancelled` or `deadline`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incomplete_reason: Option<String>,
+    /// Directories recorded after the scan began stopping; every other
+    /// directory in an incomplete snapshot holds its full subtree.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub incomplete_dirs: Vec<String>,
+    /// Mount points below the scan root left unscanned because the scan
+    /// stayed on one filesystem.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped_mounts: Vec<SkippedMount>,
+}
+
+fn default_ignore_files() -> String {
+    "disabled".to_string()
+}
+
+/// A mount point a scan did not descend into
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedMount {
+    pub path: String,
+    pub device: u64,
+    pub fs_type: String,
 }
 
 /// Represents an error encountered during scanning
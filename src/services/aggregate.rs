@@ -3,21 +3,16 @@
 use crate::models::DirectoryEntry;
 use std::collections::HashMap;
 
+pub use crate::models::EntryKind;
+
 /// Sort entries by a specified field
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SortBy {
     Size,
     Files,
     Dirs,
 }
 
-/// Entry classification used when folding traversal shards.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum EntryKind {
-    File,
-    Directory,
-}
-
 /// Aggregated totals accumulated across traversal threads.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct AggregateTotals {
@@ -67,8 +62,10 @@ impl DirectoryShard {
     /// Insert or replace an entry while accounting for its classification.
     pub fn absorb_entry(&mut self, entry: DirectoryEntry, kind: EntryKind) {
         match kind {
-            EntryKind::File => self.totals.record_file(entry.size_bytes),
             EntryKind::Directory => self.totals.record_directory(entry.size_bytes),
+            EntryKind::File | EntryKind::Symlink | EntryKind::Other => {
+                self.totals.record_file(entry.size_bytes);
+            }
         }
 
         self.entries.insert(entry.path.clone(), entry);
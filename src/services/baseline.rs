@@ -0,0 +1,181 @@
+//! Incremental rescans against a previous snapshot.
+//!
+//! Adding, removing or renaming a directory's children updates its mtime, so
+//! a directory whose inode and mtime still match the baseline has the same
+//! children it had then. The scan skips listing such a directory and stat'ing
+//! its files, takes their total from the baseline and only stats the
+//! subdirectories it recorded, descending into each to check it in turn. File
+//! entries of unchanged directories are copied from the baseline once the walk
+//! finishes.
+//!
+//! Changes that do not touch a directory's mtime, such as a file growing in
+//! place, are not picked up for unchanged directories.
+
+use crate::io::snapshot::{EntryFilter, SnapshotReader};
+use crate::models::{EntryAttributes, EntryKind};
+use crate::services::resume::{ensure_same_settings, restore};
+use crate::services::traverse::TraversalContext;
+use crate::{Error, Result, ScanOptions};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A directory as recorded in the baseline snapshot.
+#[derive(Debug, Default)]
+pub(crate) struct BaselineDir {
+    inode: Option<u64>,
+    mtime_ns: Option<i64>,
+    size_bytes: u64,
+    subdir_bytes: u64,
+    /// Regular files directly inside the directory.
+    pub(crate) file_count: u32,
+    /// Names of the subdirectories recorded below the directory.
+    pub(crate) subdirs: Vec<String>,
+    linked_inodes: Vec<(u64, u64)>,
+}
+
+impl BaselineDir {
+    /// Bytes held directly by the directory's files.
+    pub(crate) fn file_bytes(&self) -> u64 {
+        self.size_bytes.saturating_sub(self.subdir_bytes)
+    }
+
+    /// Hardlinked files directly inside the directory, counted by the baseline.
+    pub(crate) fn linked_inodes(&self) -> &[(u64, u64)] {
+        &self.linked_inodes
+    }
+
+    fn matches(&self, attrs: &EntryAttributes) -> bool {
+        self.inode.is_some()
+            && self.mtime_ns.is_some()
+            && self.inode == attrs.inode
+            && self.mtime_ns == attrs.mtime_ns
+    }
+}
+
+/// Directories of a previous snapshot, keyed by path.
+#[derive(Debug)]
+pub struct BaselinePlan {
+    source: PathBuf,
+    dirs: HashMap<String, BaselineDir>,
+    /// Directories found unchanged, whose file entries are copied over.
+    unchanged: Mutex<HashSet<String>>,
+}
+
+impl BaselinePlan {
+    /// Read the directories of `source`, a snapshot taken with settings
+    /// compatible with `opts`.
+    pub fn load(source: &Path, opts: &ScanOptions) -> Result<Self> {
+        let reader = SnapshotReader::open(source)?;
+        let meta = reader.meta();
+        ensure_same_settings(meta, source, opts)?;
+        if meta.incomplete && meta.incomplete_dirs.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} does not record which directories finished and cannot be used as a baseline",
+                source.display()
+            )));
+        }
+
+        let incomplete: HashSet<&str> = meta.incomplete_dirs.iter().map(String::as_str).collect();
+        let mut dirs: HashMap<String, BaselineDir> = HashMap::new();
+        for batch in reader.entries(EntryFilter::new())? {
+            for entry in batch? {
+                let parent = entry.parent_path.as_deref().unwrap_or_default();
+                match entry.kind {
+                    EntryKind::Directory => {
+                        if !parent.is_empty() {
+                            let name = entry.path.rsplit('/').next().unwrap_or_default();
+                            let parent_dir = dirs.entry(parent.to_string()).or_default();
+                            parent_dir.subdirs.push(name.to_string());
+                            parent_dir.subdir_bytes =
+                                parent_dir.subdir_bytes.saturating_add(entry.size_bytes);
+                        }
+                        let dir = dirs.entry(entry.path).or_default();
+                        dir.inode = entry.inode;
+                        dir.mtime_ns = entry.mtime_ns;
+                        dir.size_bytes = entry.size_bytes;
+                        dir.file_count = entry.file_count;
+                    }
+                    EntryKind::File if entry.nlink.is_some_and(|links| links > 1) => {
+                        if let (Some(device), Some(inode)) = (entry.device, entry.inode) {
+                            dirs.entry(parent.to_string())
+                                .or_default()
+                                .linked_inodes
+                                .push((device, inode));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        // Directories cut short by an interrupted baseline scan are walked again.
+        dirs.retain(|path, _| !incomplete.contains(path.as_str()));
+
+        log::info!("Baseline {}: {} directories", source.display(), dirs.len());
+
+        Ok(Self {
+            source: source.to_path_buf(),
+            dirs,
+            unchanged: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Snapshot the plan was read from.
+    #[must_use]
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// The baseline record of `path` if its inode and mtime are unchanged,
+    /// marking its file entries for copying.
+    pub(crate) fn unchanged(&self, path: &str, attrs: &EntryAttributes) -> Option<&BaselineDir> {
+        let dir = self.dirs.get(path).filter(|dir| dir.matches(attrs))?;
+        self.unchanged.lock().unwrap().insert(path.to_string());
+        Some(dir)
+    }
+
+    /// Copy the file entries and errors of every unchanged directory into `context`'s sink.
+    pub(crate) fn copy_unchanged(&self, context: &TraversalContext) -> io::Result<()> {
+        let unchanged = std::mem::take(&mut *self.unchanged.lock().unwrap());
+        if unchanged.is_empty() {
+            return Ok(());
+        }
+
+        let reader = SnapshotReader::open(&self.source)?;
+        let mut copied = 0u64;
+        for batch in reader.entries(EntryFilter::new())? {
+            for entry in batch? {
+                if entry.kind != EntryKind::Directory
+                    && entry
+                        .parent_path
+                        .as_deref()
+                        .is_some_and(|parent| unchanged.contains(parent))
+                {
+                    restore(context, entry)?;
+                    copied += 1;
+                }
+            }
+        }
+        for error in reader.errors()? {
+            let parent = error.path.rsplit_once('/').map(|(parent, _)| parent);
+            if parent.is_some_and(|parent| unchanged.contains(parent)) {
+                context.restore_error(error)?;
+            }
+        }
+        // Mount points are not recorded as subdirectories, so the walk never
+        // revisits those inside unchanged directories.
+        for mount in &reader.meta().skipped_mounts {
+            let parent = mount.path.rsplit_once('/').map(|(parent, _)| parent);
+            if parent.is_some_and(|parent| unchanged.contains(parent)) {
+                context.restore_skipped_mount(mount.clone());
+            }
+        }
+
+        log::info!(
+            "Copied {copied} entries from {} unchanged directories",
+            unchanged.len()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Comparison of two snapshots joined by entry path.
+
+use crate::models::{DirectoryEntry, SnapshotMeta};
+use crate::{Error, Result};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Classification of a path between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffStatus {
+    /// Present in both snapshots and larger in the newer one.
+    Grown,
+    /// Present in both snapshots and smaller in the newer one.
+    Shrunk,
+    /// Only present in the newer snapshot.
+    Added,
+    /// Only present in the older snapshot.
+    Removed,
+    /// Present in both snapshots with identical size.
+    Unchanged,
+}
+
+impl DiffStatus {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiffStatus::Grown => "grown",
+            DiffStatus::Shrunk => "shrunk",
+            DiffStatus::Added => "added",
+            DiffStatus::Removed => "removed",
+            DiffStatus::Unchanged => "unchanged",
+        }
+    }
+}
+
+/// Size change of a single path between two snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffEntry {
+    pub path: String,
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+    pub delta_bytes: i64,
+    pub status: DiffStatus,
+}
+
+impl DiffEntry {
+    fn from_pair(path: &str, old: Option<&DirectoryEntry>, new: Option<&DirectoryEntry>) -> Self {
+        let old_size = old.map(|e| e.size_bytes);
+        let new_size = new.map(|e| e.size_bytes);
+        let delta_bytes = signed_delta(old_size.unwrap_or(0), new_size.unwrap_or(0));
+
+        let status = match (old_size, new_size) {
+            (None, _) => DiffStatus::Added,
+            (_, None) => DiffStatus::Removed,
+            _ if delta_bytes > 0 => DiffStatus::Grown,
+            _ if delta_bytes < 0 => DiffStatus::Shrunk,
+            _ => DiffStatus::Unchanged,
+        };
+
+        Self {
+            path: path.to_string(),
+            old_size,
+            new_size,
+            delta_bytes,
+            status,
+        }
+    }
+}
+
+/// Result of comparing the subtree below one path across two snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    /// The path whose subtree was compared.
+    pub root: DiffEntry,
+    /// Changed paths ranked by absolute byte delta, largest first.
+    pub entries: Vec<DiffEntry>,
+}
+
+/// Refuse to compare snapshots of different roots or size bases, whose
+/// deltas would be meaningless.
+pub fn ensure_comparable(old: &SnapshotMeta, new: &SnapshotMeta) -> Result<()> {
+    if old.scan_root != new.scan_root {
+        return Err(Error::InvalidInput(format!(
+            "snapshots are of different roots: {} and {}",
+            old.scan_root, new.scan_root
+        )));
+    }
+    if old.size_basis != new.size_basis {
+        return Err(Error::InvalidInput(format!(
+            "snapshots use different size bases: {} and {}",
+            old.size_basis, new.size_basis
+        )));
+    }
+    Ok(())
+}
+
+/// Compare every path below `path` between two snapshots.
+///
+/// Entries are joined by path. Paths present in both snapshots are compared
+/// at every depth, so a directory that grew is listed along with the
+/// descendants that grew it. An added or removed subtree is listed once, at
+/// its top. Unchanged paths are omitted; the rest are ranked by absolute byte
+/// delta and truncated to `top_k`. Returns `None` when `path` exists in
+/// neither snapshot.
+#[must_use]
+pub fn diff_subtree(
+    old_entries: &[DirectoryEntry],
+    new_entries: &[DirectoryEntry],
+    path: &str,
+    top_k: Option<usize>,
+) -> Option<SnapshotDiff> {
+    let old_root = old_entries.iter().find(|e| e.path == path);
+    let new_root = new_entries.iter().find(|e| e.path == path);
+    new_root.or(old_root)?;
+
+    let old_by_path = below(old_entries, path);
+    let new_by_path = below(new_entries, path);
+
+    // Only the top of an added or removed subtree is reported: an entry
+    // missing from one side is skipped when its parent is missing too.
+    let is_subtree_top = |entry: &DirectoryEntry, other: &HashMap<&str, &DirectoryEntry>| {
+        entry
+            .parent_path
+            .as_deref()
+            .is_none_or(|parent| parent == path || other.contains_key(parent))
+    };
+
+    let mut entries: Vec<DiffEntry> = Vec::new();
+    for (child, old) in &old_by_path {
+        match new_by_path.get(child) {
+            Some(new) => entries.push(DiffEntry::from_pair(child, Some(old), Some(new))),
+            None if is_subtree_top(old, &new_by_path) => {
+                entries.push(DiffEntry::from_pair(child, Some(old), None));
+            }
+            None => {}
+        }
+    }
+    for (child, new) in &new_by_path {
+        if !old_by_path.contains_key(child) && is_subtree_top(new, &old_by_path) {
+            entries.push(DiffEntry::from_pair(child, None, Some(new)));
+        }
+    }
+    entries.retain(|entry| entry.status != DiffStatus::Unchanged);
+
+    entries.sort_by(|a, b| {
+        b.delta_bytes
+            .unsigned_abs()
+            .cmp(&a.delta_bytes.unsigned_abs())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    if let Some(k) = top_k {
+        entries.truncate(k);
+    }
+
+    Some(SnapshotDiff {
+        root: DiffEntry::from_pair(path, old_root, new_root),
+        entries,
+    })
+}
+
+/// Entries strictly below `path`, keyed by path.
+fn below<'a>(entries: &'a [DirectoryEntry], path: &str) -> HashMap<&'a str, &'a DirectoryEntry> {
+    let prefix = if path.ends_with('/') {
+        path.to_string()
+    } else {
+        format!("{path}/")
+    };
+    entries
+        .iter()
+        .filter(|e| e.path.starts_with(&prefix))
+        .map(|e| (e.path.as_str(), e))
+        .collect()
+}
+
+fn signed_delta(old: u64, new: u64) -> i64 {
+    if new >= old {
+        i64::try_from(new - old).unwrap_or(i64::MAX)
+    } else {
+        i64::try_from(old - new).map_or(i64::MIN, |d| -d)
+    }
+}
@@ -0,0 +1,205 @@
+//! Exclusion rules applied to traversal entries.
+//!
+//! Patterns use a small glob dialect shared by `--exclude` and `--exclude-from`:
+//!
+//! - `*` matches any run of characters except `/`, `?` matches one such character.
+//! - `[abc]`, `[a-z]` and `[!abc]` match character classes.
+//! - `**` matches any run of characters including `/`.
+//! - A trailing `/` restricts the pattern to directories.
+//! - Patterns without a `/` match the entry name (`.git`, `*.log`).
+//! - Patterns with a `/` match trailing path components (`build/cache`), or the
+//!   full path when they start with `/`.
+
+use std::io;
+use std::path::Path;
+
+/// A single compiled exclusion pattern.
+#[derive(Debug, Clone)]
+struct ExcludePattern {
+    glob: Vec<char>,
+    match_name: bool,
+    dir_only: bool,
+}
+
+impl ExcludePattern {
+    fn parse(raw: &str) -> Option<Self> {
+        let mut pattern = raw.trim();
+        if pattern.is_empty() {
+            return None;
+        }
+
+        let dir_only = pattern.len() > 1 && pattern.ends_with('/');
+        if dir_only {
+            pattern = pattern.trim_end_matches('/');
+        }
+
+        let match_name = !pattern.contains('/');
+        let glob = if match_name || pattern.starts_with('/') || pattern.starts_with("**/") {
+            pattern.chars().collect()
+        } else {
+            format!("**/{pattern}").chars().collect()
+        };
+
+        Some(Self {
+            glob,
+            match_name,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, path: &[char], name: &[char], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let subject = if self.match_name { name } else { path };
+        glob_match(&self.glob, subject)
+    }
+}
+
+/// Compiled set of exclusion patterns consulted during traversal.
+#[derive(Debug, Clone, Default)]
+pub struct ExcludeFilter {
+    patterns: Vec<ExcludePattern>,
+}
+
+impl ExcludeFilter {
+    /// Compile the supplied patterns, ignoring blank entries.
+    #[must_use]
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        Self {
+            patterns: patterns
+                .iter()
+                .filter_map(|p| ExcludePattern::parse(p.as_ref()))
+                .collect(),
+        }
+    }
+
+    /// Return `true` when no patterns are configured.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check whether the entry at `path` should be skipped.
+    ///
+    /// `path` is expected in normalized form (forward slashes).
+    #[must_use]
+    pub fn is_excluded(&self, path: &str, is_dir: bool) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+
+        let trimmed = path.trim_end_matches('/');
+        let path_chars: Vec<char> = trimmed.chars().collect();
+        let name_start = path_chars
+            .iter()
+            .rposition(|c| *c == '/')
+            .map_or(0, |idx| idx + 1);
+        let name_chars = &path_chars[name_start..];
+
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches(&path_chars, name_chars, is_dir))
+    }
+}
+
+/// Read exclusion patterns from a file, one per line.
+///
+/// Blank lines and lines starting with `#` are ignored.
+pub fn read_exclude_file<P: AsRef<Path>>(path: P) -> io::Result<Vec<String>> {
+    let contents = std::fs::read_to_string(path)?;
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// Match `text` against a glob `pattern` using the dialect described in the module docs.
+pub(crate) fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') if pattern.get(1) == Some(&'*') => {
+            let mut rest = &pattern[2..];
+            // `**/` may also match zero directories.
+            if rest.first() == Some(&'/') && glob_match(&rest[1..], text) {
+                return true;
+            }
+            while rest.first() == Some(&'*') {
+                rest = &rest[1..];
+            }
+            (0..=text.len()).any(|skip| glob_match(rest, &text[skip..]))
+        }
+        Some('*') => {
+            let rest = &pattern[1..];
+            for skip in 0..=text.len() {
+                if glob_match(rest, &text[skip..]) {
+                    return true;
+                }
+                if text.get(skip) == Some(&'/') {
+                    break;
+                }
+            }
+            false
+        }
+        Some('?') => match text.first() {
+            Some(c) if *c != '/' => glob_match(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some('[') => match (text.first(), parse_class(pattern)) {
+            (Some(c), Some((matched, consumed))) if *c != '/' => {
+                matched(*c) && glob_match(&pattern[consumed..], &text[1..])
+            }
+            (Some(c), None) => *c == '[' && glob_match(&pattern[1..], &text[1..]),
+            _ => false,
+        },
+        Some('\\') if pattern.len() > 1 => {
+            text.first() == Some(&pattern[1]) && glob_match(&pattern[2..], &text[1..])
+        }
+        Some(literal) => text.first() == Some(literal) && glob_match(&pattern[1..], &text[1..]),
+    }
+}
+
+/// Parse a `[...]` character class at the start of `pattern`.
+///
+/// Returns a predicate together with the number of pattern characters consumed,
+/// or `None` when the class is unterminated and should be treated literally.
+fn parse_class(pattern: &[char]) -> Option<(impl Fn(char) -> bool, usize)> {
+    let mut idx = 1;
+    let negated = matches!(pattern.get(idx), Some('!' | '^'));
+    if negated {
+        idx += 1;
+    }
+
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    let mut first = true;
+
+    while let Some(&c) = pattern.get(idx) {
+        if c == ']' && !first {
+            let matcher = move |candidate: char| {
+                let hit = ranges
+                    .iter()
+                    .any(|(lo, hi)| *lo <= candidate && candidate <= *hi);
+                hit != negated
+            };
+            return Some((matcher, idx + 1));
+        }
+
+        if pattern.get(idx + 1) == Some(&'-')
+            && let Some(&hi) = pattern.get(idx + 2)
+            && hi != ']'
+        {
+            ranges.push((c, hi));
+            idx += 3;
+        } else {
+            ranges.push((c, c));
+            idx += 1;
+        }
+        first = false;
+    }
+
+    None
+}
@@ -25,6 +25,13 @@ pub fn format_size(bytes: u64) -> String {
     }
 }
 
+/// Format a signed byte delta with an explicit sign (e.g. `+1.50 KB`, `-512 B`)
+#[must_use]
+pub fn format_delta(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{sign}{}", format_size(delta.unsigned_abs()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,4 +45,11 @@ mod tests {
         assert_eq!(format_size(1_048_576), "1.00 MB");
         assert_eq!(format_size(1_073_741_824), "1.00 GB");
     }
+
+    #[test]
+    fn test_format_delta() {
+        assert_eq!(format_delta(0), "+0 B");
+        assert_eq!(format_delta(1536), "+1.50 KB");
+        assert_eq!(format_delta(-512), "-512 B");
+    }
 }
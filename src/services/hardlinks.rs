@@ -0,0 +1,145 @@
+//! Hardlink groups recorded in a snapshot.
+//!
+//! Files with more than one link are grouped by device and inode. Under
+//! `HardlinkPolicy::Dedupe` only the first link in path order carries the
+//! file's bytes, so directory totals do not show how much of a directory is
+//! shared with others. The report lists each group with its links and, per
+//! directory, the bytes it shares with other directories and how many of
+//! those bytes were charged to it.
+
+use crate::io::snapshot::{EntryFilter, SnapshotReader, compare_paths};
+use crate::models::{DirectoryEntry, EntryKind};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::io;
+
+/// The links of one multiply-linked file found in a snapshot.
+#[derive(Debug, Clone, Serialize)]
+pub struct HardlinkGroup {
+    pub device: u64,
+    pub inode: u64,
+    /// Size of one copy of the file.
+    pub size_bytes: u64,
+    /// Link count reported by the filesystem; links outside the scan are
+    /// missing from `paths`.
+    pub nlink: u64,
+    /// Links found in the snapshot, in path order.
+    pub paths: Vec<String>,
+    /// Number of distinct directories holding the links.
+    pub directories: usize,
+}
+
+/// Bytes a directory shares with other directories through hardlinks.
+#[derive(Debug, Clone, Serialize)]
+pub struct SharedDirectory {
+    pub path: String,
+    /// Files in the directory that have links in other directories.
+    pub linked_files: u64,
+    /// Size of those files.
+    pub shared_bytes: u64,
+    /// Part of `shared_bytes` the snapshot charges to this directory.
+    pub charged_bytes: u64,
+}
+
+/// Hardlink groups of a snapshot and the directories they span.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HardlinkReport {
+    /// Groups ranked by size, largest first.
+    pub groups: Vec<HardlinkGroup>,
+    /// Directories sharing files with other directories, ranked by shared bytes.
+    pub directories: Vec<SharedDirectory>,
+    /// Size of all groups with each file counted once.
+    pub unique_bytes: u64,
+    /// Size of all groups if every link found were a separate copy.
+    pub apparent_bytes: u64,
+}
+
+/// Group the multiply-linked files among `entries` by device and inode.
+pub fn hardlink_report<I: IntoIterator<Item = DirectoryEntry>>(entries: I) -> HardlinkReport {
+    let mut links: HashMap<(u64, u64), Vec<DirectoryEntry>> = HashMap::new();
+    for entry in entries {
+        if let Some(key) = link_key(&entry) {
+            links.entry(key).or_default().push(entry);
+        }
+    }
+
+    let mut report = HardlinkReport::default();
+    let mut directories: HashMap<String, SharedDirectory> = HashMap::new();
+    for ((device, inode), mut group) in links {
+        if group.len() < 2 {
+            continue;
+        }
+        group.sort_by(|a, b| compare_paths(&a.path, &b.path));
+
+        let size_bytes = group.iter().map(|e| e.size_bytes).max().unwrap_or(0);
+        let dirs: BTreeSet<&str> = group
+            .iter()
+            .map(|e| e.parent_path.as_deref().unwrap_or_default())
+            .collect();
+
+        if dirs.len() > 1 {
+            for dir in &dirs {
+                let charged: u64 = group
+                    .iter()
+                    .filter(|e| e.parent_path.as_deref().unwrap_or_default() == *dir)
+                    .map(|e| e.size_bytes)
+                    .sum();
+                let shared =
+                    directories
+                        .entry((*dir).to_string())
+                        .or_insert_with(|| SharedDirectory {
+                            path: (*dir).to_string(),
+                            linked_files: 0,
+                            shared_bytes: 0,
+                            charged_bytes: 0,
+                        });
+                shared.linked_files += 1;
+                shared.shared_bytes = shared.shared_bytes.saturating_add(size_bytes);
+                shared.charged_bytes = shared.charged_bytes.saturating_add(charged);
+            }
+        }
+
+        report.unique_bytes = report.unique_bytes.saturating_add(size_bytes);
+        report.apparent_bytes = report
+            .apparent_bytes
+            .saturating_add(size_bytes.saturating_mul(group.len() as u64));
+        report.groups.push(HardlinkGroup {
+            device,
+            inode,
+            size_bytes,
+            nlink: group[0].nlink.unwrap_or_default(),
+            directories: dirs.len(),
+            paths: group.into_iter().map(|e| e.path).collect(),
+        });
+    }
+
+    report.groups.sort_by(|a, b| {
+        b.size_bytes
+            .cmp(&a.size_bytes)
+            .then_with(|| compare_paths(&a.paths[0], &b.paths[0]))
+    });
+    report.directories = directories.into_values().collect();
+    report.directories.sort_by(|a, b| {
+        b.shared_bytes
+            .cmp(&a.shared_bytes)
+            .then_with(|| compare_paths(&a.path, &b.path))
+    });
+    report
+}
+
+/// Read the multiply-linked files of a snapshot and group them.
+pub fn read_hardlink_report(reader: &SnapshotReader) -> io::Result<HardlinkReport> {
+    let mut linked = Vec::new();
+    for batch in reader.entries(EntryFilter::new())? {
+        linked.extend(batch?.into_iter().filter(|e| link_key(e).is_some()));
+    }
+    Ok(hardlink_report(linked))
+}
+
+/// Device and inode of a file with more than one link.
+fn link_key(entry: &DirectoryEntry) -> Option<(u64, u64)> {
+    if entry.kind != EntryKind::File || entry.nlink.is_none_or(|links| links < 2) {
+        return None;
+    }
+    Some((entry.device?, entry.inode?))
+}
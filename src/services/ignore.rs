@@ -0,0 +1,162 @@
+//! Hierarchical ignore-file support (`.gitignore`, `.ignore`, `.duaignore`).
+//!
+//! Ignore files are loaded lazily as traversal descends. Each directory that
+//! contains at least one ignore file pushes a new scope onto an immutable
+//! stack shared between parallel workers; directories without ignore files
+//! reuse their parent's scope.
+//!
+//! Rules follow gitignore semantics: later rules override earlier ones, deeper
+//! files override shallower ones, `!` re-includes, a trailing `/` matches only
+//! directories, and patterns containing a `/` are anchored to the directory
+//! holding the ignore file. Only ignore files at or below the scan root are
+//! consulted.
+
+use crate::services::filter::glob_match;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Ignore file names consulted in every directory, in increasing precedence.
+pub const IGNORE_FILE_NAMES: &[&str] = &[".gitignore", ".ignore", ".duaignore"];
+
+#[derive(Debug)]
+struct IgnoreRule {
+    glob: Vec<char>,
+    anchored: bool,
+    dir_only: bool,
+    negated: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, mut pattern) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+
+        let dir_only = pattern.len() > 1 && pattern.ends_with('/');
+        if dir_only {
+            pattern = pattern.trim_end_matches('/');
+        }
+
+        let anchored = pattern.contains('/');
+        let pattern = pattern.trim_start_matches('/');
+        if pattern.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            glob: pattern.chars().collect(),
+            anchored,
+            dir_only,
+            negated,
+        })
+    }
+
+    fn matches(&self, relative: &[char], name: &[char], is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            glob_match(&self.glob, relative)
+        } else {
+            glob_match(&self.glob, name)
+        }
+    }
+}
+
+#[derive(Debug)]
+struct IgnoreScope {
+    parent: Option<Arc<IgnoreScope>>,
+    base: String,
+    rules: Vec<IgnoreRule>,
+}
+
+/// Stack of ignore rules in effect for a directory during traversal.
+///
+/// Cloning is cheap; scopes are reference counted and shared across threads.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreStack {
+    top: Option<Arc<IgnoreScope>>,
+}
+
+impl IgnoreStack {
+    /// Construct a stack with no rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the stack in effect inside `dir`, loading any ignore files it contains.
+    ///
+    /// `normalized_dir` must be the forward-slash form of `dir`.
+    #[must_use]
+    pub fn descend(&self, dir: &Path, normalized_dir: &str) -> Self {
+        let mut rules = Vec::new();
+
+        for name in IGNORE_FILE_NAMES {
+            let path = dir.join(name);
+            match std::fs::read_to_string(&path) {
+                Ok(contents) => rules.extend(contents.lines().filter_map(IgnoreRule::parse)),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    log::debug!("Failed to read ignore file {}: {err}", path.display());
+                }
+            }
+        }
+
+        if rules.is_empty() {
+            return self.clone();
+        }
+
+        Self {
+            top: Some(Arc::new(IgnoreScope {
+                parent: self.top.clone(),
+                base: normalized_dir.trim_end_matches('/').to_string(),
+                rules,
+            })),
+        }
+    }
+
+    /// Check whether the entry at the normalized `path` is ignored.
+    #[must_use]
+    pub fn is_ignored(&self, path: &str, is_dir: bool) -> bool {
+        let Some(top) = self.top.as_deref() else {
+            return false;
+        };
+
+        let trimmed = path.trim_end_matches('/');
+        let name: Vec<char> = trimmed
+            .rsplit('/')
+            .next()
+            .unwrap_or(trimmed)
+            .chars()
+            .collect();
+
+        let mut scope = Some(top);
+        while let Some(current) = scope {
+            if let Some(relative) = trimmed
+                .strip_prefix(current.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                let relative: Vec<char> = relative.chars().collect();
+                if let Some(rule) = current
+                    .rules
+                    .iter()
+                    .rev()
+                    .find(|rule| rule.matches(&relative, &name, is_dir))
+                {
+                    return !rule.negated;
+                }
+            }
+            scope = current.parent.as_deref();
+        }
+
+        false
+    }
+}
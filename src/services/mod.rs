@@ -1,7 +1,14 @@
 //! Core services for traversal, aggregation, and size computation
 
 pub mod aggregate;
+pub mod baseline;
+pub mod diff;
+pub mod filter;
 pub mod format;
+pub mod hardlinks;
+pub mod ignore;
+pub mod resume;
 pub mod sink;
 pub mod size;
 pub mod traverse;
+pub mod watch;
@@ -0,0 +1,214 @@
+//! Resuming an interrupted scan from its partial snapshot.
+//!
+//! A directory entry is written once every entry below it has been recorded,
+//! so a partial snapshot holds complete subtrees for every directory except
+//! those still being walked when the scan stopped; those are listed in
+//! `SnapshotMeta::incomplete_dirs`. A resumed scan walks the tree again but
+//! reuses the recorded size of each completed directory instead of
+//! descending, then copies the entries of those subtrees from the old
+//! snapshot so the new one is a full merge.
+
+use crate::io::snapshot::{EntryFilter, SnapshotReader};
+use crate::models::{DirectoryEntry, EntryKind, SnapshotMeta};
+use crate::services::traverse::TraversalContext;
+use crate::{Error, HardlinkPolicy, Result, ScanOptions, SizeBasis};
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Completed directories recorded by an interrupted scan.
+#[derive(Debug)]
+pub struct ResumePlan {
+    source: PathBuf,
+    /// Size of each completed directory, keyed by path.
+    completed: HashMap<String, u64>,
+    /// Device and inode of multiply-linked files inside completed directories.
+    linked_inodes: Vec<(u64, u64)>,
+    /// Completed directories the new scan reused instead of walking.
+    reused: Mutex<HashSet<String>>,
+}
+
+impl ResumePlan {
+    /// Read the completed directories of `source`, a snapshot of `root` taken with
+    /// settings compatible with `opts`.
+    pub fn load(source: &Path, root: &str, opts: &ScanOptions) -> Result<Self> {
+        let reader = SnapshotReader::open(source)?;
+        let meta = reader.meta();
+
+        if meta.scan_root != root {
+            return Err(Error::InvalidInput(format!(
+                "{} is a snapshot of {}, not {root}",
+                source.display(),
+                meta.scan_root
+            )));
+        }
+        ensure_same_settings(meta, source, opts)?;
+        if meta.incomplete && meta.incomplete_dirs.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "{} does not record which directories finished and cannot be resumed",
+                source.display()
+            )));
+        }
+
+        let incomplete: HashSet<&str> = meta.incomplete_dirs.iter().map(String::as_str).collect();
+        let mut completed = HashMap::new();
+        let mut linked_inodes = Vec::new();
+        for batch in reader.entries(EntryFilter::new())? {
+            for entry in batch? {
+                match entry.kind {
+                    EntryKind::Directory if !incomplete.contains(entry.path.as_str()) => {
+                        completed.insert(entry.path, entry.size_bytes);
+                    }
+                    EntryKind::File
+                        if entry.nlink.is_some_and(|links| links > 1)
+                            && entry
+                                .parent_path
+                                .as_deref()
+                                .is_some_and(|parent| !incomplete.contains(parent)) =>
+                    {
+                        if let (Some(device), Some(inode)) = (entry.device, entry.inode) {
+                            linked_inodes.push((device, inode));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        log::info!(
+            "Resuming from {}: {} completed directories",
+            source.display(),
+            completed.len()
+        );
+
+        Ok(Self {
+            source: source.to_path_buf(),
+            completed,
+            linked_inodes,
+            reused: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Snapshot the plan was read from.
+    #[must_use]
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Number of completed directories available for reuse.
+    #[must_use]
+    pub fn completed_dirs(&self) -> usize {
+        self.completed.len()
+    }
+
+    /// Hardlinked files already counted inside completed directories.
+    pub(crate) fn linked_inodes(&self) -> &[(u64, u64)] {
+        &self.linked_inodes
+    }
+
+    /// Size of `path` if it was completed, marking its subtree for copying.
+    pub(crate) fn reuse(&self, path: &str) -> Option<u64> {
+        let size = *self.completed.get(path)?;
+        self.reused.lock().unwrap().insert(path.to_string());
+        Some(size)
+    }
+
+    /// Copy entries and errors of every reused subtree into `context`'s sink.
+    pub(crate) fn copy_reused(&self, context: &TraversalContext) -> io::Result<()> {
+        let reused = std::mem::take(&mut *self.reused.lock().unwrap());
+        if reused.is_empty() {
+            return Ok(());
+        }
+
+        let reader = SnapshotReader::open(&self.source)?;
+        let mut copied = 0u64;
+        for batch in reader.entries(EntryFilter::new())? {
+            for entry in batch? {
+                if within_any(&entry.path, &reused) {
+                    restore(context, entry)?;
+                    copied += 1;
+                }
+            }
+        }
+        for error in reader.errors()? {
+            if within_any(&error.path, &reused) {
+                context.restore_error(error)?;
+            }
+        }
+        for mount in &reader.meta().skipped_mounts {
+            if within_any(&mount.path, &reused) {
+                context.restore_skipped_mount(mount.clone());
+            }
+        }
+
+        log::info!(
+            "Copied {copied} entries from {} completed subtrees",
+            reused.len()
+        );
+        Ok(())
+    }
+}
+
+/// Reject snapshots whose sizes were computed differently from a scan with `opts`.
+pub(crate) fn ensure_same_settings(
+    meta: &SnapshotMeta,
+    source: &Path,
+    opts: &ScanOptions,
+) -> Result<()> {
+    let basis = match opts.basis {
+        SizeBasis::Physical => "physical",
+        SizeBasis::Logical => "logical",
+    };
+    let hardlinks = match opts.hardlink_policy {
+        HardlinkPolicy::Dedupe => "dedupe",
+        HardlinkPolicy::Count => "count",
+    };
+    let mut differing = Vec::new();
+    if meta.size_basis != basis {
+        differing.push("size basis");
+    }
+    if meta.hardlink_policy != hardlinks {
+        differing.push("hardlink");
+    }
+    if meta.excludes != opts.excludes {
+        differing.push("exclude");
+    }
+    if meta.ignore_files != opts.ignore_files.as_str() {
+        differing.push("ignore file");
+    }
+    if meta.max_depth != opts.max_depth {
+        differing.push("max depth");
+    }
+    if meta.follow_symlinks != opts.follow_symlinks {
+        differing.push("symlink");
+    }
+    if meta.cross_filesystem != opts.cross_filesystem {
+        differing.push("mount");
+    }
+    if !differing.is_empty() {
+        return Err(Error::InvalidInput(format!(
+            "{} was scanned with different {} settings",
+            source.display(),
+            differing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+pub(crate) fn restore(context: &TraversalContext, entry: DirectoryEntry) -> io::Result<()> {
+    if entry.kind == EntryKind::Directory {
+        context.register_directory_progress();
+    } else {
+        context.register_file_progress(entry.size_bytes);
+    }
+    context.restore_entry(entry)
+}
+
+/// Whether `path` is one of `roots` or lies below one of them.
+pub(crate) fn within_any(path: &str, roots: &HashSet<String>) -> bool {
+    roots.contains(path)
+        || path
+            .match_indices('/')
+            .any(|(index, _)| roots.contains(&path[..index]))
+}
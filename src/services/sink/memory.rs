@@ -32,6 +32,15 @@ impl ScanSink for MemorySink {
         Ok(())
     }
 
+    fn adjust_sizes(&mut self, deltas: &HashMap<String, i64>) -> io::Result<()> {
+        for (path, delta) in deltas {
+            if let Some(entry) = self.entries.get_mut(path) {
+                entry.size_bytes = entry.size_bytes.saturating_add_signed(*delta);
+            }
+        }
+        Ok(())
+    }
+
     fn set_metadata(&mut self, meta: &SnapshotMeta) -> io::Result<()> {
         self.metadata = Some(meta.clone());
         Ok(())
@@ -1,6 +1,7 @@
 //! Scan sinks for handling traversal output without retaining everything in-memory.
 
-use crate::{DirectoryEntry, ErrorItem, SnapshotMeta};
+use crate::{DirectoryEntry, ErrorItem, ProgressSnapshot, SnapshotMeta};
+use std::collections::HashMap;
 use std::io;
 
 /// Aggregated result returned by a sink after traversal completes.
@@ -30,11 +31,26 @@ pub trait ScanSink: Send {
     /// Record an error encountered during traversal.
     fn record_error(&mut self, error: ErrorItem) -> io::Result<()>;
 
+    /// Shift the `size_bytes` of recorded entries by the delta given for
+    /// their path. Paths without an entry are ignored.
+    ///
+    /// Called once traversal completes, when hardlinked bytes are moved to
+    /// the first link in path order; sinks that ignore it keep the sizes as
+    /// first recorded.
+    fn adjust_sizes(&mut self, _deltas: &HashMap<String, i64>) -> io::Result<()> {
+        Ok(())
+    }
+
     /// Provide snapshot metadata prior to finalization.
     fn set_metadata(&mut self, _meta: &SnapshotMeta) -> io::Result<()> {
         Ok(())
     }
 
+    /// Provide the progress timeline collected during traversal prior to finalization.
+    fn set_progress(&mut self, _progress: &[ProgressSnapshot]) -> io::Result<()> {
+        Ok(())
+    }
+
     /// Finalize the sink once traversal completes.
     fn finish(self: Box<Self>) -> io::Result<SinkFinish>;
 }
@@ -21,7 +21,14 @@ pub fn logical_size(metadata: &Metadata) -> u64 {
 pub fn physical_size_from_metadata(metadata: &Metadata) -> u64 {
     // On Unix, use block count * 512 (standard block size)
     // This accounts for filesystem block allocation
-    metadata.blocks() * 512
+    physical_size_from_blocks(metadata.blocks())
+}
+
+/// Compute physical size from a raw count of 512-byte blocks (`st_blocks`)
+#[cfg(unix)]
+#[must_use]
+pub fn physical_size_from_blocks(blocks: u64) -> u64 {
+    blocks * 512
 }
 
 /// Compute physical size from metadata (Windows platform)
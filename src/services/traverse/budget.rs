@@ -0,0 +1,84 @@
+//! Limits on resources held by a traversal.
+//!
+//! The fd-based backends open every subdirectory of a directory before
+//! descending, so wide trees can hold far more descriptors than
+//! `RLIMIT_NOFILE` allows. [`FdBudget`] caps how many of those pending
+//! descriptors exist at once; directories beyond the cap are reopened by path
+//! when their turn comes.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Budget used when the descriptor limit cannot be queried or is unlimited.
+pub const FALLBACK_MAX_OPEN_FDS: usize = 4_096;
+
+/// Smallest budget derived from the descriptor limit.
+const MIN_OPEN_FDS: usize = 16;
+
+/// Default budget: half the soft `RLIMIT_NOFILE`, leaving the rest for the
+/// snapshot writer and the descriptors each worker holds while listing.
+#[must_use]
+pub fn default_max_open_fds() -> usize {
+    #[cfg(unix)]
+    {
+        use rustix::process::{Resource, getrlimit};
+
+        getrlimit(Resource::Nofile)
+            .current
+            .and_then(|soft| usize::try_from(soft / 2).ok())
+            .map_or(FALLBACK_MAX_OPEN_FDS, |half| half.max(MIN_OPEN_FDS))
+    }
+
+    #[cfg(not(unix))]
+    {
+        FALLBACK_MAX_OPEN_FDS
+    }
+}
+
+/// Counter of directory descriptors held open while waiting to be traversed.
+#[derive(Debug)]
+pub struct FdBudget {
+    limit: usize,
+    held: AtomicUsize,
+}
+
+impl FdBudget {
+    /// Create a budget allowing `limit` descriptors (at least one).
+    #[must_use]
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            held: AtomicUsize::new(0),
+        }
+    }
+
+    /// Maximum number of descriptors that may be held.
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Number of descriptors currently held.
+    #[must_use]
+    pub fn held(&self) -> usize {
+        self.held.load(Ordering::Relaxed)
+    }
+
+    /// Reserve up to `wanted` descriptors, returning how many were granted.
+    pub fn acquire(&self, wanted: usize) -> usize {
+        let mut granted = 0;
+        let _ = self
+            .held
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |held| {
+                granted = wanted.min(self.limit.saturating_sub(held));
+                (granted > 0).then_some(held + granted)
+            });
+        granted
+    }
+
+    /// Return `count` previously acquired descriptors to the budget.
+    pub fn release(&self, count: usize) {
+        if count > 0 {
+            self.held.fetch_sub(count, Ordering::AcqRel);
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Stopping a scan before it finishes.
+//!
+//! Embedders hand a [`CancellationToken`] and/or a deadline to
+//! [`ScanOptions`](crate::ScanOptions). Backends poll
+//! [`TraversalContext::should_stop`](super::TraversalContext::should_stop)
+//! between entries and unwind without descending further, so the entries
+//! recorded so far still form a consistent (if partial) tree.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag used to request that a running scan stop early.
+///
+/// Clones observe the same flag, so one copy can live in `ScanOptions` while
+/// another is cancelled from a different thread or signal handler.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation of every scan observing this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Whether cancellation has been requested.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// Why a scan stopped before visiting the whole tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    /// The scan's [`CancellationToken`] was cancelled.
+    Cancelled,
+    /// The scan ran past `ScanOptions::deadline`.
+    DeadlineExceeded,
+}
+
+impl StopReason {
+    /// Label persisted in `SnapshotMeta::incomplete_reason`.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StopReason::Cancelled => "cancelled",
+            StopReason::DeadlineExceeded => "deadline",
+        }
+    }
+}
+
+impl std::fmt::Display for StopReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
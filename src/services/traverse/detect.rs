@@ -1,17 +1,133 @@
 //! Filesystem detection helpers for strategy selection.
+//!
+//! On Linux the scan root is classified by its `statfs` magic number, falling
+//! back to the filesystem type name from `/proc/self/mountinfo` for magics
+//! that are not recognised.
 
 use super::StrategyKind;
-use std::path::Path;
+use super::mounts::MountEntry;
+use std::path::{Path, PathBuf};
 
 /// Normalized filesystem classifications used for strategy selection.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum FilesystemKind {
     Ntfs,
     Apfs,
     Ext,
+    Btrfs,
+    Xfs,
+    Zfs,
+    Tmpfs,
+    Nfs,
+    Cifs,
+    Overlay,
+    Fuse,
+    #[default]
     Other,
 }
 
+impl FilesystemKind {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FilesystemKind::Ntfs => "ntfs",
+            FilesystemKind::Apfs => "apfs",
+            FilesystemKind::Ext => "ext",
+            FilesystemKind::Btrfs => "btrfs",
+            FilesystemKind::Xfs => "xfs",
+            FilesystemKind::Zfs => "zfs",
+            FilesystemKind::Tmpfs => "tmpfs",
+            FilesystemKind::Nfs => "nfs",
+            FilesystemKind::Cifs => "cifs",
+            FilesystemKind::Overlay => "overlay",
+            FilesystemKind::Fuse => "fuse",
+            FilesystemKind::Other => "other",
+        }
+    }
+
+    /// Whether the filesystem is served over the network.
+    #[must_use]
+    pub fn is_network(&self) -> bool {
+        matches!(self, FilesystemKind::Nfs | FilesystemKind::Cifs)
+    }
+
+    /// Classify a Linux `statfs` `f_type` magic number.
+    #[must_use]
+    pub fn from_statfs_magic(magic: u32) -> Self {
+        match magic {
+            0xEF53 => FilesystemKind::Ext,
+            0x9123_683E => FilesystemKind::Btrfs,
+            0x5846_5342 => FilesystemKind::Xfs,
+            0x2FC1_2FC1 => FilesystemKind::Zfs,
+            0x0102_1994 => FilesystemKind::Tmpfs,
+            0x6969 => FilesystemKind::Nfs,
+            0x517B | 0xFF53_4D42 | 0xFE53_4D42 => FilesystemKind::Cifs,
+            0x794C_7630 => FilesystemKind::Overlay,
+            0x6573_5546 => FilesystemKind::Fuse,
+            0x5346_544E => FilesystemKind::Ntfs,
+            _ => FilesystemKind::Other,
+        }
+    }
+
+    /// Classify a filesystem type name as listed in `mountinfo`.
+    #[must_use]
+    pub fn from_fs_type(fs_type: &str) -> Self {
+        match fs_type {
+            "ext2" | "ext3" | "ext4" => FilesystemKind::Ext,
+            "btrfs" => FilesystemKind::Btrfs,
+            "xfs" => FilesystemKind::Xfs,
+            "zfs" => FilesystemKind::Zfs,
+            "tmpfs" => FilesystemKind::Tmpfs,
+            "nfs" | "nfs4" => FilesystemKind::Nfs,
+            "cifs" | "smb3" | "smbfs" => FilesystemKind::Cifs,
+            "overlay" => FilesystemKind::Overlay,
+            "ntfs" | "ntfs3" => FilesystemKind::Ntfs,
+            "apfs" => FilesystemKind::Apfs,
+            name if name == "fuse" || name == "fuseblk" || name.starts_with("fuse.") => {
+                FilesystemKind::Fuse
+            }
+            _ => FilesystemKind::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for FilesystemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Filesystem details for a path, including its mount when known.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilesystemInfo {
+    pub kind: FilesystemKind,
+    /// Filesystem type name from the mount table, e.g. `fuse.sshfs`.
+    pub fs_type: Option<String>,
+    /// Mount source, e.g. `server:/export`.
+    pub source: Option<String>,
+    /// Mount point containing the path.
+    pub mount_point: Option<PathBuf>,
+}
+
+impl FilesystemInfo {
+    /// Whether `path` is the mount point itself rather than a path below it.
+    #[must_use]
+    pub fn is_mount_point(&self, path: &Path) -> bool {
+        self.mount_point
+            .as_deref()
+            .is_some_and(|mount| path.canonicalize().is_ok_and(|path| path == mount))
+    }
+
+    fn from_mount(kind: FilesystemKind, mount: Option<&MountEntry>) -> Self {
+        Self {
+            kind,
+            fs_type: mount.map(|m| m.fs_type.clone()),
+            source: mount.map(|m| m.source.clone()),
+            mount_point: mount.map(|m| m.mount_point.clone()),
+        }
+    }
+}
+
 /// Determine the filesystem kind for the provided path.
 #[must_use]
 pub fn filesystem_kind_for_path(path: &Path) -> FilesystemKind {
@@ -27,7 +143,7 @@ pub fn filesystem_kind_for_path(path: &Path) -> FilesystemKind {
 
     #[cfg(all(unix, not(target_os = "macos")))]
     {
-        detect_unix_filesystem(path)
+        detect_unix_filesystem(path).kind
     }
 
     #[cfg(not(any(windows, target_os = "macos", unix)))]
@@ -37,23 +153,66 @@ pub fn filesystem_kind_for_path(path: &Path) -> FilesystemKind {
     }
 }
 
+/// Determine the filesystem kind and mount details for the provided path.
+///
+/// Mount details are only available where a mount table can be read (Linux).
+#[must_use]
+pub fn filesystem_info_for_path(path: &Path) -> FilesystemInfo {
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        detect_unix_filesystem(path)
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        FilesystemInfo::from_mount(filesystem_kind_for_path(path), None)
+    }
+}
+
 /// Return the default traversal strategy for the current host.
+///
+/// This is a platform default only; the scan root is inspected with
+/// [`strategy_for_path`] once traversal starts.
 #[must_use]
 pub fn default_strategy() -> StrategyKind {
-    let cwd = std::env::current_dir().ok();
-    let fs_kind = cwd
-        .as_deref()
-        .map_or(FilesystemKind::Other, filesystem_kind_for_path);
-    strategy_for_filesystem(fs_kind)
+    if cfg!(windows) {
+        StrategyKind::WindowsOptimized
+    } else if cfg!(unix) {
+        StrategyKind::PosixOptimized
+    } else {
+        StrategyKind::Legacy
+    }
+}
+
+/// Return the preferred traversal strategy for the filesystem holding `path`.
+#[must_use]
+pub fn strategy_for_path(path: &Path) -> StrategyKind {
+    strategy_for_filesystem(filesystem_kind_for_path(path))
 }
 
 /// Map a filesystem kind to the preferred traversal strategy.
+///
+/// Network and FUSE filesystems use the sequential legacy walker: the parallel
+/// `openat` fan-out of the POSIX backend floods remote servers and userspace
+/// daemons with concurrent requests.
+///
+/// NTFS is walked with the host's native backend: Win32 on Windows, and the
+/// POSIX backend when a kernel driver such as `ntfs3` mounts it on Linux.
 #[must_use]
 pub fn strategy_for_filesystem(kind: FilesystemKind) -> StrategyKind {
     match kind {
-        FilesystemKind::Ntfs => StrategyKind::WindowsOptimized,
-        FilesystemKind::Apfs | FilesystemKind::Ext => StrategyKind::PosixOptimized,
-        FilesystemKind::Other => StrategyKind::Legacy,
+        FilesystemKind::Ntfs => default_strategy(),
+        FilesystemKind::Apfs
+        | FilesystemKind::Ext
+        | FilesystemKind::Btrfs
+        | FilesystemKind::Xfs
+        | FilesystemKind::Zfs
+        | FilesystemKind::Tmpfs
+        | FilesystemKind::Overlay => StrategyKind::PosixOptimized,
+        FilesystemKind::Nfs
+        | FilesystemKind::Cifs
+        | FilesystemKind::Fuse
+        | FilesystemKind::Other => StrategyKind::Legacy,
     }
 }
 
@@ -84,14 +243,43 @@ fn detect_macos_filesystem(path: &Path) -> FilesystemKind {
 }
 
 #[cfg(all(unix, not(target_os = "macos")))]
-fn detect_unix_filesystem(_path: &Path) -> FilesystemKind {
-    #[cfg(target_os = "linux")]
-    {
-        FilesystemKind::Ext
-    }
+fn detect_unix_filesystem(path: &Path) -> FilesystemInfo {
+    use std::os::unix::fs::MetadataExt;
 
-    #[cfg(not(target_os = "linux"))]
-    {
-        FilesystemKind::Other
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let device = std::fs::metadata(&canonical).ok().map(|md| {
+        let dev = md.dev();
+        (rustix::fs::major(dev), rustix::fs::minor(dev))
+    });
+
+    let table = super::mounts::read_mount_table();
+    let mount = super::mounts::mount_for_path(&table, &canonical, device);
+
+    let kind = match statfs_kind(&canonical) {
+        FilesystemKind::Other => mount.map_or(FilesystemKind::Other, |m| {
+            FilesystemKind::from_fs_type(&m.fs_type)
+        }),
+        kind => kind,
+    };
+
+    FilesystemInfo::from_mount(kind, mount)
+}
+
+#[cfg(target_os = "linux")]
+fn statfs_kind(path: &Path) -> FilesystemKind {
+    match rustix::fs::statfs(path) {
+        // `f_type` is signed on some targets; magics are 32-bit values.
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::unnecessary_cast
+        )]
+        Ok(stat) => FilesystemKind::from_statfs_magic(stat.f_type as u32),
+        Err(_) => FilesystemKind::Other,
     }
 }
+
+#[cfg(all(unix, not(any(target_os = "linux", target_os = "macos"))))]
+fn statfs_kind(_path: &Path) -> FilesystemKind {
+    FilesystemKind::Other
+}
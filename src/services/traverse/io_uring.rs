@@ -0,0 +1,483 @@
+//! `io_uring` traversal strategy for Linux.
+//!
+//! The walk is the POSIX backend's fd-relative traversal, but the `statx` and
+//! `openat` calls for the children of a directory are submitted to an
+//! `io_uring` in batches, so one `io_uring_enter` covers a whole directory
+//! instead of one syscall per child. Every rayon worker owns its own ring.
+//!
+//! Kernels without `io_uring` (or with it disabled by seccomp or
+//! `kernel.io_uring_disabled`) are detected once by probing for the `STATX`
+//! and `OPENAT` opcodes; the dispatcher then falls back to the POSIX backend.
+
+use super::detect::FilesystemInfo;
+use super::strategy::TraversalStrategy;
+use super::{StrategyKind, TraversalContext, posix};
+use crate::ScanOptions;
+use std::io;
+use std::path::Path;
+
+/// `io_uring` traversal backend.
+#[derive(Debug, Default)]
+pub struct IoUringTraversal;
+
+impl IoUringTraversal {
+    /// Whether the running kernel accepts `io_uring` `statx` and `openat` submissions.
+    #[must_use]
+    pub fn is_supported() -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            static SUPPORTED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+            *SUPPORTED.get_or_init(ring::probe)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+}
+
+impl TraversalStrategy for IoUringTraversal {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::IoUring
+    }
+
+    fn is_available(&self) -> bool {
+        Self::is_supported()
+    }
+
+    /// Only runs when requested explicitly; automatic selection keeps the
+    /// POSIX backend.
+    fn is_eligible(&self, _opts: &ScanOptions, _fs: &FilesystemInfo) -> bool {
+        false
+    }
+
+    fn traverse(&self, root: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64> {
+        #[cfg(target_os = "linux")]
+        {
+            posix::traverse_with(root, depth, context, &ring::RingCalls)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            log::debug!("io_uring::IoUringTraversal invoked off Linux; falling back to posix");
+            posix::PosixTraversal.traverse(root, depth, context)
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod ring {
+    use super::posix::{
+        ChildOps, ChildStat, DIRECTORY_FLAGS, DirectCalls, PendingChild, child_stat_from_statx,
+        statx_mask,
+    };
+    use crate::SizeBasis;
+    use rustix::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+    use rustix::fs::{AtFlags, Statx};
+    use rustix::io_uring::{
+        IORING_OFF_CQ_RING, IORING_OFF_SQ_RING, IORING_OFF_SQES, IoringEnterFlags, IoringOp,
+        IoringOpFlags, IoringRegisterOp, io_uring_cqe, io_uring_enter, io_uring_params,
+        io_uring_probe, io_uring_probe_op, io_uring_ptr, io_uring_register, io_uring_setup,
+        io_uring_sqe, io_uring_user_data,
+    };
+    use rustix::mm::{MapFlags, ProtFlags, mmap, munmap};
+    use std::cell::RefCell;
+    use std::ffi::{CStr, c_void};
+    use std::io;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Submission queue size requested per ring; larger directories are
+    /// submitted in several batches.
+    const RING_ENTRIES: u32 = 256;
+
+    thread_local! {
+        static RING: RefCell<RingSlot> = const { RefCell::new(RingSlot::Uninit) };
+    }
+
+    enum RingSlot {
+        Uninit,
+        Ready(Ring),
+        /// Setup or a submission failed on this thread; use direct syscalls.
+        Unavailable,
+    }
+
+    /// Probe whether `io_uring` with `STATX` and `OPENAT` is usable.
+    pub(super) fn probe() -> bool {
+        match Ring::new(4).and_then(|ring| ring.supports(&[IoringOp::Statx, IoringOp::Openat])) {
+            Ok(supported) => {
+                if !supported {
+                    log::debug!("io_uring lacks STATX/OPENAT support");
+                }
+                supported
+            }
+            Err(err) => {
+                log::debug!("io_uring unavailable: {err}");
+                false
+            }
+        }
+    }
+
+    /// Submits child calls through the calling thread's ring.
+    pub(super) struct RingCalls;
+
+    impl RingCalls {
+        /// Run `op` on this thread's ring, falling back to `fallback` when no
+        /// ring can be used. A ring whose submission fails is retired.
+        fn with_ring<T>(
+            op: impl FnOnce(&mut Ring) -> io::Result<Vec<T>>,
+            fallback: impl FnOnce() -> Vec<T>,
+        ) -> Vec<T> {
+            RING.with_borrow_mut(|slot| {
+                if matches!(slot, RingSlot::Uninit) {
+                    *slot = match Ring::new(RING_ENTRIES) {
+                        Ok(ring) => RingSlot::Ready(ring),
+                        Err(err) => {
+                            log::debug!("io_uring setup failed on worker: {err}");
+                            RingSlot::Unavailable
+                        }
+                    };
+                }
+
+                let RingSlot::Ready(ring) = slot else {
+                    return fallback();
+                };
+                match op(ring) {
+                    Ok(results) => results,
+                    Err(err) => {
+                        log::warn!("io_uring submission failed, using direct syscalls: {err}");
+                        *slot = RingSlot::Unavailable;
+                        fallback()
+                    }
+                }
+            })
+        }
+    }
+
+    impl ChildOps for RingCalls {
+        fn stat_children(
+            &self,
+            dir_fd: &OwnedFd,
+            children: &[PendingChild],
+            basis: SizeBasis,
+        ) -> Vec<io::Result<ChildStat>> {
+            if children.is_empty() {
+                return Vec::new();
+            }
+            Self::with_ring(
+                |ring| ring.statx_batch(dir_fd, children, basis),
+                || DirectCalls.stat_children(dir_fd, children, basis),
+            )
+        }
+
+        fn open_directories(&self, dir_fd: &OwnedFd, names: &[&CStr]) -> Vec<io::Result<OwnedFd>> {
+            if names.is_empty() {
+                return Vec::new();
+            }
+            Self::with_ring(
+                |ring| ring.openat_batch(dir_fd, names),
+                || DirectCalls.open_directories(dir_fd, names),
+            )
+        }
+    }
+
+    /// A shared memory region mapped from the ring fd.
+    struct Mapping {
+        ptr: *mut c_void,
+        len: usize,
+    }
+
+    impl Mapping {
+        fn new(fd: &OwnedFd, len: usize, offset: u64) -> io::Result<Self> {
+            // SAFETY: a fresh shared mapping of the ring fd at a kernel-defined offset.
+            let ptr = unsafe {
+                mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    ProtFlags::READ | ProtFlags::WRITE,
+                    MapFlags::SHARED | MapFlags::POPULATE,
+                    fd,
+                    offset,
+                )?
+            };
+            Ok(Self { ptr, len })
+        }
+
+        /// Pointer `offset` bytes into the mapping.
+        fn at<T>(&self, offset: u32) -> *mut T {
+            // SAFETY: offsets come from `io_uring_params` and lie within the mapping.
+            unsafe { self.ptr.cast::<u8>().add(offset as usize).cast() }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: the region was mapped by `Mapping::new` and is unmapped once.
+            let _ = unsafe { munmap(self.ptr, self.len) };
+        }
+    }
+
+    /// A minimal single-issuer `io_uring`: submit a batch, wait for all of it.
+    struct Ring {
+        sq_head: *const AtomicU32,
+        sq_tail: *const AtomicU32,
+        sq_mask: u32,
+        sq_array: *mut u32,
+        sq_entries: u32,
+        sqes: *mut io_uring_sqe,
+        cq_head: *const AtomicU32,
+        cq_tail: *const AtomicU32,
+        cq_mask: u32,
+        cqes: *const io_uring_cqe,
+        // Field order matters: the mappings are unmapped before the fd closes.
+        _sq_map: Mapping,
+        _cq_map: Mapping,
+        _sqe_map: Mapping,
+        fd: OwnedFd,
+    }
+
+    impl Ring {
+        fn new(entries: u32) -> io::Result<Self> {
+            let mut params = io_uring_params::default();
+            let fd = io_uring_setup(entries, &mut params)?;
+
+            let sq_len = params.sq_off.array as usize
+                + params.sq_entries as usize * std::mem::size_of::<u32>();
+            let cq_len = params.cq_off.cqes as usize
+                + params.cq_entries as usize * std::mem::size_of::<io_uring_cqe>();
+            let sqes_len = params.sq_entries as usize * std::mem::size_of::<io_uring_sqe>();
+
+            let sq_ring = Mapping::new(&fd, sq_len, IORING_OFF_SQ_RING)?;
+            let cq_ring = Mapping::new(&fd, cq_len, IORING_OFF_CQ_RING)?;
+            let sqe_array = Mapping::new(&fd, sqes_len, IORING_OFF_SQES)?;
+
+            // SAFETY: the mask fields are plain u32s inside the mapped rings.
+            let (sq_mask, cq_mask) = unsafe {
+                (
+                    *sq_ring.at::<u32>(params.sq_off.ring_mask),
+                    *cq_ring.at::<u32>(params.cq_off.ring_mask),
+                )
+            };
+
+            Ok(Self {
+                sq_head: sq_ring.at(params.sq_off.head),
+                sq_tail: sq_ring.at(params.sq_off.tail),
+                sq_mask,
+                sq_array: sq_ring.at(params.sq_off.array),
+                sq_entries: params.sq_entries,
+                sqes: sqe_array.at(0),
+                cq_head: cq_ring.at(params.cq_off.head),
+                cq_tail: cq_ring.at(params.cq_off.tail),
+                cq_mask,
+                cqes: cq_ring.at(params.cq_off.cqes),
+                _sq_map: sq_ring,
+                _cq_map: cq_ring,
+                _sqe_map: sqe_array,
+                fd,
+            })
+        }
+
+        /// Whether every opcode in `ops` is reported as supported.
+        fn supports(&self, ops: &[IoringOp]) -> io::Result<bool> {
+            const PROBE_OPS: u32 = 256;
+
+            #[repr(C)]
+            struct ProbeBuffer {
+                header: io_uring_probe,
+                ops: [io_uring_probe_op; PROBE_OPS as usize],
+            }
+
+            // SAFETY: all-zero is a valid probe buffer (opcode 0 is `Nop`).
+            let mut buffer: ProbeBuffer = unsafe { std::mem::zeroed() };
+            // SAFETY: the kernel writes at most `PROBE_OPS` entries into `buffer`.
+            unsafe {
+                io_uring_register(
+                    self.fd.as_fd(),
+                    IoringRegisterOp::RegisterProbe,
+                    std::ptr::from_mut(&mut buffer).cast(),
+                    PROBE_OPS,
+                )?;
+            }
+
+            let known = usize::from(buffer.header.ops_len).min(buffer.ops.len());
+            Ok(ops.iter().all(|op| {
+                let index = *op as usize;
+                index < known && buffer.ops[index].flags.contains(IoringOpFlags::SUPPORTED)
+            }))
+        }
+
+        fn statx_batch(
+            &mut self,
+            dir_fd: &OwnedFd,
+            children: &[PendingChild],
+            basis: SizeBasis,
+        ) -> io::Result<Vec<io::Result<ChildStat>>> {
+            // SAFETY: `Statx` is a plain C struct; zero is a valid bit pattern.
+            let mut buffers: Vec<Statx> = (0..children.len())
+                .map(|_| unsafe { std::mem::zeroed() })
+                .collect();
+
+            let sqes: Vec<io_uring_sqe> = children
+                .iter()
+                .zip(buffers.iter_mut())
+                .map(|(child, buffer)| {
+                    let mut sqe = io_uring_sqe {
+                        opcode: IoringOp::Statx,
+                        fd: dir_fd.as_raw_fd(),
+                        ..Default::default()
+                    };
+                    sqe.addr_or_splice_off_in.addr =
+                        io_uring_ptr::from(child.name.as_ptr().cast_mut().cast::<c_void>());
+                    sqe.len.len = statx_mask(basis, !child.known_dir).bits();
+                    sqe.off_or_addr2.addr2 =
+                        io_uring_ptr::from(std::ptr::from_mut(buffer).cast::<c_void>());
+                    sqe.op_flags.statx_flags = AtFlags::SYMLINK_NOFOLLOW;
+                    sqe
+                })
+                .collect();
+
+            let mut results = vec![NOT_COMPLETED; sqes.len()];
+            if let Err(err) = self.submit_all(&sqes, &mut results) {
+                // Submitted requests may still write into the buffers.
+                std::mem::forget(buffers);
+                return Err(err);
+            }
+
+            Ok(results
+                .into_iter()
+                .zip(&buffers)
+                .map(|(res, buffer)| errno_result(res).map(|_| child_stat_from_statx(buffer)))
+                .collect())
+        }
+
+        fn openat_batch(
+            &mut self,
+            dir_fd: &OwnedFd,
+            names: &[&CStr],
+        ) -> io::Result<Vec<io::Result<OwnedFd>>> {
+            let sqes: Vec<io_uring_sqe> = names
+                .iter()
+                .map(|name| {
+                    let mut sqe = io_uring_sqe {
+                        opcode: IoringOp::Openat,
+                        fd: dir_fd.as_raw_fd(),
+                        ..Default::default()
+                    };
+                    sqe.addr_or_splice_off_in.addr =
+                        io_uring_ptr::from(name.as_ptr().cast_mut().cast::<c_void>());
+                    sqe.op_flags.open_flags = DIRECTORY_FLAGS;
+                    sqe
+                })
+                .collect();
+
+            let mut results = vec![NOT_COMPLETED; sqes.len()];
+            if let Err(err) = self.submit_all(&sqes, &mut results) {
+                // Close the directories opened before the failure.
+                for fd in results.into_iter().filter(|&res| res >= 0) {
+                    // SAFETY: a non-negative OPENAT result is a new fd we now own.
+                    drop(unsafe { OwnedFd::from_raw_fd(fd) });
+                }
+                return Err(err);
+            }
+
+            Ok(results
+                .into_iter()
+                .map(|res| {
+                    // SAFETY: a non-negative OPENAT result is a new fd we now own.
+                    errno_result(res).map(|fd| unsafe { OwnedFd::from_raw_fd(fd) })
+                })
+                .collect())
+        }
+
+        /// Submit `sqes` in ring-sized chunks, storing each raw `res` in
+        /// `results` in order.
+        ///
+        /// On error, `results` keeps the completions reaped so far; the other
+        /// slots are left as they were.
+        fn submit_all(&mut self, sqes: &[io_uring_sqe], results: &mut [i32]) -> io::Result<()> {
+            let chunk_len = self.sq_entries as usize;
+            for (chunk, results) in sqes.chunks(chunk_len).zip(results.chunks_mut(chunk_len)) {
+                self.submit_chunk(chunk, results)?;
+            }
+            Ok(())
+        }
+
+        fn submit_chunk(&mut self, sqes: &[io_uring_sqe], results: &mut [i32]) -> io::Result<()> {
+            // Chunks never exceed `sq_entries`, which fits in u32.
+            #[allow(clippy::cast_possible_truncation)]
+            let count = sqes.len() as u32;
+
+            // SAFETY: this thread is the only submitter; the queue is drained
+            // before returning, so all `count` slots are free.
+            unsafe {
+                let tail = (*self.sq_tail).load(Ordering::Relaxed);
+                debug_assert_eq!(tail, (*self.sq_head).load(Ordering::Acquire));
+                for (offset, sqe) in (0u32..).zip(sqes) {
+                    let index = tail.wrapping_add(offset) & self.sq_mask;
+                    let mut sqe = *sqe;
+                    sqe.user_data = io_uring_user_data::from_u64(u64::from(offset));
+                    self.sqes.add(index as usize).write(sqe);
+                    self.sq_array.add(index as usize).write(index);
+                }
+                (*self.sq_tail).store(tail.wrapping_add(count), Ordering::Release);
+            }
+
+            let mut to_submit = count;
+            let mut completed = 0u32;
+            while completed < count {
+                // SAFETY: the SQEs point at names and buffers that outlive the call.
+                let entered = unsafe {
+                    io_uring_enter(
+                        &self.fd,
+                        to_submit,
+                        1,
+                        IoringEnterFlags::GETEVENTS,
+                        std::ptr::null(),
+                        0,
+                    )
+                };
+                match entered {
+                    Ok(submitted) => to_submit = to_submit.saturating_sub(submitted),
+                    Err(rustix::io::Errno::INTR | rustix::io::Errno::AGAIN) => {}
+                    Err(err) => return Err(err.into()),
+                }
+                completed += self.reap(results);
+            }
+            Ok(())
+        }
+
+        /// Drain the completion queue into `results`, returning how many were read.
+        fn reap(&mut self, results: &mut [i32]) -> u32 {
+            // SAFETY: head/tail live in the mapped CQ ring; we are the only consumer.
+            unsafe {
+                let mut head = (*self.cq_head).load(Ordering::Relaxed);
+                let tail = (*self.cq_tail).load(Ordering::Acquire);
+                let mut reaped = 0;
+                while head != tail {
+                    let cqe = &*self.cqes.add((head & self.cq_mask) as usize);
+                    #[allow(clippy::cast_possible_truncation)]
+                    let slot = cqe.user_data.u64_() as usize;
+                    if let Some(result) = results.get_mut(slot) {
+                        *result = cqe.res;
+                    }
+                    head = head.wrapping_add(1);
+                    reaped += 1;
+                }
+                (*self.cq_head).store(head, Ordering::Release);
+                reaped
+            }
+        }
+    }
+
+    /// Result slot of a request that has not completed.
+    const NOT_COMPLETED: i32 = -rustix::io::Errno::CANCELED.raw_os_error();
+
+    /// Convert a completion result (`-errno` on failure) into an `io::Result`.
+    fn errno_result(res: i32) -> io::Result<i32> {
+        if res < 0 {
+            Err(io::Error::from_raw_os_error(-res))
+        } else {
+            Ok(res)
+        }
+    }
+}
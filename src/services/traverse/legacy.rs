@@ -3,18 +3,30 @@
 //! fallback strategy when optimized backends are unavailable or explicitly
 //! disabled.
 
-use super::StrategyKind;
+use super::budget::{self, FdBudget};
+use super::cancel::{CancellationToken, StopReason};
+use super::detect::{FilesystemInfo, FilesystemKind};
 use super::progress::ProgressThrottler;
-use crate::models::{DirectoryEntry, ErrorItem, ProgressSnapshot};
+use super::strategy::TraversalStrategy;
+use super::throttle::RateLimiter;
+use super::{StrategyKind, TraversalDispatcher, detect};
+use crate::io::snapshot::compare_paths;
+use crate::models::{
+    DirectoryEntry, EntryAttributes, EntryKind, ErrorItem, ProgressSnapshot, SkippedMount,
+};
+use crate::services::baseline::{BaselineDir, BaselinePlan};
+use crate::services::filter::ExcludeFilter;
+use crate::services::ignore::IgnoreStack;
+use crate::services::resume::ResumePlan;
 use crate::services::sink::{ScanSink, SinkFinish, memory::MemorySink};
-use crate::{HardlinkPolicy, ScanOptions, SizeBasis, SnapshotMeta};
-use std::collections::HashSet;
+use crate::{HardlinkPolicy, IgnoreFileMode, ScanOptions, SizeBasis, SnapshotMeta};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{Error as IoError, Result as IoResult};
-use std::path::Path;
-use std::sync::Mutex;
-use std::sync::atomic::{AtomicU8, AtomicU64, Ordering};
-use std::time::{Duration, Instant};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
 
 #[cfg(unix)]
 use std::os::unix::fs::MetadataExt;
@@ -31,8 +43,8 @@ use std::os::windows::io::AsRawHandle;
 use windows_sys::Win32::Foundation::HANDLE;
 #[cfg(windows)]
 use windows_sys::Win32::Storage::FileSystem::{
-    BY_HANDLE_FILE_INFORMATION, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE,
-    GetFileInformationByHandle,
+    BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS, FILE_SHARE_DELETE, FILE_SHARE_READ,
+    FILE_SHARE_WRITE, GetFileInformationByHandle,
 };
 
 /// File identifier for hardlink tracking (device, inode)
@@ -42,14 +54,70 @@ pub struct FileId {
     ino: u64,
 }
 
+impl FileId {
+    #[must_use]
+    pub fn new(dev: u64, ino: u64) -> Self {
+        Self { dev, ino }
+    }
+}
+
+/// A multiply-linked file met during the walk or copied from a reused snapshot.
+struct LinkedFile {
+    /// Links recorded with a non-zero size, and that size.
+    charged: Vec<(String, u64)>,
+    /// First of its links in path order, which the bytes are settled on.
+    owner: String,
+    size: u64,
+}
+
+impl LinkedFile {
+    fn new(owner: String) -> Self {
+        Self {
+            charged: Vec::new(),
+            owner,
+            size: 0,
+        }
+    }
+
+    /// Note a link at `path`, remembering it if it sorts before the owner.
+    fn link(&mut self, path: String) {
+        if compare_paths(&path, &self.owner).is_lt() {
+            self.owner = path;
+        }
+    }
+
+    fn charge(&mut self, path: String, size: u64) {
+        self.size = self.size.max(size);
+        self.charged.push((path, size));
+    }
+}
+
 /// Traversal context to track state during directory walk
 pub struct TraversalContext {
     root_device: Mutex<Option<u64>>,
+    root_filesystem: FilesystemKind,
     seen_inodes: Mutex<HashSet<FileId>>,
+    /// Multiply-linked files counted under [`HardlinkPolicy::Dedupe`].
+    linked_files: Mutex<HashMap<FileId, LinkedFile>>,
+    /// Directories walked so far and the path each was first reached by;
+    /// only tracked when symlinks are followed.
+    visited_dirs: Mutex<HashMap<FileId, PathBuf>>,
+    scan_root: Option<PathBuf>,
+    fd_budget: FdBudget,
+    rate_limiter: Option<RateLimiter>,
+    stop_reason: OnceLock<StopReason>,
+    truncated_dirs: Mutex<Vec<String>>,
+    resume: Option<ResumePlan>,
+    baseline: Option<BaselinePlan>,
+    dispatcher: Option<TraversalDispatcher>,
+    /// Filesystems of mount roots whose directory entry is not recorded yet.
+    mount_roots: Mutex<HashMap<String, FilesystemInfo>>,
+    skipped_mounts: Mutex<Vec<SkippedMount>>,
     sink: Mutex<Option<Box<dyn ScanSink>>>,
+    exclude_filter: ExcludeFilter,
     pub options: ScanOptions,
     pub max_depth: Option<u16>,
-    strategy: AtomicU8,
+    strategy: Mutex<StrategyKind>,
     processed_entries: AtomicU64,
     processed_bytes: AtomicU64,
     progress_events: Mutex<Vec<ProgressSnapshot>>,
@@ -58,22 +126,6 @@ pub struct TraversalContext {
     pub progress_interval: Duration,
 }
 
-fn encode_strategy(kind: StrategyKind) -> u8 {
-    match kind {
-        StrategyKind::Legacy => 0,
-        StrategyKind::WindowsOptimized => 1,
-        StrategyKind::PosixOptimized => 2,
-    }
-}
-
-fn decode_strategy(value: u8) -> StrategyKind {
-    match value {
-        1 => StrategyKind::WindowsOptimized,
-        2 => StrategyKind::PosixOptimized,
-        _ => StrategyKind::Legacy,
-    }
-}
-
 impl TraversalContext {
     #[must_use]
     pub fn new(options: ScanOptions, max_depth: Option<u16>) -> Self {
@@ -88,13 +140,33 @@ impl TraversalContext {
     ) -> Self {
         let interval = options.progress_interval;
         let trigger = options.progress_byte_trigger;
+        let exclude_filter = ExcludeFilter::new(&options.excludes);
+        let fd_budget = FdBudget::new(
+            options
+                .max_open_fds
+                .unwrap_or_else(budget::default_max_open_fds),
+        );
         Self {
             root_device: Mutex::new(None),
+            root_filesystem: FilesystemKind::Other,
             seen_inodes: Mutex::new(HashSet::new()),
+            linked_files: Mutex::new(HashMap::new()),
+            visited_dirs: Mutex::new(HashMap::new()),
+            scan_root: None,
+            fd_budget,
+            rate_limiter: options.max_ops_per_sec.map(RateLimiter::new),
+            stop_reason: OnceLock::new(),
+            truncated_dirs: Mutex::new(Vec::new()),
+            resume: None,
+            baseline: None,
+            dispatcher: None,
+            mount_roots: Mutex::new(HashMap::new()),
+            skipped_mounts: Mutex::new(Vec::new()),
             sink: Mutex::new(Some(sink)),
+            exclude_filter,
             options,
             max_depth,
-            strategy: AtomicU8::new(encode_strategy(StrategyKind::Legacy)),
+            strategy: Mutex::new(StrategyKind::Legacy),
             processed_entries: AtomicU64::new(0),
             processed_bytes: AtomicU64::new(0),
             progress_events: Mutex::new(Vec::new()),
@@ -124,12 +196,11 @@ impl TraversalContext {
 
     #[must_use]
     pub fn strategy(&self) -> StrategyKind {
-        decode_strategy(self.strategy.load(Ordering::Relaxed))
+        *self.strategy.lock().unwrap()
     }
 
     pub fn set_strategy(&self, strategy: StrategyKind) {
-        self.strategy
-            .store(encode_strategy(strategy), Ordering::Relaxed);
+        *self.strategy.lock().unwrap() = strategy;
     }
 
     pub fn update_progress_interval(&mut self, interval: Duration) {
@@ -157,14 +228,280 @@ impl TraversalContext {
         }
     }
 
+    /// Filesystem detected for the scan root.
+    #[must_use]
+    pub fn root_filesystem(&self) -> FilesystemKind {
+        self.root_filesystem
+    }
+
+    pub fn set_root_filesystem(&mut self, kind: FilesystemKind) {
+        self.root_filesystem = kind;
+    }
+
+    /// Budget for directory descriptors held open awaiting traversal.
+    pub(crate) fn fd_budget(&self) -> &FdBudget {
+        &self.fd_budget
+    }
+
+    /// Account for `ops` filesystem operations, blocking while over `max_ops_per_sec`.
+    ///
+    /// Backends call this before each stat, open or directory read.
+    pub fn throttle(&self, ops: u64) {
+        if let Some(limiter) = &self.rate_limiter {
+            limiter.acquire(ops);
+        }
+    }
+
+    /// Whether the scan should stop because it was cancelled or ran past its deadline.
+    ///
+    /// Backends check this between entries and stop descending once it returns
+    /// `true`; the first reason observed is latched for [`Self::stop_reason`].
+    pub fn should_stop(&self) -> bool {
+        if self.stop_reason.get().is_some() {
+            return true;
+        }
+
+        let reason = if self
+            .options
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            StopReason::Cancelled
+        } else if self
+            .options
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            StopReason::DeadlineExceeded
+        } else {
+            return false;
+        };
+
+        let _ = self.stop_reason.set(reason);
+        true
+    }
+
+    /// Why the scan stopped early, or `None` if it ran to completion.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason.get().copied()
+    }
+
+    /// Directories recorded after the scan began stopping, whose totals may be partial.
+    pub fn truncated_dirs(&self) -> Vec<String> {
+        self.truncated_dirs.lock().unwrap().clone()
+    }
+
+    /// Reuse completed directories of an interrupted scan instead of walking them.
+    pub fn set_resume_plan(&mut self, plan: ResumePlan) {
+        for &(device, inode) in plan.linked_inodes() {
+            self.should_count_inode(device, inode);
+        }
+        self.resume = Some(plan);
+    }
+
+    /// Size recorded for `path` by the resumed scan, if it completed there.
+    ///
+    /// Backends call this before descending into a subdirectory; the subtree's
+    /// entries are copied over by [`Self::copy_reused_entries`].
+    pub(crate) fn reuse_completed(&self, path: &Path) -> Option<u64> {
+        let size = self.resume.as_ref()?.reuse(&normalize_path(path))?;
+        log::debug!("Reusing completed directory: {}", path.display());
+        Some(size)
+    }
+
+    /// Compare directories against a previous snapshot and reuse unchanged ones.
+    pub fn set_baseline_plan(&mut self, plan: BaselinePlan) {
+        self.baseline = Some(plan);
+    }
+
+    /// The baseline record of the directory at `path` if it is unchanged.
+    ///
+    /// Backends list an unchanged directory's recorded subdirectories instead
+    /// of reading it, and take its file totals from the record; the file
+    /// entries are copied over by [`Self::copy_reused_entries`].
+    pub(crate) fn unchanged_directory(
+        &self,
+        path: &Path,
+        attrs: &EntryAttributes,
+    ) -> Option<&BaselineDir> {
+        let dir = self
+            .baseline
+            .as_ref()?
+            .unchanged(&normalize_path(path), attrs)?;
+        for &(device, inode) in dir.linked_inodes() {
+            self.should_count_inode(device, inode);
+        }
+        log::debug!("Unchanged since baseline: {}", path.display());
+        Some(dir)
+    }
+
+    /// Copy entries reused from a resumed or baseline snapshot into the sink.
+    pub fn copy_reused_entries(&self) -> IoResult<()> {
+        if let Some(plan) = &self.resume {
+            plan.copy_reused(self)?;
+        }
+        if let Some(plan) = &self.baseline {
+            plan.copy_unchanged(self)?;
+        }
+        Ok(())
+    }
+
+    /// Dispatcher consulted for subtrees on other filesystems.
+    pub(crate) fn set_dispatcher(&mut self, dispatcher: TraversalDispatcher) {
+        self.dispatcher = Some(dispatcher);
+    }
+
+    /// Whether a subdirectory on `device` inside a directory on `parent_device`
+    /// starts a mount that should be handed to [`Self::traverse_mount`]. Only
+    /// happens when crossing filesystems is allowed.
+    #[must_use]
+    pub fn enters_mount(&self, parent_device: Option<u64>, device: u64) -> bool {
+        self.options.cross_filesystem
+            && self.dispatcher.is_some()
+            && parent_device.is_some_and(|parent| parent != device)
+    }
+
+    /// Walk the mount rooted at `path`, found at `depth`, with the strategy the
+    /// dispatcher picks for its filesystem.
+    ///
+    /// The chosen backend starts afresh at the mount root, so ignore files
+    /// above it no longer apply inside the mount. Backends call this instead
+    /// of descending into a nested mount themselves.
+    pub fn traverse_mount(&self, path: &Path, depth: u16) -> IoResult<u64> {
+        let Some(dispatcher) = &self.dispatcher else {
+            return Ok(0);
+        };
+        let fs = detect::filesystem_info_for_path(path);
+        let strategy = dispatcher.select(&self.options, &fs);
+        log::debug!(
+            "Entering {} mount {}; walking it with '{}'",
+            fs.fs_type.as_deref().unwrap_or(fs.kind.as_str()),
+            path.display(),
+            strategy.kind()
+        );
+        self.note_mount_root(path, fs);
+        strategy.traverse(path, depth, self)
+    }
+
+    /// Annotate the directory entry of `path` with `fs` once it is recorded.
+    pub(crate) fn note_mount_root(&self, path: &Path, fs: FilesystemInfo) {
+        self.mount_roots
+            .lock()
+            .unwrap()
+            .insert(normalize_path(path), fs);
+    }
+
+    /// Note that the directory at `path`, on `device`, was left unscanned
+    /// because it belongs to another filesystem.
+    pub fn record_skipped_mount(&self, path: &Path, device: u64) {
+        let fs = detect::filesystem_info_for_path(path);
+        log::debug!("Not crossing into mount {}", path.display());
+        self.restore_skipped_mount(SkippedMount {
+            path: normalize_path(path),
+            device,
+            fs_type: fs.fs_type.unwrap_or_else(|| fs.kind.to_string()),
+        });
+    }
+
+    /// Record a skipped mount copied from a resumed or baseline snapshot.
+    pub(crate) fn restore_skipped_mount(&self, mount: SkippedMount) {
+        self.skipped_mounts.lock().unwrap().push(mount);
+    }
+
+    /// Mount points left unscanned so far, ordered by path.
+    pub fn skipped_mounts(&self) -> Vec<SkippedMount> {
+        let mut mounts = self.skipped_mounts.lock().unwrap().clone();
+        mounts.sort_by(|a, b| a.path.cmp(&b.path));
+        mounts
+    }
+
+    /// Whether an entry lives on a different filesystem than the scan root.
+    ///
+    /// Always `false` when crossing filesystems is allowed. Overlayfs reports
+    /// the device of the backing layer for non-directories, so only
+    /// directories are compared there.
+    #[must_use]
+    pub fn crosses_filesystem(&self, metadata: &fs::Metadata) -> bool {
+        self.crosses_device(get_device_id(metadata), metadata.is_dir())
+    }
+
+    /// Same as [`Self::crosses_filesystem`] for a raw device number.
+    #[must_use]
+    pub fn crosses_device(&self, device: u64, is_dir: bool) -> bool {
+        if self.options.cross_filesystem {
+            return false;
+        }
+
+        if self.root_filesystem == FilesystemKind::Overlay && !is_dir {
+            return false;
+        }
+
+        self.root_device()
+            .is_some_and(|root_dev| device != root_dev)
+    }
+
+    /// Check whether an entry matches one of the configured exclusion patterns.
+    /// Excluded entries are neither recorded nor counted.
+    #[must_use]
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.exclude_filter.is_empty() {
+            return false;
+        }
+
+        let excluded = self
+            .exclude_filter
+            .is_excluded(&normalize_path(path), is_dir);
+        if excluded {
+            log::debug!("Excluded: {}", path.display());
+        }
+        excluded
+    }
+
+    /// Return the ignore rules in effect inside `dir`, given the rules of its parent.
+    ///
+    /// Ignore files are only read when an `IgnoreFileMode` other than `Disabled`
+    /// is set. Backends start from [`IgnoreStack::new`] at their root.
+    #[must_use]
+    pub fn enter_ignore_scope(&self, parent: &IgnoreStack, dir: &Path) -> IgnoreStack {
+        if self.options.ignore_files == IgnoreFileMode::Disabled {
+            parent.clone()
+        } else {
+            parent.descend(dir, &normalize_path(dir))
+        }
+    }
+
+    /// Check whether an entry is matched by the ignore files in effect.
+    #[must_use]
+    pub fn is_ignored(&self, ignore: &IgnoreStack, path: &Path, is_dir: bool) -> bool {
+        if self.options.ignore_files == IgnoreFileMode::Disabled {
+            return false;
+        }
+
+        let ignored = ignore.is_ignored(&normalize_path(path), is_dir);
+        if ignored {
+            log::debug!("Ignored: {}", path.display());
+        }
+        ignored
+    }
+
+    /// Whether ignored directories should be recorded as collapsed entries
+    /// carrying their total size instead of being skipped.
+    #[must_use]
+    pub fn collapse_ignored(&self) -> bool {
+        self.options.ignore_files == IgnoreFileMode::Collapse
+    }
+
     /// Check if we should count this file (based on hardlink policy)
-    pub(crate) fn should_count_file(&self, path: &Path, metadata: &fs::Metadata) -> bool {
+    pub fn should_count_file(&self, path: &Path, metadata: &fs::Metadata) -> bool {
         match self.options.hardlink_policy {
             HardlinkPolicy::Count => true,
             HardlinkPolicy::Dedupe => {
                 if let Some(file_id) = file_id_from_metadata(path, metadata) {
-                    let mut seen = self.seen_inodes.lock().unwrap();
-                    seen.insert(file_id)
+                    let (_, _, nlink) = file_identity(metadata);
+                    self.should_count_link(path, file_id, nlink.unwrap_or(1), || {
+                        self.get_size(path, metadata)
+                    })
                 } else {
                     true
                 }
@@ -172,9 +509,183 @@ impl TraversalContext {
         }
     }
 
+    /// Whether the file `id` with `nlink` links, reached at `path`, should be
+    /// counted; only its first link seen is.
+    ///
+    /// Which link a worker reaches first varies between runs, so for
+    /// multiply-linked files the first link in path order is remembered as
+    /// well and the bytes are moved there once the walk is over. Backends
+    /// without `fs::Metadata` call this with the identity they read; `size`
+    /// is only evaluated for the first link.
+    pub fn should_count_link(
+        &self,
+        path: &Path,
+        id: FileId,
+        nlink: u64,
+        size: impl FnOnce() -> u64,
+    ) -> bool {
+        if self.options.hardlink_policy == HardlinkPolicy::Count {
+            return true;
+        }
+        if nlink <= 1 {
+            return self.seen_inodes.lock().unwrap().insert(id);
+        }
+
+        let path = normalize_path(path);
+        let mut linked = self.linked_files.lock().unwrap();
+        let first = self.seen_inodes.lock().unwrap().insert(id);
+        let file = linked
+            .entry(id)
+            .or_insert_with(|| LinkedFile::new(path.clone()));
+        if first {
+            file.charge(path.clone(), size());
+        }
+        file.link(path);
+        first
+    }
+
+    /// Note a multiply-linked file copied from a resumed or baseline snapshot,
+    /// so that [`Self::settle_hardlinks`] weighs its links with those walked.
+    fn note_reused_link(&self, entry: &DirectoryEntry) {
+        if self.options.hardlink_policy == HardlinkPolicy::Count
+            || entry.kind != EntryKind::File
+            || entry.nlink.is_none_or(|links| links <= 1)
+        {
+            return;
+        }
+        let (Some(dev), Some(ino)) = (entry.device, entry.inode) else {
+            return;
+        };
+        let mut linked = self.linked_files.lock().unwrap();
+        let file = linked
+            .entry(FileId { dev, ino })
+            .or_insert_with(|| LinkedFile::new(entry.path.clone()));
+        if entry.size_bytes > 0 {
+            file.charge(entry.path.clone(), entry.size_bytes);
+        }
+        file.link(entry.path.clone());
+    }
+
+    /// Move the bytes of each multiply-linked file from the links that were
+    /// charged to the first link in path order, adjusting the recorded sizes
+    /// of those links and of their ancestors.
+    ///
+    /// Runs after reused entries are copied, so links in reused subtrees are
+    /// settled too, and a file charged both by the walk and by a reused
+    /// snapshot ends up counted once.
+    pub(crate) fn settle_hardlinks(&self) -> IoResult<()> {
+        let linked = std::mem::take(&mut *self.linked_files.lock().unwrap());
+        let mut deltas: HashMap<String, i64> = HashMap::new();
+        let mut shift = |path: &str, delta: i64| {
+            for ancestor in Path::new(path).ancestors() {
+                *deltas.entry(normalize_path(ancestor)).or_default() += delta;
+            }
+        };
+        for file in linked.into_values().filter(|file| file.size > 0) {
+            if let [(path, _)] = file.charged.as_slice()
+                && *path == file.owner
+            {
+                continue;
+            }
+            for (path, size) in &file.charged {
+                shift(path, -i64::try_from(*size).unwrap_or(i64::MAX));
+            }
+            shift(&file.owner, i64::try_from(file.size).unwrap_or(i64::MAX));
+        }
+        deltas.retain(|_, delta| *delta != 0);
+        if deltas.is_empty() {
+            return Ok(());
+        }
+
+        log::debug!("Settling hardlinked bytes on {} paths", deltas.len());
+        self.with_sink_mut(|sink| sink.adjust_sizes(&deltas))
+    }
+
+    /// Mark a raw device and inode pair as counted, returning whether it was
+    /// new; used for files whose bytes an earlier snapshot already attributed.
+    pub(crate) fn should_count_inode(&self, device: u64, inode: u64) -> bool {
+        match self.options.hardlink_policy {
+            HardlinkPolicy::Count => true,
+            HardlinkPolicy::Dedupe => self.seen_inodes.lock().unwrap().insert(FileId {
+                dev: device,
+                ino: inode,
+            }),
+        }
+    }
+
+    /// Remember the canonical scan root so symlinks into the scanned tree are
+    /// recognised; only needed when following symlinks.
+    pub(crate) fn set_scan_root(&mut self, root: &Path) {
+        if self.options.follow_symlinks {
+            self.scan_root = fs::canonicalize(root).ok();
+        }
+    }
+
+    /// Whether the directory `id` reached at `path` should be walked.
+    ///
+    /// Only consulted when following symlinks. Every directory is walked at
+    /// most once, and a symlink is only followed to a directory outside the
+    /// scanned tree: directories inside the tree are counted at their real
+    /// path. Links pointing back at one of their own ancestors, or at an
+    /// ancestor of the scan root, are recorded as errors.
+    pub(crate) fn enter_directory(
+        &self,
+        path: &Path,
+        id: Option<FileId>,
+        via_symlink: bool,
+    ) -> IoResult<bool> {
+        let Some(id) = id else {
+            return Ok(true);
+        };
+        let target = if via_symlink {
+            fs::canonicalize(path).ok()
+        } else {
+            None
+        };
+        let cycle_to = {
+            let mut visited = self.visited_dirs.lock().unwrap();
+            if let Some(first) = visited.get(&id) {
+                if !(via_symlink && path.starts_with(first)) {
+                    log::debug!(
+                        "Not walking {}: already counted at {}",
+                        path.display(),
+                        first.display()
+                    );
+                    return Ok(false);
+                }
+                first.clone()
+            } else if let (Some(root), Some(target)) = (&self.scan_root, &target) {
+                if root.starts_with(target) {
+                    root.clone()
+                } else if target.starts_with(root) {
+                    log::debug!(
+                        "Not following {}: its target is inside the scanned tree",
+                        path.display()
+                    );
+                    return Ok(false);
+                } else {
+                    visited.insert(id, path.to_path_buf());
+                    return Ok(true);
+                }
+            } else {
+                visited.insert(id, path.to_path_buf());
+                return Ok(true);
+            }
+        };
+
+        let item = ErrorItem {
+            path: path.to_string_lossy().to_string(),
+            code: "ELOOP".to_string(),
+            message: format!("symlink cycle back to {}", cycle_to.display()),
+        };
+        self.with_sink_mut(|sink| sink.record_error(item))?;
+        Ok(false)
+    }
+
     /// Get size based on the configured basis
     #[allow(unused_variables)]
-    pub(crate) fn get_size(&self, path: &Path, metadata: &fs::Metadata) -> u64 {
+    #[must_use]
+    pub fn get_size(&self, path: &Path, metadata: &fs::Metadata) -> u64 {
         use crate::services::size;
 
         match self.options.basis {
@@ -212,8 +723,18 @@ impl TraversalContext {
         }
     }
 
-    /// Record an error encountered during traversal
-    pub(crate) fn record_error(&self, path: &Path, error: &std::io::Error) -> IoResult<()> {
+    /// Tell the configured [`DirectoryNotifier`](crate::DirectoryNotifier)
+    /// that the directory at `path` is about to be listed; backends call this
+    /// before reading each directory they walk.
+    pub fn notify_directory(&self, path: &Path) {
+        if let Some(notifier) = &self.options.directory_notifier {
+            notifier(path);
+        }
+    }
+
+    /// Record an error encountered during traversal against `path`; the scan
+    /// carries on and the error is listed with the results.
+    pub fn record_error(&self, path: &Path, error: &std::io::Error) -> IoResult<()> {
         let code = match error.kind() {
             std::io::ErrorKind::NotFound => "ENOENT",
             std::io::ErrorKind::PermissionDenied => "EACCES",
@@ -229,6 +750,22 @@ impl TraversalContext {
         self.with_sink_mut(|sink| sink.record_error(item))
     }
 
+    /// Record why the scan stopped early as an error against the scan root.
+    pub(crate) fn record_interruption(&self, root: &Path, reason: StopReason) -> IoResult<()> {
+        let (code, message) = match reason {
+            StopReason::Cancelled => ("INTERRUPTED", "scan cancelled before completion"),
+            StopReason::DeadlineExceeded => ("DEADLINE", "scan deadline passed before completion"),
+        };
+
+        let item = ErrorItem {
+            path: root.to_string_lossy().to_string(),
+            code: code.to_string(),
+            message: message.to_string(),
+        };
+
+        self.with_sink_mut(|sink| sink.record_error(item))
+    }
+
     pub fn set_sink_metadata(&self, meta: &SnapshotMeta) -> IoResult<()> {
         self.with_sink_mut(|sink| sink.set_metadata(meta))
     }
@@ -313,32 +850,56 @@ impl TraversalContext {
         }
     }
 
-    pub fn insert_entry(&self, entry: DirectoryEntry) -> IoResult<()> {
+    pub fn insert_entry(&self, mut entry: DirectoryEntry) -> IoResult<()> {
+        if entry.kind == EntryKind::Directory {
+            // Directories finish in post-order, so any recorded once stopping
+            // began may be missing children; everything recorded earlier is
+            // complete.
+            if self.stop_reason.get().is_some() {
+                self.truncated_dirs.lock().unwrap().push(entry.path.clone());
+            }
+            if let Some(fs) = self.mount_roots.lock().unwrap().remove(&entry.path) {
+                entry.fs_type = Some(fs.fs_type.unwrap_or_else(|| fs.kind.to_string()));
+                entry.mount_source = fs.source;
+            }
+        }
         self.with_sink_mut(|sink| sink.record_entry(entry))
     }
 
-    pub fn into_parts(self) -> IoResult<(SinkFinish, Vec<ProgressSnapshot>, StrategyKind)> {
-        let strategy = decode_strategy(self.strategy.load(Ordering::Relaxed));
+    /// Record an entry copied from a resumed snapshot.
+    pub(crate) fn restore_entry(&self, entry: DirectoryEntry) -> IoResult<()> {
+        self.note_reused_link(&entry);
+        self.with_sink_mut(|sink| sink.record_entry(entry))
+    }
 
-        let sink_finish: SinkFinish = self
-            .sink
-            .into_inner()
-            .unwrap_or_else(std::sync::PoisonError::into_inner)
-            .ok_or_else(|| IoError::other("scan sink missing"))?
-            .finish()?;
+    /// Record an error copied from a resumed snapshot.
+    pub(crate) fn restore_error(&self, error: ErrorItem) -> IoResult<()> {
+        self.with_sink_mut(|sink| sink.record_error(error))
+    }
+
+    pub fn into_parts(self) -> IoResult<(SinkFinish, Vec<ProgressSnapshot>, StrategyKind)> {
+        let strategy = self.strategy();
 
         let progress = self
             .progress_events
             .into_inner()
             .unwrap_or_else(std::sync::PoisonError::into_inner);
 
+        let mut sink = self
+            .sink
+            .into_inner()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .ok_or_else(|| IoError::other("scan sink missing"))?;
+        sink.set_progress(&progress)?;
+        let sink_finish: SinkFinish = sink.finish()?;
+
         Ok((sink_finish, progress, strategy))
     }
 }
 
 #[cfg(unix)]
 #[allow(clippy::unnecessary_wraps)]
-fn file_id_from_metadata(_path: &Path, metadata: &fs::Metadata) -> Option<FileId> {
+pub(crate) fn file_id_from_metadata(_path: &Path, metadata: &fs::Metadata) -> Option<FileId> {
     Some(FileId {
         dev: metadata.dev(),
         ino: metadata.ino(),
@@ -346,12 +907,14 @@ fn file_id_from_metadata(_path: &Path, metadata: &fs::Metadata) -> Option<FileId
 }
 
 #[cfg(windows)]
-fn file_id_from_metadata(path: &Path, _metadata: &fs::Metadata) -> Option<FileId> {
+pub(crate) fn file_id_from_metadata(path: &Path, _metadata: &fs::Metadata) -> Option<FileId> {
     use std::io;
 
+    // Directories can only be opened with backup semantics.
     let file = match OpenOptions::new()
         .read(true)
         .share_mode(FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
         .open(path)
     {
         Ok(f) => f,
@@ -385,7 +948,7 @@ fn file_id_from_metadata(path: &Path, _metadata: &fs::Metadata) -> Option<FileId
 }
 
 #[cfg(not(any(unix, windows)))]
-fn file_id_from_metadata(_path: &Path, _metadata: &fs::Metadata) -> Option<FileId> {
+pub(crate) fn file_id_from_metadata(_path: &Path, _metadata: &fs::Metadata) -> Option<FileId> {
     None
 }
 
@@ -416,6 +979,55 @@ pub(crate) fn normalize_path(path: &Path) -> String {
     }
 }
 
+/// Capture the kind, timestamps and identity recorded for an entry.
+pub(crate) fn entry_attributes(metadata: &fs::Metadata) -> EntryAttributes {
+    let file_type = metadata.file_type();
+    let kind = if file_type.is_symlink() {
+        EntryKind::Symlink
+    } else if file_type.is_dir() {
+        EntryKind::Directory
+    } else if file_type.is_file() {
+        EntryKind::File
+    } else {
+        EntryKind::Other
+    };
+
+    let (inode, device, nlink) = file_identity(metadata);
+
+    EntryAttributes {
+        kind,
+        mtime_ns: metadata.modified().ok().and_then(unix_nanos),
+        atime_ns: metadata.accessed().ok().and_then(unix_nanos),
+        inode,
+        device,
+        nlink,
+    }
+}
+
+/// Convert a timestamp into signed nanoseconds relative to the Unix epoch.
+pub(crate) fn unix_nanos(time: SystemTime) -> Option<i64> {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(after) => i64::try_from(after.as_nanos()).ok(),
+        Err(before) => i64::try_from(before.duration().as_nanos()).ok().map(|n| -n),
+    }
+}
+
+/// Inode, device and link count reported by the platform.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)]
+fn file_identity(metadata: &fs::Metadata) -> (Option<u64>, Option<u64>, Option<u64>) {
+    (
+        Some(metadata.ino()),
+        Some(metadata.dev()),
+        Some(metadata.nlink()),
+    )
+}
+
+#[cfg(not(unix))]
+fn file_identity(_metadata: &fs::Metadata) -> (Option<u64>, Option<u64>, Option<u64>) {
+    (None, None, None)
+}
+
 /// Get filesystem device ID for boundary detection
 #[cfg(unix)]
 pub(crate) fn get_device_id(metadata: &fs::Metadata) -> u64 {
@@ -427,13 +1039,34 @@ pub(crate) fn get_device_id(_metadata: &fs::Metadata) -> u64 {
     0
 }
 
+/// Sequential `std::fs` walker, the fallback for every other backend.
+#[derive(Debug, Default)]
+pub struct LegacyTraversal;
+
+impl TraversalStrategy for LegacyTraversal {
+    fn kind(&self) -> StrategyKind {
+        StrategyKind::Legacy
+    }
+
+    /// Always eligible, so it catches every filesystem no other strategy takes.
+    fn is_eligible(&self, _opts: &ScanOptions, _fs: &FilesystemInfo) -> bool {
+        true
+    }
+
+    fn traverse(&self, root: &Path, depth: u16, context: &TraversalContext) -> IoResult<u64> {
+        traverse_from(root, depth, context)
+    }
+}
+
 /// Traverse a directory tree and collect entries using the legacy algorithm.
 pub fn traverse_directory<P: AsRef<Path>>(
     root: P,
     context: &TraversalContext,
 ) -> std::io::Result<u64> {
-    let root = root.as_ref();
+    traverse_from(root.as_ref(), 0, context)
+}
 
+fn traverse_from(root: &Path, depth: u16, context: &TraversalContext) -> IoResult<u64> {
     let root_metadata = match fs::symlink_metadata(root) {
         Ok(m) => m,
         Err(e) => {
@@ -446,7 +1079,156 @@ pub fn traverse_directory<P: AsRef<Path>>(
         context.set_root_device_if_absent(get_device_id(&root_metadata));
     }
 
-    traverse_recursive(root, 0, context)
+    traverse_recursive(root, depth, context, &IgnoreStack::new())
+}
+
+/// Record an ignored directory as a single collapsed entry.
+///
+/// The subtree is sized without emitting entries for its contents so that the
+/// space it occupies stays visible in the snapshot. Returns the subtree size.
+pub(crate) fn collapse_ignored_directory(
+    path: &Path,
+    attrs: EntryAttributes,
+    depth: u16,
+    context: &TraversalContext,
+) -> IoResult<u64> {
+    let mut file_count = 0u32;
+    let mut dir_count = 0u32;
+    let mut total_size = 0u64;
+
+    context.throttle(1);
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) => {
+            context.record_error(path, &e)?;
+            return Ok(0);
+        }
+    };
+
+    for entry in entries {
+        if context.should_stop() {
+            break;
+        }
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                context.record_error(path, &e)?;
+                continue;
+            }
+        };
+
+        let entry_path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if context.is_excluded(&entry_path, file_type.is_dir()) {
+            continue;
+        }
+
+        total_size = total_size.saturating_add(subtree_size(&entry_path, context)?);
+        if file_type.is_dir() {
+            dir_count = dir_count.saturating_add(1);
+        } else if file_type.is_file() {
+            file_count = file_count.saturating_add(1);
+        }
+    }
+
+    if context.max_depth.is_none_or(|max| depth <= max) {
+        let entry = DirectoryEntry {
+            path: normalize_path(path),
+            parent_path: path.parent().map(normalize_path),
+            depth,
+            size_bytes: total_size,
+            file_count,
+            dir_count,
+            ..DirectoryEntry::default()
+        }
+        .with_attributes(attrs);
+        log::debug!(
+            "Collapsed ignored directory: {} (size: {total_size})",
+            entry.path
+        );
+        context.insert_entry(entry)?;
+    }
+    context.register_directory_progress();
+
+    Ok(total_size)
+}
+
+/// Sum the size of a subtree without recording entries.
+fn subtree_size(path: &Path, context: &TraversalContext) -> IoResult<u64> {
+    context.throttle(1);
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(e) => {
+            context.record_error(path, &e)?;
+            return Ok(0);
+        }
+    };
+
+    if metadata.is_symlink() {
+        return Ok(0);
+    }
+
+    if context.crosses_filesystem(&metadata) {
+        if metadata.is_dir() {
+            context.record_skipped_mount(path, get_device_id(&metadata));
+        }
+        return Ok(0);
+    }
+
+    if metadata.is_file() {
+        let size = if context.should_count_file(path, &metadata) {
+            context.get_size(path, &metadata)
+        } else {
+            0
+        };
+        context.register_file_progress(size);
+        return Ok(size);
+    }
+
+    if !metadata.is_dir() {
+        return Ok(0);
+    }
+
+    let mut total = 0u64;
+    context.throttle(1);
+    match fs::read_dir(path) {
+        Ok(entries) => {
+            for entry in entries {
+                if context.should_stop() {
+                    break;
+                }
+                match entry {
+                    Ok(entry) => {
+                        let entry_path = entry.path();
+                        let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                        if !context.is_excluded(&entry_path, is_dir) {
+                            total = total.saturating_add(subtree_size(&entry_path, context)?);
+                        }
+                    }
+                    Err(e) => context.record_error(path, &e)?,
+                }
+            }
+        }
+        Err(e) => context.record_error(path, &e)?,
+    }
+
+    Ok(total)
+}
+
+/// `metadata` of the entry at `path`, or of its target when it is a symlink
+/// and symlinks are followed.
+pub(crate) fn followed_metadata(
+    path: &Path,
+    metadata: fs::Metadata,
+    context: &TraversalContext,
+) -> IoResult<fs::Metadata> {
+    if metadata.is_symlink() && context.options.follow_symlinks {
+        fs::metadata(path)
+    } else {
+        Ok(metadata)
+    }
 }
 
 #[allow(clippy::too_many_lines)]
@@ -454,6 +1236,7 @@ fn traverse_recursive(
     current: &Path,
     depth: u16,
     context: &TraversalContext,
+    ignore: &IgnoreStack,
 ) -> std::io::Result<u64> {
     if let Some(max_depth) = context.max_depth
         && depth > max_depth
@@ -461,6 +1244,7 @@ fn traverse_recursive(
         return Ok(0);
     }
 
+    context.throttle(1);
     let metadata = match fs::symlink_metadata(current) {
         Ok(m) => m,
         Err(e) => {
@@ -472,14 +1256,20 @@ fn traverse_recursive(
     if metadata.is_symlink() && !context.options.follow_symlinks {
         return Ok(0);
     }
-
-    if !context.options.cross_filesystem
-        && let Some(root_dev) = context.root_device()
-    {
-        let current_dev = get_device_id(&metadata);
-        if current_dev != root_dev {
+    let via_symlink = metadata.is_symlink();
+    let metadata = match followed_metadata(current, metadata, context) {
+        Ok(m) => m,
+        Err(e) => {
+            context.record_error(current, &e)?;
             return Ok(0);
         }
+    };
+
+    if context.crosses_filesystem(&metadata) {
+        if metadata.is_dir() && !via_symlink {
+            context.record_skipped_mount(current, get_device_id(&metadata));
+        }
+        return Ok(0);
     }
 
     if metadata.is_file() {
@@ -490,29 +1280,69 @@ fn traverse_recursive(
         };
         Ok(size)
     } else if metadata.is_dir() {
+        if context.options.follow_symlinks
+            && !context.enter_directory(
+                current,
+                file_id_from_metadata(current, &metadata),
+                via_symlink,
+            )?
+        {
+            return Ok(0);
+        }
+        context.notify_directory(current);
+
         let mut total_size = 0u64;
         let mut file_count = 0u32;
         let mut dir_count = 0u32;
 
-        let entries = match fs::read_dir(current) {
-            Ok(e) => e,
-            Err(e) => {
-                context.record_error(current, &e)?;
-                return Ok(0);
+        // An unchanged directory is not listed: only the subdirectories the
+        // baseline recorded are visited, and its files come from the baseline.
+        // Listed children keep their `DirEntry`, whose metadata is free on
+        // Windows.
+        let children: Vec<std::io::Result<(PathBuf, Option<fs::DirEntry>)>> = if let Some(listing) =
+            context.unchanged_directory(current, &entry_attributes(&metadata))
+        {
+            total_size = listing.file_bytes();
+            file_count = listing.file_count;
+            listing
+                .subdirs
+                .iter()
+                .map(|name| Ok((current.join(name), None)))
+                .collect()
+        } else {
+            context.throttle(1);
+            match fs::read_dir(current) {
+                Ok(entries) => entries
+                    .map(|entry| entry.map(|entry| (entry.path(), Some(entry))))
+                    .collect(),
+                Err(e) => {
+                    context.record_error(current, &e)?;
+                    return Ok(0);
+                }
             }
         };
+        let ignore = context.enter_ignore_scope(ignore, current);
 
-        for entry in entries {
-            let entry = match entry {
-                Ok(e) => e,
+        for child in children {
+            if context.should_stop() {
+                break;
+            }
+            let (entry_path, entry) = match child {
+                Ok(child) => child,
                 Err(e) => {
                     context.record_error(current, &e)?;
                     continue;
                 }
             };
 
-            let entry_path = entry.path();
-            let entry_metadata = match entry.metadata() {
+            context.throttle(1);
+            let entry_metadata = match entry
+                .map_or_else(
+                    || fs::symlink_metadata(&entry_path),
+                    |entry| entry.metadata(),
+                )
+                .and_then(|m| followed_metadata(&entry_path, m, context))
+            {
                 Ok(m) => m,
                 Err(e) => {
                     context.record_error(&entry_path, &e)?;
@@ -520,6 +1350,23 @@ fn traverse_recursive(
                 }
             };
 
+            if context.is_excluded(&entry_path, entry_metadata.is_dir()) {
+                continue;
+            }
+
+            if context.is_ignored(&ignore, &entry_path, entry_metadata.is_dir()) {
+                if entry_metadata.is_dir() && context.collapse_ignored() {
+                    total_size += collapse_ignored_directory(
+                        &entry_path,
+                        entry_attributes(&entry_metadata),
+                        depth + 1,
+                        context,
+                    )?;
+                    dir_count += 1;
+                }
+                continue;
+            }
+
             if entry_metadata.is_file() {
                 let file_size = if context.should_count_file(&entry_path, &entry_metadata) {
                     context.get_size(&entry_path, &entry_metadata)
@@ -542,12 +1389,23 @@ fn traverse_recursive(
                         size_bytes: file_size,
                         file_count: 0,
                         dir_count: 0,
-                    };
+                        ..DirectoryEntry::default()
+                    }
+                    .with_attributes(entry_attributes(&entry_metadata));
                     log::debug!("File entry: {} (size: {})", file_entry.path, file_size);
                     context.insert_entry(file_entry)?;
                 }
             } else if entry_metadata.is_dir() {
-                let subdir_size = traverse_recursive(&entry_path, depth + 1, context)?;
+                let subdir_size = if let Some(size) = context.reuse_completed(&entry_path) {
+                    size
+                } else if context.enters_mount(
+                    Some(get_device_id(&metadata)),
+                    get_device_id(&entry_metadata),
+                ) {
+                    context.traverse_mount(&entry_path, depth + 1)?
+                } else {
+                    traverse_recursive(&entry_path, depth + 1, context, &ignore)?
+                };
                 total_size += subdir_size;
                 dir_count += 1;
             }
@@ -563,7 +1421,9 @@ fn traverse_recursive(
             size_bytes: total_size,
             file_count,
             dir_count,
-        };
+            ..DirectoryEntry::default()
+        }
+        .with_attributes(entry_attributes(&metadata));
 
         log::debug!(
             "Directory entry: {normalized_path} (size: {total_size}, files: {file_count}, dirs: {dir_count}, depth: {depth})"
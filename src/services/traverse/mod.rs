@@ -2,26 +2,34 @@
 //!
 //! This module orchestrates filesystem traversal strategies across supported
 //! platforms. The dispatcher selects the appropriate backend (legacy, Windows
-//! optimized, POSIX optimized, etc.) based on runtime context and user
-//! overrides while enforcing shared invariants:
+//! optimized, POSIX optimized, `io_uring`, or one registered by the caller)
+//! from a [`StrategyRegistry`] based on runtime context and user overrides
+//! while enforcing shared invariants:
 //!
 //! - Directory traversal MUST respect `ScanOptions` boundaries (max-depth,
 //!   filesystem crossing, hardlink policy).
 //! - Aggregated results MUST match legacy traversal within 1% or 10 MB.
 //! - Progress emitters MUST remain monotonic and never regress when reported.
 
+pub mod budget;
+pub mod cancel;
 pub mod detect;
+pub mod io_uring;
 pub mod legacy;
+pub mod mounts;
 pub mod posix;
 pub mod progress;
 pub mod strategy;
+pub mod throttle;
 pub mod windows;
 
 pub use legacy::TraversalContext;
 
 use crate::ScanOptions;
+use detect::FilesystemInfo;
 use std::path::Path;
-use strategy::TraversalStrategy;
+use std::sync::Arc;
+use strategy::{StrategyRegistry, TraversalStrategy};
 
 /// Enumeration of available traversal strategies.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -33,6 +41,10 @@ pub enum StrategyKind {
     WindowsOptimized,
     /// POSIX optimized traversal leveraging `openat`/`getdents64`.
     PosixOptimized,
+    /// Linux traversal batching `statx`/`openat` through `io_uring`.
+    IoUring,
+    /// A backend registered by a library user, identified by name.
+    Custom(&'static str),
 }
 
 impl StrategyKind {
@@ -42,6 +54,18 @@ impl StrategyKind {
             StrategyKind::Legacy => "legacy",
             StrategyKind::WindowsOptimized => "windows",
             StrategyKind::PosixOptimized => "posix",
+            StrategyKind::IoUring => "io_uring",
+            StrategyKind::Custom(name) => name,
+        }
+    }
+
+    /// Backend an explicit request steps down to when this one cannot run.
+    #[must_use]
+    pub fn fallback(&self) -> Option<Self> {
+        match self {
+            StrategyKind::IoUring => Some(StrategyKind::PosixOptimized),
+            StrategyKind::Legacy => None,
+            _ => Some(StrategyKind::Legacy),
         }
     }
 
@@ -51,6 +75,7 @@ impl StrategyKind {
             "legacy" => Some(StrategyKind::Legacy),
             "windows" | "ntfs" => Some(StrategyKind::WindowsOptimized),
             "posix" | "unix" => Some(StrategyKind::PosixOptimized),
+            "io_uring" | "iouring" | "uring" => Some(StrategyKind::IoUring),
             _ => None,
         }
     }
@@ -71,11 +96,16 @@ impl std::str::FromStr for StrategyKind {
 }
 
 /// Traversal dispatcher responsible for selecting and executing the appropriate strategy.
-#[derive(Debug)]
+///
+/// When the scan may cross filesystems, every subtree on another device is
+/// handed back to the dispatcher, which detects its filesystem and picks the
+/// strategy for it.
+#[derive(Debug, Clone)]
 pub struct TraversalDispatcher {
     strategy: StrategyKind,
     explicit_override: bool,
     progress_interval: std::time::Duration,
+    registry: Arc<StrategyRegistry>,
 }
 
 impl Default for TraversalDispatcher {
@@ -84,6 +114,7 @@ impl Default for TraversalDispatcher {
             strategy: StrategyKind::Legacy,
             explicit_override: false,
             progress_interval: std::time::Duration::from_secs(2),
+            registry: Arc::new(StrategyRegistry::with_defaults()),
         }
     }
 }
@@ -95,22 +126,21 @@ impl TraversalDispatcher {
         Self {
             strategy,
             explicit_override,
-            progress_interval: std::time::Duration::from_secs(2),
+            ..Self::default()
         }
     }
 
     /// Derive the best-fit strategy for the current platform and options.
     #[must_use]
     pub fn for_platform(opts: &ScanOptions) -> Self {
-        if let Some(override_kind) = opts.strategy_override {
-            let mut dispatcher = Self::with_strategy(override_kind, true);
-            dispatcher.progress_interval = opts.progress_interval;
-            return dispatcher;
-        }
-
-        let default_strategy = detect::default_strategy();
-        let mut dispatcher = Self::with_strategy(default_strategy, false);
+        let mut dispatcher = match opts.strategy_override {
+            Some(override_kind) => Self::with_strategy(override_kind, true),
+            None => Self::with_strategy(detect::default_strategy(), false),
+        };
         dispatcher.progress_interval = opts.progress_interval;
+        if let Some(registry) = &opts.strategies {
+            dispatcher.registry = Arc::clone(registry);
+        }
         dispatcher
     }
 
@@ -126,6 +156,12 @@ impl TraversalDispatcher {
         self.progress_interval
     }
 
+    /// Strategies the dispatcher chooses from.
+    #[must_use]
+    pub fn registry(&self) -> &StrategyRegistry {
+        &self.registry
+    }
+
     /// Execute traversal for the supplied root path using the configured strategy.
     pub fn traverse<P: AsRef<Path>>(
         &self,
@@ -133,51 +169,54 @@ impl TraversalDispatcher {
         context: &mut TraversalContext,
     ) -> std::io::Result<u64> {
         let root_ref = root.as_ref();
-        let resolved = self.resolve_strategy(root_ref);
+        let fs_info = detect::filesystem_info_for_path(root_ref);
+        let strategy = self.select(&context.options, &fs_info);
 
-        if self.explicit_override && resolved != self.strategy {
+        if self.explicit_override && strategy.kind() != self.strategy {
             log::warn!(
                 "Requested traversal strategy '{}' unsupported; falling back to '{}'",
                 self.strategy,
-                resolved,
+                strategy.kind(),
             );
         }
 
-        context.set_strategy(resolved);
+        context.set_strategy(strategy.kind());
+        context.set_root_filesystem(fs_info.kind);
         context.update_progress_interval(self.progress_interval);
-
-        match resolved {
-            StrategyKind::Legacy => legacy::traverse_directory(root_ref, context),
-            StrategyKind::WindowsOptimized => {
-                let strategy = windows::WindowsTraversal;
-                strategy.traverse(root_ref, context)
-            }
-            StrategyKind::PosixOptimized => {
-                let strategy = posix::PosixTraversal;
-                strategy.traverse(root_ref, context)
-            }
+        context.set_dispatcher(self.clone());
+        context.set_scan_root(root_ref);
+        if fs_info.is_mount_point(root_ref) {
+            context.note_mount_root(root_ref, fs_info.clone());
         }
-    }
 
-    fn resolve_strategy(&self, root: &Path) -> StrategyKind {
-        if self.explicit_override {
-            return Self::ensure_supported(self.strategy);
-        }
+        let pool = Self::build_pool(&context.options)?;
+        pool.install(|| strategy.traverse(root_ref, 0, context))
+    }
 
-        let fs_kind = detect::filesystem_kind_for_path(root);
-        let preferred = detect::strategy_for_filesystem(fs_kind);
-        Self::ensure_supported(preferred)
+    /// Pick the strategy for a filesystem: the explicit override when set,
+    /// otherwise the first eligible registered strategy. The legacy walker
+    /// runs when the registry offers nothing usable.
+    #[must_use]
+    pub fn select(&self, opts: &ScanOptions, fs: &FilesystemInfo) -> &dyn TraversalStrategy {
+        let selected = if self.explicit_override {
+            self.registry.resolve(self.strategy)
+        } else {
+            self.registry.select(opts, fs)
+        };
+        selected.unwrap_or(&legacy::LegacyTraversal)
     }
 
-    fn ensure_supported(kind: StrategyKind) -> StrategyKind {
-        match kind {
-            StrategyKind::WindowsOptimized if windows::WindowsTraversal::is_supported() => {
-                StrategyKind::WindowsOptimized
-            }
-            StrategyKind::PosixOptimized if posix::PosixTraversal::is_supported() => {
-                StrategyKind::PosixOptimized
-            }
-            _ => StrategyKind::Legacy,
-        }
+    /// Dedicated worker pool so scans neither share nor resize the global rayon pool.
+    ///
+    /// Workers lower their own scheduling priority when a gentle mode is requested.
+    fn build_pool(opts: &ScanOptions) -> std::io::Result<rayon::ThreadPool> {
+        let nice = opts.nice;
+        let io_priority = opts.io_priority;
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(opts.threads.unwrap_or(0))
+            .thread_name(|index| format!("dua-walk-{index}"))
+            .start_handler(move |_| throttle::apply_worker_priority(nice, io_priority))
+            .build()
+            .map_err(std::io::Error::other)
     }
 }
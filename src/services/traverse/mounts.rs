@@ -0,0 +1,117 @@
+//! Mount table lookups backed by `/proc/self/mountinfo`.
+//!
+//! Parsing is platform independent so it can be exercised anywhere; reading
+//! the live table is only available on Linux and yields an empty table
+//! elsewhere.
+
+use std::path::{Path, PathBuf};
+
+/// A single mount as described by one `mountinfo` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountEntry {
+    /// Where the filesystem is mounted.
+    pub mount_point: PathBuf,
+    /// Filesystem type name, e.g. `ext4`, `nfs4` or `fuse.sshfs`.
+    pub fs_type: String,
+    /// Mount source, e.g. `/dev/sda1` or `server:/export`.
+    pub source: String,
+    /// Device major number reported for the mount.
+    pub major: u32,
+    /// Device minor number reported for the mount.
+    pub minor: u32,
+}
+
+/// Parse the contents of a `mountinfo` file, skipping malformed lines.
+#[must_use]
+pub fn parse_mountinfo(content: &str) -> Vec<MountEntry> {
+    content.lines().filter_map(parse_mountinfo_line).collect()
+}
+
+/// Parse one line: `id parent major:minor root mount_point options [optional...] - fstype source super_options`.
+fn parse_mountinfo_line(line: &str) -> Option<MountEntry> {
+    let mut fields = line.split_whitespace();
+    let _mount_id = fields.next()?;
+    let _parent_id = fields.next()?;
+    let (major, minor) = fields.next()?.split_once(':')?;
+    let _root = fields.next()?;
+    let mount_point = fields.next()?;
+
+    // Optional fields run until the lone "-" separator.
+    let mut fields = fields.skip_while(|field| *field != "-").skip(1);
+    let fs_type = fields.next()?;
+    let source = fields.next()?;
+
+    Some(MountEntry {
+        mount_point: PathBuf::from(unescape(mount_point)),
+        fs_type: unescape(fs_type),
+        source: unescape(source),
+        major: major.parse().ok()?,
+        minor: minor.parse().ok()?,
+    })
+}
+
+/// Decode the octal escapes (`\040` for space) used by the kernel.
+fn unescape(field: &str) -> String {
+    if !field.contains('\\') {
+        return field.to_string();
+    }
+
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\\'
+            && let Some(digits) = bytes.get(i + 1..i + 4)
+            && let Ok(text) = std::str::from_utf8(digits)
+            && let Ok(value) = u8::from_str_radix(text, 8)
+        {
+            out.push(value);
+            i += 4;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Read the mount table of the current process.
+#[must_use]
+pub fn read_mount_table() -> Vec<MountEntry> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_to_string("/proc/self/mountinfo")
+            .map(|content| parse_mountinfo(&content))
+            .unwrap_or_default()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Vec::new()
+    }
+}
+
+/// Find the mount containing `path`.
+///
+/// The deepest mount point wins; when several mounts share it, one whose
+/// device matches `device` (as `(major, minor)`) is preferred, then the one
+/// mounted last, which shadows the others.
+#[must_use]
+pub fn mount_for_path<'a>(
+    table: &'a [MountEntry],
+    path: &Path,
+    device: Option<(u32, u32)>,
+) -> Option<&'a MountEntry> {
+    table
+        .iter()
+        .enumerate()
+        .filter(|(_, mount)| path.starts_with(&mount.mount_point))
+        .max_by_key(|(index, mount)| {
+            (
+                mount.mount_point.components().count(),
+                device == Some((mount.major, mount.minor)),
+                *index,
+            )
+        })
+        .map(|(_, mount)| mount)
+}
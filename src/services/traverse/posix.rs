@@ -1,9 +1,11 @@
 //! POSIX-optimized traversal strategy leveraging `rustix` primitives.
 //!
-//! The optimized implementation will land in later phases; this placeholder
-//! routes through the legacy traversal engine while we wire coordination and
-//! eligibility checks.
+//! Directories are walked through open file descriptors: children are
+//! stat'ed and opened relative to their parent's fd (`statx`/`fstatat`,
+//! `openat`), so the kernel never re-resolves full paths, and `d_type` from
+//! `getdents` drops entries that cannot contribute before any stat call.
 
+use super::detect::{self, FilesystemInfo};
 use super::legacy;
 use super::strategy::TraversalStrategy;
 use super::{StrategyKind, TraversalContext};
@@ -12,10 +14,20 @@ use std::io;
 use std::path::Path;
 
 #[cfg(unix)]
-use crate::models::DirectoryEntry;
+use crate::models::{DirectoryEntry, EntryAttributes};
+#[cfg(unix)]
+use crate::services::ignore::IgnoreStack;
 #[cfg(unix)]
 use std::path::PathBuf;
 
+#[cfg(unix)]
+use super::budget::FdBudget;
+#[cfg(unix)]
+use super::legacy::FileId;
+#[cfg(unix)]
+use crate::SizeBasis;
+#[cfg(unix)]
+use crate::models::EntryKind;
 #[cfg(unix)]
 use rayon::prelude::*;
 #[cfg(unix)]
@@ -23,13 +35,18 @@ use rustix::fd::OwnedFd;
 #[cfg(unix)]
 use rustix::fs::{self as rfs, Dir, Mode, OFlags};
 #[cfg(unix)]
-use std::ffi::OsString;
+use rustix::fs::{AtFlags, FileType};
+#[cfg(unix)]
+use std::ffi::{CStr, CString, OsStr};
 #[cfg(unix)]
-use std::os::unix::ffi::OsStringExt;
+use std::os::unix::ffi::OsStrExt;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
 #[cfg(unix)]
 use std::sync::atomic::{AtomicU64, Ordering};
 
-/// POSIX traversal backend placeholder.
+/// POSIX traversal backend walking directories through `openat`/`statx` on
+/// directory file descriptors.
 #[derive(Debug, Default)]
 pub struct PosixTraversal;
 
@@ -45,14 +62,18 @@ impl TraversalStrategy for PosixTraversal {
         StrategyKind::PosixOptimized
     }
 
-    fn is_eligible(&self, _opts: &ScanOptions) -> bool {
+    fn is_available(&self) -> bool {
         Self::is_supported()
     }
 
-    fn traverse(&self, root: &Path, context: &mut TraversalContext) -> io::Result<u64> {
+    fn is_eligible(&self, _opts: &ScanOptions, fs: &FilesystemInfo) -> bool {
+        detect::strategy_for_filesystem(fs.kind) == StrategyKind::PosixOptimized
+    }
+
+    fn traverse(&self, root: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64> {
         #[cfg(unix)]
         {
-            posix_traverse(root, context)
+            traverse_with(root, depth, context, &DirectCalls)
         }
 
         #[cfg(not(unix))]
@@ -60,13 +81,113 @@ impl TraversalStrategy for PosixTraversal {
             log::debug!(
                 "posix::PosixTraversal invoked on non-Unix platform; falling back to legacy"
             );
-            legacy::traverse_directory(root, context)
+            legacy::LegacyTraversal.traverse(root, depth, context)
+        }
+    }
+}
+
+/// How a backend issues the per-child `stat` and `openat` calls of one directory.
+///
+/// The walk hands over every child of a directory at once so implementations
+/// may batch the calls; results are returned in input order.
+#[cfg(unix)]
+pub(super) trait ChildOps: Sync {
+    /// Stat each child relative to `dir_fd` without following symlinks.
+    fn stat_children(
+        &self,
+        dir_fd: &OwnedFd,
+        children: &[PendingChild],
+        basis: SizeBasis,
+    ) -> Vec<io::Result<ChildStat>>;
+
+    /// Open each named subdirectory of `dir_fd` for reading.
+    fn open_directories(&self, dir_fd: &OwnedFd, names: &[&CStr]) -> Vec<io::Result<OwnedFd>>;
+}
+
+/// Issues one synchronous syscall per child.
+#[cfg(unix)]
+pub(super) struct DirectCalls;
+
+#[cfg(unix)]
+impl ChildOps for DirectCalls {
+    fn stat_children(
+        &self,
+        dir_fd: &OwnedFd,
+        children: &[PendingChild],
+        basis: SizeBasis,
+    ) -> Vec<io::Result<ChildStat>> {
+        children
+            .iter()
+            .map(|child| stat_child(dir_fd, &child.name, basis, !child.known_dir))
+            .collect()
+    }
+
+    fn open_directories(&self, dir_fd: &OwnedFd, names: &[&CStr]) -> Vec<io::Result<OwnedFd>> {
+        names
+            .iter()
+            .map(|name| {
+                rfs::openat(dir_fd, *name, DIRECTORY_FLAGS, Mode::empty()).map_err(Into::into)
+            })
+            .collect()
+    }
+}
+
+/// A directory fd, returned to the open-fd budget when dropped if it counted against it.
+#[cfg(unix)]
+struct DirHandle<'a> {
+    fd: OwnedFd,
+    budget: Option<&'a FdBudget>,
+}
+
+#[cfg(unix)]
+impl DirHandle<'_> {
+    fn unbudgeted(fd: OwnedFd) -> Self {
+        Self { fd, budget: None }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for DirHandle<'_> {
+    fn drop(&mut self) {
+        if let Some(budget) = self.budget {
+            budget.release(1);
         }
     }
 }
 
+/// Whether an open failed because the process or system ran out of descriptors.
 #[cfg(unix)]
-fn posix_traverse(root: &Path, context: &TraversalContext) -> io::Result<u64> {
+fn is_fd_exhaustion(err: &io::Error) -> bool {
+    let raw = err.raw_os_error();
+    raw == Some(rustix::io::Errno::MFILE.raw_os_error())
+        || raw == Some(rustix::io::Errno::NFILE.raw_os_error())
+}
+
+/// Flags used to open directories for listing.
+#[cfg(unix)]
+pub(super) const DIRECTORY_FLAGS: OFlags = OFlags::RDONLY
+    .union(OFlags::DIRECTORY)
+    .union(OFlags::CLOEXEC);
+
+/// A directory entry that survived the `d_type` checks and awaits its stat.
+#[cfg(unix)]
+pub(super) struct PendingChild {
+    pub(super) name: CString,
+    pub(super) path: PathBuf,
+    /// `d_type` already reported a directory, so no size is needed.
+    pub(super) known_dir: bool,
+}
+
+/// Walk `root`, found at `depth`, through directory fds, issuing child calls
+/// through `ops`.
+#[cfg(unix)]
+pub(super) fn traverse_with<O: ChildOps>(
+    root: &Path,
+    depth: u16,
+    context: &TraversalContext,
+    ops: &O,
+) -> io::Result<u64> {
+    context.throttle(1);
     let root_metadata = match std::fs::symlink_metadata(root) {
         Ok(meta) => meta,
         Err(err) => {
@@ -76,88 +197,196 @@ fn posix_traverse(root: &Path, context: &TraversalContext) -> io::Result<u64> {
     };
 
     if !root_metadata.is_dir() {
-        return legacy::traverse_directory(root, context);
+        return legacy::LegacyTraversal.traverse(root, depth, context);
     }
 
     if !context.options.cross_filesystem {
         context.set_root_device_if_absent(legacy::get_device_id(&root_metadata));
     }
+    if context.options.follow_symlinks {
+        let id = FileId::new(legacy::get_device_id(&root_metadata), root_metadata.ino());
+        context.enter_directory(root, Some(id), false)?;
+    }
+
+    context.throttle(1);
+    let dir_fd = rfs::openat(rfs::CWD, root, DIRECTORY_FLAGS, Mode::empty())
+        .map_err(std::io::Error::from)?;
 
-    let dir_fd = rfs::openat(
-        rfs::CWD,
+    traverse_directory_fd(
+        ops,
         root,
-        OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
-        Mode::empty(),
+        DirHandle::unbudgeted(dir_fd),
+        legacy::entry_attributes(&root_metadata),
+        depth,
+        context,
+        &IgnoreStack::new(),
     )
-    .map_err(std::io::Error::from)?;
+}
 
-    traverse_directory_fd(root, dir_fd, 0, context)
+/// Read the entries of `dir_fd` that may contribute to the scan.
+#[cfg(unix)]
+fn list_children(
+    dir_fd: &OwnedFd,
+    current: &Path,
+    context: &TraversalContext,
+) -> io::Result<Vec<PendingChild>> {
+    let mut pending = Vec::new();
+    context.throttle(1);
+    let dir_iter = Dir::read_from(dir_fd).map_err(std::io::Error::from)?;
+
+    for entry_result in dir_iter {
+        if context.should_stop() {
+            break;
+        }
+        let entry = match entry_result {
+            Ok(entry) => entry,
+            Err(err) => {
+                let io_err: std::io::Error = err.into();
+                context.record_error(current, &io_err)?;
+                continue;
+            }
+        };
+
+        let name = entry.file_name();
+        let name_bytes = name.to_bytes();
+        if name_bytes == b"." || name_bytes == b".." {
+            continue;
+        }
+
+        // `d_type` lets us drop entries that never contribute without a stat
+        // call; filesystems that do not fill it report `Unknown`.
+        let dirent_type = entry.file_type();
+        match dirent_type {
+            FileType::Symlink if !context.options.follow_symlinks => continue,
+            FileType::Fifo
+            | FileType::Socket
+            | FileType::CharacterDevice
+            | FileType::BlockDevice => continue,
+            _ => {}
+        }
+
+        let child_path: PathBuf = current.join(OsStr::from_bytes(name_bytes));
+
+        let known_dir = dirent_type == FileType::Directory;
+        if known_dir && context.is_excluded(&child_path, true) {
+            continue;
+        }
+
+        pending.push(PendingChild {
+            name: name.to_owned(),
+            path: child_path,
+            known_dir,
+        });
+    }
+
+    Ok(pending)
 }
 
 #[cfg(unix)]
 #[allow(clippy::too_many_lines)]
-fn traverse_directory_fd(
+fn traverse_directory_fd<O: ChildOps>(
+    ops: &O,
     current: &Path,
-    dir_fd: OwnedFd,
+    dir: DirHandle<'_>,
+    attrs: EntryAttributes,
     depth: u16,
     context: &TraversalContext,
+    ignore: &IgnoreStack,
 ) -> io::Result<u64> {
     if let Some(max_depth) = context.max_depth
         && depth > max_depth
     {
         return Ok(0);
     }
+    context.notify_directory(current);
 
     let mut total_size = 0u64;
     let mut file_count = 0u32;
     let mut dir_count = 0u32;
-    let mut child_dirs: Vec<(PathBuf, OwnedFd)> = Vec::new();
+    let ignore = context.enter_ignore_scope(ignore, current);
+
+    let dir_fd = &dir.fd;
+    // An unchanged directory is not listed: only the subdirectories the
+    // baseline recorded are stat'ed, and its files come from the baseline.
+    let pending = match context.unchanged_directory(current, &attrs) {
+        Some(listing) => {
+            total_size = listing.file_bytes();
+            file_count = listing.file_count;
+            listing
+                .subdirs
+                .iter()
+                .filter_map(|name| {
+                    Some(PendingChild {
+                        name: CString::new(name.as_bytes()).ok()?,
+                        path: current.join(name),
+                        known_dir: false,
+                    })
+                })
+                .collect()
+        }
+        None => list_children(dir_fd, current, context)?,
+    };
 
-    let dir_iter = Dir::read_from(&dir_fd).map_err(std::io::Error::from)?;
+    context.throttle(pending.len() as u64);
+    let stats = ops.stat_children(dir_fd, &pending, context.options.basis);
+    let mut subdirs: Vec<(PendingChild, EntryAttributes)> = Vec::new();
+    let mut mounts: Vec<PathBuf> = Vec::new();
 
-    for entry_result in dir_iter {
-        let entry = match entry_result {
-            Ok(entry) => entry,
+    for (child, stat) in pending.into_iter().zip(stats) {
+        let child_path = child.path.as_path();
+        let via_symlink = matches!(&stat, Ok(stat) if stat.file_type == FileType::Symlink);
+        if via_symlink && !context.options.follow_symlinks {
+            continue;
+        }
+        let stat = if via_symlink {
+            context.throttle(1);
+            follow_child(dir_fd, &child.name, context.options.basis)
+        } else {
+            stat
+        };
+        let stat = match stat {
+            Ok(stat) => stat,
             Err(err) => {
-                let io_err: std::io::Error = err.into();
-                context.record_error(current, &io_err)?;
+                context.record_error(child_path, &err)?;
                 continue;
             }
         };
+        let is_dir = stat.file_type == FileType::Directory;
 
-        let name_bytes = entry.file_name().to_bytes();
-        if name_bytes == b"." || name_bytes == b".." {
+        if !child.known_dir && context.is_excluded(child_path, is_dir) {
             continue;
         }
 
-        let child_name = OsString::from_vec(name_bytes.to_vec());
-        let child_component = PathBuf::from(&child_name);
-        let child_path: PathBuf = current.join(&child_component);
-
-        let metadata = match std::fs::symlink_metadata(&child_path) {
-            Ok(meta) => meta,
-            Err(err) => {
-                context.record_error(&child_path, &err)?;
-                continue;
+        if context.is_ignored(&ignore, child_path, is_dir) {
+            if is_dir && context.collapse_ignored() {
+                let collapsed = legacy::collapse_ignored_directory(
+                    child_path,
+                    stat.attributes(),
+                    depth + 1,
+                    context,
+                )?;
+                total_size = total_size.saturating_add(collapsed);
+                dir_count = dir_count.saturating_add(1);
             }
-        };
-
-        if metadata.is_symlink() && !context.options.follow_symlinks {
             continue;
         }
 
-        if !context.options.cross_filesystem
-            && let Some(root_dev) = context.root_device()
-        {
-            let current_dev = legacy::get_device_id(&metadata);
-            if current_dev != root_dev {
-                continue;
+        if context.crosses_device(stat.device, is_dir) {
+            if is_dir && !via_symlink {
+                context.record_skipped_mount(child_path, stat.device);
             }
+            continue;
         }
 
-        if metadata.is_file() {
-            let file_size = if context.should_count_file(&child_path, &metadata) {
-                context.get_size(&child_path, &metadata)
+        if stat.file_type == FileType::RegularFile {
+            let basis = context.options.basis;
+            let file_size = if context.should_count_link(
+                child_path,
+                FileId::new(stat.device, stat.inode),
+                stat.nlink,
+                || stat.size_bytes(basis),
+            ) {
+                stat.size_bytes(basis)
             } else {
                 0
             };
@@ -170,53 +399,121 @@ fn traverse_directory_fd(
             if context.max_depth.is_none_or(|max| file_depth <= max) {
                 let parent_path_str = legacy::normalize_path(current);
                 let file_entry = DirectoryEntry {
-                    path: legacy::normalize_path(&child_path),
+                    path: legacy::normalize_path(child_path),
                     parent_path: Some(parent_path_str),
                     depth: file_depth,
                     size_bytes: file_size,
                     file_count: 0,
                     dir_count: 0,
-                };
+                    ..DirectoryEntry::default()
+                }
+                .with_attributes(stat.attributes());
                 context.insert_entry(file_entry)?;
             }
-        } else if metadata.is_dir() {
+        } else if is_dir {
             dir_count = dir_count.saturating_add(1);
+            if let Some(size) = context.reuse_completed(child_path) {
+                total_size = total_size.saturating_add(size);
+                continue;
+            }
             let next_depth = depth + 1;
 
             if context.max_depth.is_some_and(|max| next_depth > max) {
                 continue;
             }
+            if context.enters_mount(attrs.device, stat.device) {
+                mounts.push(child.path);
+                continue;
+            }
+            if context.options.follow_symlinks
+                && !context.enter_directory(
+                    child_path,
+                    Some(FileId::new(stat.device, stat.inode)),
+                    via_symlink,
+                )?
+            {
+                continue;
+            }
 
-            let child_fd = match rfs::openat(
-                &dir_fd,
-                child_component.as_path(),
-                OFlags::RDONLY | OFlags::DIRECTORY | OFlags::CLOEXEC,
-                Mode::empty(),
-            ) {
-                Ok(fd) => fd,
-                Err(err) => {
-                    let io_err: std::io::Error = err.into();
-                    context.record_error(&child_path, &io_err)?;
+            subdirs.push((child, stat.attributes()));
+        }
+    }
+
+    // Subdirectories are opened relative to this fd while the budget allows;
+    // the rest are reopened by path when a worker reaches them.
+    let budget = context.fd_budget();
+    let granted = budget.acquire(subdirs.len());
+    context.throttle(granted as u64);
+    let opened = {
+        let names: Vec<&CStr> = subdirs[..granted]
+            .iter()
+            .map(|(child, _)| child.name.as_c_str())
+            .collect();
+        ops.open_directories(dir_fd, &names)
+    };
+
+    let mut opened = opened.into_iter();
+    let mut child_dirs: Vec<(PathBuf, Option<DirHandle<'_>>, EntryAttributes)> = Vec::new();
+    for (child, child_attrs) in subdirs {
+        let handle = match opened.next() {
+            Some(Ok(fd)) => Some(DirHandle {
+                fd,
+                budget: Some(budget),
+            }),
+            Some(Err(err)) => {
+                budget.release(1);
+                if !is_fd_exhaustion(&err) {
+                    context.record_error(&child.path, &err)?;
                     continue;
                 }
-            };
-
-            child_dirs.push((child_path.clone(), child_fd));
-        }
+                None
+            }
+            None => None,
+        };
+        child_dirs.push((child.path, handle, child_attrs));
     }
 
-    drop(dir_fd);
+    drop(dir);
 
     let subdir_total = AtomicU64::new(0);
     child_dirs
         .into_par_iter()
-        .try_for_each(|(child_path, child_fd)| {
-            let size = traverse_directory_fd(&child_path, child_fd, depth + 1, context)?;
+        .try_for_each(|(child_path, child_dir, child_attrs)| {
+            if context.should_stop() {
+                return Ok(());
+            }
+            let child_dir = if let Some(handle) = child_dir {
+                handle
+            } else {
+                context.throttle(1);
+                match rfs::openat(rfs::CWD, &child_path, DIRECTORY_FLAGS, Mode::empty()) {
+                    Ok(fd) => DirHandle::unbudgeted(fd),
+                    Err(err) => return context.record_error(&child_path, &err.into()),
+                }
+            };
+            let size = traverse_directory_fd(
+                ops,
+                &child_path,
+                child_dir,
+                child_attrs,
+                depth + 1,
+                context,
+                &ignore,
+            )?;
             subdir_total.fetch_add(size, Ordering::Relaxed);
             Ok::<(), io::Error>(())
         })?;
     total_size = total_size.saturating_add(subdir_total.load(Ordering::Relaxed));
 
+    // Other filesystems get the backend chosen for them.
+    for mount in mounts {
+        if context.should_stop() {
+            break;
+        }
+        let size = context.traverse_mount(&mount, depth + 1)?;
+        total_size = total_size.saturating_add(size);
+    }
+
     let parent_path = current.parent().map(legacy::normalize_path);
     let normalized_path = legacy::normalize_path(current);
 
@@ -227,10 +524,169 @@ fn traverse_directory_fd(
         size_bytes: total_size,
         file_count,
         dir_count,
-    };
+        ..DirectoryEntry::default()
+    }
+    .with_attributes(attrs);
 
     context.insert_entry(entry)?;
     context.register_directory_progress();
 
     Ok(total_size)
 }
+
+/// The subset of `stat` results the traversal records for a child entry.
+#[cfg(unix)]
+pub(super) struct ChildStat {
+    file_type: FileType,
+    device: u64,
+    inode: u64,
+    nlink: u64,
+    size: u64,
+    blocks: u64,
+    mtime_ns: Option<i64>,
+    atime_ns: Option<i64>,
+}
+
+#[cfg(unix)]
+impl ChildStat {
+    fn attributes(&self) -> EntryAttributes {
+        let kind = match self.file_type {
+            FileType::Symlink => EntryKind::Symlink,
+            FileType::Directory => EntryKind::Directory,
+            FileType::RegularFile => EntryKind::File,
+            _ => EntryKind::Other,
+        };
+
+        EntryAttributes {
+            kind,
+            mtime_ns: self.mtime_ns,
+            atime_ns: self.atime_ns,
+            inode: Some(self.inode),
+            device: Some(self.device),
+            nlink: Some(self.nlink),
+        }
+    }
+
+    fn size_bytes(&self, basis: SizeBasis) -> u64 {
+        match basis {
+            SizeBasis::Logical => self.size,
+            SizeBasis::Physical => crate::services::size::physical_size_from_blocks(self.blocks),
+        }
+    }
+}
+
+/// Stat `name` relative to `dir_fd` without following symlinks.
+///
+/// On Linux this uses `statx` and asks only for the size field `basis` needs,
+/// skipping it entirely when `need_size` is false. Kernels without `statx`
+/// and other Unix platforms use `fstatat`.
+#[cfg(unix)]
+fn stat_child(
+    dir_fd: &OwnedFd,
+    name: &CStr,
+    basis: SizeBasis,
+    need_size: bool,
+) -> io::Result<ChildStat> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let mask = statx_mask(basis, need_size);
+        match rfs::statx(dir_fd, name, AtFlags::SYMLINK_NOFOLLOW, mask) {
+            Ok(stx) => return Ok(child_stat_from_statx(&stx)),
+            Err(rustix::io::Errno::NOSYS) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let _ = (basis, need_size);
+
+    let stat = rfs::statat(dir_fd, name, AtFlags::SYMLINK_NOFOLLOW)?;
+    Ok(child_stat_from_stat(&stat))
+}
+
+/// Stat the target of the symlink `name` relative to `dir_fd`.
+#[cfg(unix)]
+fn follow_child(dir_fd: &OwnedFd, name: &CStr, basis: SizeBasis) -> io::Result<ChildStat> {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        match rfs::statx(dir_fd, name, AtFlags::empty(), statx_mask(basis, true)) {
+            Ok(stx) => return Ok(child_stat_from_statx(&stx)),
+            Err(rustix::io::Errno::NOSYS) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    let _ = basis;
+
+    let stat = rfs::statat(dir_fd, name, AtFlags::empty())?;
+    Ok(child_stat_from_stat(&stat))
+}
+
+/// The `statx` fields recorded for an entry, plus the size field `basis` needs.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn statx_mask(basis: SizeBasis, need_size: bool) -> rustix::fs::StatxFlags {
+    use rustix::fs::StatxFlags;
+
+    let mask = StatxFlags::TYPE
+        | StatxFlags::INO
+        | StatxFlags::NLINK
+        | StatxFlags::MTIME
+        | StatxFlags::ATIME;
+    match (need_size, basis) {
+        (false, _) => mask,
+        (true, SizeBasis::Logical) => mask | StatxFlags::SIZE,
+        (true, SizeBasis::Physical) => mask | StatxFlags::BLOCKS,
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn child_stat_from_statx(stx: &rustix::fs::Statx) -> ChildStat {
+    use rustix::fs::StatxFlags;
+
+    let returned = StatxFlags::from_bits_retain(stx.stx_mask);
+    let timestamp = |flag: StatxFlags, ts: &rustix::fs::StatxTimestamp| {
+        returned
+            .contains(flag)
+            .then(|| timestamp_ns(ts.tv_sec, i64::from(ts.tv_nsec)))
+            .flatten()
+    };
+
+    ChildStat {
+        file_type: FileType::from_raw_mode(u32::from(stx.stx_mode)),
+        device: rfs::makedev(stx.stx_dev_major, stx.stx_dev_minor),
+        inode: stx.stx_ino,
+        nlink: u64::from(stx.stx_nlink),
+        size: stx.stx_size,
+        blocks: stx.stx_blocks,
+        mtime_ns: timestamp(StatxFlags::MTIME, &stx.stx_mtime),
+        atime_ns: timestamp(StatxFlags::ATIME, &stx.stx_atime),
+    }
+}
+
+// Field widths and signedness of `struct stat` vary between platforms.
+#[cfg(unix)]
+#[allow(
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::unnecessary_cast,
+    clippy::useless_conversion
+)]
+fn child_stat_from_stat(stat: &rfs::Stat) -> ChildStat {
+    ChildStat {
+        file_type: FileType::from_raw_mode(stat.st_mode as rfs::RawMode),
+        device: stat.st_dev as u64,
+        inode: stat.st_ino as u64,
+        nlink: stat.st_nlink as u64,
+        size: stat.st_size as u64,
+        blocks: stat.st_blocks as u64,
+        mtime_ns: timestamp_ns(stat.st_mtime as i64, stat.st_mtime_nsec as i64),
+        atime_ns: timestamp_ns(stat.st_atime as i64, stat.st_atime_nsec as i64),
+    }
+}
+
+/// Combine seconds and nanoseconds into nanoseconds since the Unix epoch.
+#[cfg(unix)]
+fn timestamp_ns(secs: i64, nanos: i64) -> Option<i64> {
+    secs.checked_mul(1_000_000_000)?.checked_add(nanos)
+}
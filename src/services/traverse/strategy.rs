@@ -1,29 +1,52 @@
 //! Strategy trait and registry helpers for filesystem traversal backends.
+//!
+//! The dispatcher never names a backend directly: it asks a
+//! [`StrategyRegistry`] for the first strategy eligible for the scan options
+//! and the filesystem being walked. Library users can register their own
+//! [`TraversalStrategy`] implementations, e.g. for a FUSE mount backed by an
+//! object store, and they are consulted before the built-in backends.
+//!
+//! A backend reports through the [`TraversalContext`] it is handed: besides
+//! recording entries it checks excludes and ignore files, dedupes hardlinks,
+//! records errors, announces each directory before listing it and hands
+//! nested mounts back to the dispatcher, the same way the built-in backends
+//! do.
 
-use super::{StrategyKind, TraversalContext};
+use super::detect::FilesystemInfo;
+use super::{StrategyKind, TraversalContext, io_uring, legacy, posix, windows};
 use crate::ScanOptions;
 use std::io;
 use std::path::Path;
+use std::sync::Arc;
 
 /// Common interface implemented by filesystem-specific traversal strategies.
-pub trait TraversalStrategy {
+pub trait TraversalStrategy: Send + Sync {
     /// Identify the strategy for logging and diagnostics.
     fn kind(&self) -> StrategyKind;
 
-    /// Determine whether the strategy may run under the supplied options.
-    fn is_eligible(&self, opts: &ScanOptions) -> bool;
+    /// Whether the strategy can run on this host at all. Explicit overrides
+    /// only fall back to another backend when this is false.
+    fn is_available(&self) -> bool {
+        true
+    }
+
+    /// Determine whether the strategy should walk a filesystem described by
+    /// `fs` under the supplied options.
+    fn is_eligible(&self, opts: &ScanOptions, fs: &FilesystemInfo) -> bool;
 
-    /// Execute traversal for the provided root path, returning total byte size.
-    fn traverse(&self, root: &Path, context: &mut TraversalContext) -> io::Result<u64>;
+    /// Walk `root`, recording its entries in `context` and returning the total
+    /// byte size. `depth` is the depth of `root` within the scan: 0 for the
+    /// scan root, deeper when the dispatcher hands over a subtree.
+    fn traverse(&self, root: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64>;
 }
 
-/// Placeholder registry type; will collect platform strategies in later phases.
-#[derive(Default)]
-pub struct StrategyRegistry<'a> {
-    strategies: Vec<&'a dyn TraversalStrategy>,
+/// Ordered set of traversal strategies; later registrations take precedence.
+#[derive(Clone, Default)]
+pub struct StrategyRegistry {
+    strategies: Vec<Arc<dyn TraversalStrategy>>,
 }
 
-impl<'a> StrategyRegistry<'a> {
+impl StrategyRegistry {
     /// Construct an empty registry.
     #[must_use]
     pub fn new() -> Self {
@@ -32,17 +55,64 @@ impl<'a> StrategyRegistry<'a> {
         }
     }
 
-    /// Register a strategy implementation.
-    pub fn register(&mut self, strategy: &'a dyn TraversalStrategy) {
-        self.strategies.push(strategy);
+    /// Construct a registry holding the built-in backends.
+    #[must_use]
+    pub fn with_defaults() -> Self {
+        let mut registry = Self::new();
+        registry.register(legacy::LegacyTraversal);
+        registry.register(windows::WindowsTraversal);
+        registry.register(posix::PosixTraversal);
+        registry.register(io_uring::IoUringTraversal);
+        registry
+    }
+
+    /// Register a strategy implementation, consulted before every strategy
+    /// registered earlier.
+    pub fn register<S: TraversalStrategy + 'static>(&mut self, strategy: S) {
+        self.strategies.push(Arc::new(strategy));
     }
 
-    /// Select the first eligible strategy, falling back to legacy if needed.
+    /// Registered strategies, highest precedence first.
+    pub fn strategies(&self) -> impl Iterator<Item = &dyn TraversalStrategy> {
+        self.strategies.iter().rev().map(AsRef::as_ref)
+    }
+
+    /// Look up the strategy registered for `kind`.
     #[must_use]
-    pub fn select(&'a self, opts: &ScanOptions) -> Option<&'a dyn TraversalStrategy> {
-        self.strategies
-            .iter()
-            .copied()
-            .find(|strategy| strategy.is_eligible(opts))
+    pub fn get(&self, kind: StrategyKind) -> Option<&dyn TraversalStrategy> {
+        self.strategies().find(|strategy| strategy.kind() == kind)
+    }
+
+    /// Select the first strategy eligible for `opts` on the filesystem `fs`.
+    #[must_use]
+    pub fn select(
+        &self,
+        opts: &ScanOptions,
+        fs: &FilesystemInfo,
+    ) -> Option<&dyn TraversalStrategy> {
+        self.strategies()
+            .find(|strategy| strategy.is_available() && strategy.is_eligible(opts, fs))
+    }
+
+    /// Resolve an explicitly requested strategy, stepping down through
+    /// [`StrategyKind::fallback`] while the requested one is unavailable.
+    #[must_use]
+    pub fn resolve(&self, kind: StrategyKind) -> Option<&dyn TraversalStrategy> {
+        let mut next = Some(kind);
+        while let Some(kind) = next {
+            if let Some(strategy) = self.get(kind).filter(|s| s.is_available()) {
+                return Some(strategy);
+            }
+            next = kind.fallback();
+        }
+        None
+    }
+}
+
+impl std::fmt::Debug for StrategyRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.strategies().map(TraversalStrategy::kind))
+            .finish()
     }
 }
@@ -0,0 +1,145 @@
+//! Keeping scans gentle on shared systems.
+//!
+//! [`RateLimiter`] caps filesystem operations per second across all workers
+//! and is consulted by every backend through
+//! [`TraversalContext::throttle`](super::TraversalContext::throttle).
+//! [`apply_worker_priority`] lowers the CPU and I/O scheduling priority of
+//! traversal worker threads; [`lower_process_priority`] is the CLI's fallback
+//! where threads cannot be niced one by one.
+
+use crate::IoPriority;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Nice increment applied to worker threads in `--nice` mode.
+pub const NICE_INCREMENT: i32 = 10;
+
+/// Token bucket shared by all traversal workers.
+///
+/// Callers reserve operations up front and sleep off any deficit, so the
+/// long-run rate never exceeds the limit while bursts stay within a tenth of
+/// a second's worth of operations.
+#[derive(Debug)]
+pub struct RateLimiter {
+    ops_per_sec: f64,
+    burst: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Available operations; negative while callers are sleeping off a reservation.
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `ops_per_sec` operations per second (at least one).
+    #[must_use]
+    pub fn new(ops_per_sec: u32) -> Self {
+        let ops_per_sec = f64::from(ops_per_sec.max(1));
+        let burst = (ops_per_sec / 10.0).max(1.0);
+        Self {
+            ops_per_sec,
+            burst,
+            bucket: Mutex::new(Bucket {
+                tokens: burst,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Reserve `ops` operations, blocking the calling thread until they fit.
+    pub fn acquire(&self, ops: u64) {
+        if ops == 0 {
+            return;
+        }
+
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * self.ops_per_sec).min(self.burst);
+            bucket.refilled_at = now;
+
+            // Precision loss only matters beyond 2^52 operations.
+            #[allow(clippy::cast_precision_loss)]
+            let requested = ops as f64;
+            bucket.tokens -= requested;
+            (bucket.tokens < 0.0)
+                .then(|| Duration::from_secs_f64(-bucket.tokens / self.ops_per_sec))
+        };
+
+        if let Some(wait) = wait {
+            std::thread::sleep(wait);
+        }
+    }
+}
+
+/// Lower the scheduling priority of the calling worker thread.
+///
+/// Both settings are only applied on Linux, where they affect the calling
+/// thread alone and end with the scan's worker pool. Elsewhere `nice` would
+/// renice the whole process, once per worker and for good, so it is left to
+/// [`lower_process_priority`]. Failures are logged and the scan continues at
+/// normal priority.
+pub fn apply_worker_priority(nice: bool, io_priority: IoPriority) {
+    if nice {
+        #[cfg(target_os = "linux")]
+        if let Err(err) = rustix::process::nice(NICE_INCREMENT) {
+            log::warn!("Failed to lower CPU priority: {err}");
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        log::debug!("Worker threads cannot be niced on this platform");
+    }
+
+    if io_priority == IoPriority::Idle {
+        #[cfg(target_os = "linux")]
+        if let Err(err) = set_idle_io_priority() {
+            log::warn!("Failed to set idle I/O priority: {err}");
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        log::debug!("I/O priority classes are not supported on this platform");
+    }
+}
+
+/// Lower the CPU priority of the whole process by [`NICE_INCREMENT`] on
+/// platforms where [`apply_worker_priority`] cannot nice worker threads; a
+/// no-op on Linux.
+///
+/// Meant for the `dua` binary: an unprivileged process cannot raise its
+/// priority again, so libraries should not call this.
+pub fn lower_process_priority() {
+    #[cfg(all(unix, not(target_os = "linux")))]
+    if let Err(err) = rustix::process::nice(NICE_INCREMENT) {
+        log::warn!("Failed to lower CPU priority: {err}");
+    }
+
+    #[cfg(not(unix))]
+    log::debug!("CPU priority adjustment is not supported on this platform");
+}
+
+/// Move the calling thread into the idle I/O scheduling class.
+#[cfg(target_os = "linux")]
+fn set_idle_io_priority() -> std::io::Result<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    // SAFETY: ioprio_set takes only integer arguments; `who == 0` targets the calling thread.
+    let rc = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        )
+    };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
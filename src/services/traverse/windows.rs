@@ -5,6 +5,7 @@
 //! optimized path preserves legacy invariants around error collection,
 //! hardlink deduplication, and progress reporting.
 
+use super::detect::{self, FilesystemInfo};
 use super::legacy;
 use super::strategy::TraversalStrategy;
 use super::{StrategyKind, TraversalContext};
@@ -17,6 +18,8 @@ use std::path::PathBuf;
 #[cfg(windows)]
 use crate::models::DirectoryEntry;
 #[cfg(windows)]
+use crate::services::ignore::IgnoreStack;
+#[cfg(windows)]
 use rayon::prelude::*;
 #[cfg(windows)]
 use std::ffi::OsString;
@@ -58,14 +61,18 @@ impl TraversalStrategy for WindowsTraversal {
         StrategyKind::WindowsOptimized
     }
 
-    fn is_eligible(&self, _opts: &ScanOptions) -> bool {
+    fn is_available(&self) -> bool {
         Self::is_supported()
     }
 
-    fn traverse(&self, root: &Path, context: &mut TraversalContext) -> io::Result<u64> {
+    fn is_eligible(&self, _opts: &ScanOptions, fs: &FilesystemInfo) -> bool {
+        detect::strategy_for_filesystem(fs.kind) == StrategyKind::WindowsOptimized
+    }
+
+    fn traverse(&self, root: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64> {
         #[cfg(windows)]
         {
-            traverse_windows(root, context)
+            traverse_windows(root, depth, context)
         }
 
         #[cfg(not(windows))]
@@ -73,13 +80,14 @@ impl TraversalStrategy for WindowsTraversal {
             log::debug!(
                 "windows::WindowsTraversal invoked on non-Windows platform; falling back to legacy"
             );
-            legacy::traverse_directory(root, context)
+            legacy::LegacyTraversal.traverse(root, depth, context)
         }
     }
 }
 
 #[cfg(windows)]
-fn traverse_windows(root: &Path, context: &TraversalContext) -> io::Result<u64> {
+fn traverse_windows(root: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64> {
+    context.throttle(1);
     let metadata = match fs::symlink_metadata(root) {
         Ok(meta) => meta,
         Err(err) => {
@@ -92,18 +100,24 @@ fn traverse_windows(root: &Path, context: &TraversalContext) -> io::Result<u64>
         context.set_root_device_if_absent(legacy::get_device_id(&metadata));
     }
 
-    traverse_directory(root, 0, context)
+    traverse_directory(root, depth, context, &IgnoreStack::new())
 }
 
 #[cfg(windows)]
 #[allow(clippy::too_many_lines)]
-fn traverse_directory(current: &Path, depth: u16, context: &TraversalContext) -> io::Result<u64> {
+fn traverse_directory(
+    current: &Path,
+    depth: u16,
+    context: &TraversalContext,
+    ignore: &IgnoreStack,
+) -> io::Result<u64> {
     if let Some(max_depth) = context.max_depth
         && depth > max_depth
     {
         return Ok(0);
     }
 
+    context.throttle(1);
     let metadata = match fs::symlink_metadata(current) {
         Ok(meta) => meta,
         Err(err) => {
@@ -115,14 +129,17 @@ fn traverse_directory(current: &Path, depth: u16, context: &TraversalContext) ->
     if metadata.is_symlink() && !context.options.follow_symlinks {
         return Ok(0);
     }
-
-    if !context.options.cross_filesystem {
-        if let Some(root_dev) = context.root_device() {
-            let current_dev = legacy::get_device_id(&metadata);
-            if current_dev != root_dev {
-                return Ok(0);
-            }
+    let via_symlink = metadata.is_symlink();
+    let metadata = match legacy::followed_metadata(current, metadata, context) {
+        Ok(meta) => meta,
+        Err(err) => {
+            context.record_error(current, &err)?;
+            return Ok(0);
         }
+    };
+
+    if context.crosses_filesystem(&metadata) {
+        return Ok(0);
     }
 
     if metadata.is_file() {
@@ -138,15 +155,28 @@ fn traverse_directory(current: &Path, depth: u16, context: &TraversalContext) ->
         return Ok(0);
     }
 
+    if context.options.follow_symlinks
+        && !context.enter_directory(
+            current,
+            legacy::file_id_from_metadata(current, &metadata),
+            via_symlink,
+        )?
+    {
+        return Ok(0);
+    }
+    context.notify_directory(current);
+
     let mut total_size = 0u64;
     let mut file_count = 0u32;
     let mut dir_count = 0u32;
     let mut child_dirs: Vec<PathBuf> = Vec::new();
+    let ignore = context.enter_ignore_scope(ignore, current);
 
     let search_spec = current.join("*");
     let search_wide = to_wide_null(&search_spec);
     let mut find_data = MaybeUninit::<WIN32_FIND_DATAW>::uninit();
 
+    context.throttle(1);
     let maybe_handle = match open_search_handle(&search_wide, find_data.as_mut_ptr()) {
         Ok(handle) => handle,
         Err(io_err) => {
@@ -157,17 +187,23 @@ fn traverse_directory(current: &Path, depth: u16, context: &TraversalContext) ->
 
     {
         let Some(handle) = maybe_handle else {
-            return finalize_directory(current, depth, total_size, file_count, dir_count, context);
+            return finalize_directory(
+                current, &metadata, depth, total_size, file_count, dir_count, context,
+            );
         };
 
         let mut data = unsafe { find_data.assume_init() };
 
         loop {
+            if context.should_stop() {
+                break;
+            }
             handle_entry(
                 &data,
                 current,
                 depth,
                 context,
+                &ignore,
                 &mut total_size,
                 &mut file_count,
                 &mut dir_count,
@@ -204,14 +240,19 @@ fn traverse_directory(current: &Path, depth: u16, context: &TraversalContext) ->
 
     let subdir_total = AtomicU64::new(0);
     child_dirs.into_par_iter().try_for_each(|child_path| {
-        let size = traverse_directory(&child_path, depth + 1, context)?;
+        if context.should_stop() {
+            return Ok(());
+        }
+        let size = traverse_directory(&child_path, depth + 1, context, &ignore)?;
         subdir_total.fetch_add(size, Ordering::Relaxed);
         Ok::<(), io::Error>(())
     })?;
 
     let total_size = total_size.saturating_add(subdir_total.load(Ordering::Relaxed));
 
-    finalize_directory(current, depth, total_size, file_count, dir_count, context)
+    finalize_directory(
+        current, &metadata, depth, total_size, file_count, dir_count, context,
+    )
 }
 
 #[cfg(windows)]
@@ -294,6 +335,7 @@ fn is_empty_dir_error(err: &io::Error) -> bool {
 #[cfg(windows)]
 fn finalize_directory(
     current: &Path,
+    metadata: &fs::Metadata,
     depth: u16,
     total_size: u64,
     file_count: u32,
@@ -310,7 +352,9 @@ fn finalize_directory(
         size_bytes: total_size,
         file_count,
         dir_count,
-    };
+        ..DirectoryEntry::default()
+    }
+    .with_attributes(legacy::entry_attributes(metadata));
 
     context.insert_entry(entry)?;
     context.register_directory_progress();
@@ -325,6 +369,7 @@ fn handle_entry(
     parent: &Path,
     depth: u16,
     context: &TraversalContext,
+    ignore: &IgnoreStack,
     total_size: &mut u64,
     file_count: &mut u32,
     dir_count: &mut u32,
@@ -336,6 +381,7 @@ fn handle_entry(
     }
 
     let child_path = parent.join(&name);
+    context.throttle(1);
     let entry_metadata = match fs::symlink_metadata(&child_path) {
         Ok(meta) => meta,
         Err(err) => {
@@ -347,14 +393,36 @@ fn handle_entry(
     if entry_metadata.is_symlink() && !context.options.follow_symlinks {
         return Ok(());
     }
+    // A followed link is classified by its target; the directory itself is
+    // checked for cycles when it is walked.
+    let entry_metadata = match legacy::followed_metadata(&child_path, entry_metadata, context) {
+        Ok(meta) => meta,
+        Err(err) => {
+            context.record_error(&child_path, &err)?;
+            return Ok(());
+        }
+    };
 
-    if !context.options.cross_filesystem {
-        if let Some(root_dev) = context.root_device() {
-            let current_dev = legacy::get_device_id(&entry_metadata);
-            if current_dev != root_dev {
-                return Ok(());
-            }
+    if context.is_excluded(&child_path, entry_metadata.is_dir()) {
+        return Ok(());
+    }
+
+    if context.is_ignored(ignore, &child_path, entry_metadata.is_dir()) {
+        if entry_metadata.is_dir() && context.collapse_ignored() {
+            let collapsed = legacy::collapse_ignored_directory(
+                &child_path,
+                legacy::entry_attributes(&entry_metadata),
+                depth + 1,
+                context,
+            )?;
+            *total_size = total_size.saturating_add(collapsed);
+            *dir_count = dir_count.saturating_add(1);
         }
+        return Ok(());
+    }
+
+    if context.crosses_filesystem(&entry_metadata) {
+        return Ok(());
     }
 
     if entry_metadata.is_file() {
@@ -378,11 +446,17 @@ fn handle_entry(
                 size_bytes: file_size,
                 file_count: 0,
                 dir_count: 0,
-            };
+                ..DirectoryEntry::default()
+            }
+            .with_attributes(legacy::entry_attributes(&entry_metadata));
             context.insert_entry(entry)?;
         }
     } else if entry_metadata.is_dir() {
         *dir_count = dir_count.saturating_add(1);
+        if let Some(size) = context.reuse_completed(&child_path) {
+            *total_size = total_size.saturating_add(size);
+            return Ok(());
+        }
         let next_depth = depth + 1;
 
         if context.max_depth.is_some_and(|max| next_depth > max) {
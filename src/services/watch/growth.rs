@@ -0,0 +1,79 @@
+//! Ranking directories by how fast they grew over a sliding window.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+/// Bytes a directory gained over the tracking window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Growth {
+    pub path: String,
+    /// Net bytes added directly inside the directory during the window.
+    pub delta_bytes: i64,
+    /// `delta_bytes` spread over the window.
+    pub bytes_per_sec: f64,
+}
+
+/// Size changes of the last `window`, attributed to the directory that holds
+/// the changed file.
+#[derive(Debug)]
+pub struct GrowthTracker {
+    window: Duration,
+    samples: VecDeque<(Instant, String, i64)>,
+}
+
+impl GrowthTracker {
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Length of the sliding window.
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Note that `dir` changed by `delta` bytes at `at`.
+    pub fn record(&mut self, at: Instant, dir: &str, delta: i64) {
+        if delta != 0 {
+            self.samples.push_back((at, dir.to_string(), delta));
+        }
+    }
+
+    /// The `top` directories that grew the most in the window ending at `now`.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fastest(&mut self, now: Instant, top: usize) -> Vec<Growth> {
+        while self
+            .samples
+            .front()
+            .is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > self.window)
+        {
+            self.samples.pop_front();
+        }
+
+        let mut totals: HashMap<&str, i64> = HashMap::new();
+        for (_, dir, delta) in &self.samples {
+            *totals.entry(dir.as_str()).or_default() += delta;
+        }
+        let seconds = self.window.as_secs_f64().max(f64::EPSILON);
+        let mut growth: Vec<Growth> = totals
+            .into_iter()
+            .filter(|(_, delta)| *delta > 0)
+            .map(|(path, delta_bytes)| Growth {
+                path: path.to_string(),
+                delta_bytes,
+                bytes_per_sec: delta_bytes as f64 / seconds,
+            })
+            .collect();
+        growth.sort_by(|a, b| {
+            b.delta_bytes
+                .cmp(&a.delta_bytes)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        growth.truncate(top);
+        growth
+    }
+}
@@ -0,0 +1,128 @@
+//! Directory change events from Linux inotify.
+
+use super::FsEvent;
+use rustix::event::{PollFd, PollFlags, poll};
+use rustix::fd::OwnedFd;
+use rustix::fs::inotify::{self, CreateFlags, ReadFlags, WatchFlags};
+use rustix::io::Errno;
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Events that can change the usage of a watched directory.
+const WATCH_FLAGS: WatchFlags = WatchFlags::CREATE
+    .union(WatchFlags::DELETE)
+    .union(WatchFlags::MODIFY)
+    .union(WatchFlags::CLOSE_WRITE)
+    .union(WatchFlags::MOVED_FROM)
+    .union(WatchFlags::MOVED_TO)
+    .union(WatchFlags::DELETE_SELF)
+    .union(WatchFlags::MOVE_SELF)
+    .union(WatchFlags::ONLYDIR)
+    .union(WatchFlags::DONT_FOLLOW)
+    .union(WatchFlags::EXCL_UNLINK);
+
+const EVENT_BUFFER_BYTES: usize = 64 * 1024;
+
+/// An inotify instance with one watch per directory of the tree.
+pub(crate) struct Inotify {
+    watches: Arc<Watches>,
+    root: PathBuf,
+    buf: Vec<MaybeUninit<u8>>,
+}
+
+/// The watch side of an inotify instance, shared with scan workers so they
+/// can watch each directory before listing it.
+pub(crate) struct Watches {
+    fd: OwnedFd,
+    /// Directory each watch descriptor was added for.
+    dirs: Mutex<HashMap<i32, PathBuf>>,
+}
+
+impl Watches {
+    /// Start watching `dir`. Watching a directory again, e.g. after it was
+    /// moved, updates the path its events are reported under.
+    pub(crate) fn watch(&self, dir: &Path) -> io::Result<()> {
+        let wd = inotify::add_watch(&self.fd, dir, WATCH_FLAGS)?;
+        self.dirs.lock().unwrap().insert(wd, dir.to_path_buf());
+        Ok(())
+    }
+}
+
+impl Inotify {
+    pub(crate) fn new(root: &Path) -> io::Result<Self> {
+        let fd = inotify::init(CreateFlags::CLOEXEC | CreateFlags::NONBLOCK)?;
+        Ok(Self {
+            watches: Arc::new(Watches {
+                fd,
+                dirs: Mutex::new(HashMap::new()),
+            }),
+            root: root.to_path_buf(),
+            buf: vec![MaybeUninit::uninit(); EVENT_BUFFER_BYTES],
+        })
+    }
+
+    /// Handle for adding watches from other threads.
+    pub(crate) fn watches(&self) -> Arc<Watches> {
+        Arc::clone(&self.watches)
+    }
+
+    /// Wait up to `timeout` for events and append them to `events`.
+    pub(crate) fn read(&mut self, timeout: Duration, events: &mut Vec<FsEvent>) -> io::Result<()> {
+        let timeout_ms = i32::try_from(timeout.as_millis()).unwrap_or(i32::MAX);
+        let mut fds = [PollFd::new(&self.watches.fd, PollFlags::IN)];
+        match poll(&mut fds, timeout_ms) {
+            Ok(0) | Err(Errno::INTR) => return Ok(()),
+            Ok(_) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let Self { watches, root, buf } = self;
+        let mut reader = inotify::Reader::new(&watches.fd, buf);
+        let mut dirs = watches.dirs.lock().unwrap();
+        loop {
+            let event = match reader.next() {
+                Ok(event) => event,
+                Err(Errno::AGAIN | Errno::INTR) => return Ok(()),
+                Err(err) => return Err(err.into()),
+            };
+            let flags = event.events();
+
+            if flags.contains(ReadFlags::QUEUE_OVERFLOW) {
+                events.push(FsEvent::Overflow);
+                continue;
+            }
+            let Some(dir) = dirs.get(&event.wd()) else {
+                continue;
+            };
+            if flags.intersects(ReadFlags::IGNORED | ReadFlags::DELETE_SELF | ReadFlags::MOVE_SELF)
+            {
+                if dir == root {
+                    events.push(FsEvent::RootRemoved);
+                }
+                if flags.contains(ReadFlags::IGNORED) {
+                    dirs.remove(&event.wd());
+                }
+                continue;
+            }
+            let Some(name) = event.file_name() else {
+                continue;
+            };
+            let path = dir.join(OsStr::from_bytes(name.to_bytes()));
+            let is_dir = flags.contains(ReadFlags::ISDIR);
+
+            if flags.intersects(ReadFlags::DELETE | ReadFlags::MOVED_FROM) {
+                events.push(FsEvent::Removed(path));
+            } else if is_dir && flags.intersects(ReadFlags::CREATE | ReadFlags::MOVED_TO) {
+                events.push(FsEvent::DirectoryAdded(path));
+            } else if !is_dir {
+                events.push(FsEvent::Changed(path));
+            }
+        }
+    }
+}
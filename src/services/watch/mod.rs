@@ -0,0 +1,362 @@
+//! Watch mode: a live usage tree kept current from filesystem events.
+//!
+//! [`Watch::start`] scans the root once, subscribing to change events for
+//! every directory just before the scan lists it, so changes made while the
+//! scan runs are picked up too. Each [`Watch::poll`] applies the pending
+//! events: changed files are stat'ed again and their size difference is added
+//! to every ancestor, new directories are scanned and grafted in, and removed
+//! entries are subtracted. When the kernel drops events the whole tree is
+//! scanned again.
+//!
+//! Events come from inotify and are only available on Linux. Each directory
+//! takes one watch, so very large trees may need a higher
+//! `fs.inotify.max_user_watches`.
+
+pub mod growth;
+#[cfg(target_os = "linux")]
+mod inotify;
+pub mod tree;
+
+pub use growth::{Growth, GrowthTracker};
+pub use tree::LiveTree;
+
+use crate::io::snapshot::write_sorted_snapshot;
+use crate::models::{DirectoryEntry, ErrorItem, SkippedMount, SnapshotMeta};
+use crate::services::filter::ExcludeFilter;
+use crate::services::traverse::StrategyKind;
+use crate::services::traverse::legacy::{entry_attributes, normalize_path};
+use crate::{Error, HardlinkPolicy, Result, ScanOptions, SizeBasis};
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+/// A change reported for a watched directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsEvent {
+    /// A file was created, written or moved in.
+    Changed(PathBuf),
+    /// A directory was created or moved in.
+    DirectoryAdded(PathBuf),
+    /// An entry was deleted or moved away.
+    Removed(PathBuf),
+    /// Events were dropped; the tree must be rebuilt.
+    Overflow,
+    /// The watched root itself was deleted or moved.
+    RootRemoved,
+}
+
+/// A scanned tree subscribed to change events.
+pub struct Watch {
+    root: PathBuf,
+    opts: ScanOptions,
+    started_at: SystemTime,
+    strategy: StrategyKind,
+    tree: LiveTree,
+    errors: Vec<ErrorItem>,
+    skipped_mounts: Vec<SkippedMount>,
+    growth: GrowthTracker,
+    exclude: ExcludeFilter,
+    events: inotify::Inotify,
+    watcher: Arc<Watcher>,
+}
+
+/// Adds the watches for the directories scans walk, counting those that
+/// could not be watched.
+struct Watcher {
+    watches: Arc<inotify::Watches>,
+    unwatched: AtomicUsize,
+}
+
+impl Watcher {
+    fn watch(&self, dir: &Path) {
+        if let Err(err) = self.watches.watch(dir)
+            && self.unwatched.fetch_add(1, Ordering::Relaxed) == 0
+        {
+            log::warn!(
+                "Cannot watch {}: {err}; raise fs.inotify.max_user_watches if the limit was reached",
+                dir.display()
+            );
+        }
+    }
+}
+
+impl Watch {
+    /// Scan `root` with `opts` and subscribe to changes below it, ranking
+    /// growth over `window`.
+    pub fn start(root: &Path, opts: &ScanOptions, window: Duration) -> Result<Self> {
+        let events = inotify::Inotify::new(root)?;
+        let watcher = Arc::new(Watcher {
+            watches: events.watches(),
+            unwatched: AtomicUsize::new(0),
+        });
+        let notifier = Arc::clone(&watcher);
+        let chained = opts.directory_notifier.clone();
+        let opts = ScanOptions {
+            max_depth: None,
+            resume_from: None,
+            baseline: None,
+            // Every scan of the watch, including rescans and new directories,
+            // watches a directory before listing it.
+            directory_notifier: Some(Arc::new(move |dir: &Path| {
+                notifier.watch(dir);
+                if let Some(chained) = &chained {
+                    chained(dir);
+                }
+            })),
+            ..opts.clone()
+        };
+        let started_at = SystemTime::now();
+        let summary = crate::scan_summary(root, &opts)?;
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            tree: LiveTree::from_entries(
+                &normalize_path(root),
+                opts.hardlink_policy,
+                summary.entries,
+            ),
+            exclude: ExcludeFilter::new(&opts.excludes),
+            opts,
+            started_at,
+            strategy: summary.strategy,
+            errors: summary.errors,
+            skipped_mounts: summary.skipped_mounts,
+            growth: GrowthTracker::new(window),
+            events,
+            watcher,
+        })
+    }
+
+    /// The live tree.
+    #[must_use]
+    pub fn tree(&self) -> &LiveTree {
+        &self.tree
+    }
+
+    /// Directories that could not be watched, typically because the inotify
+    /// watch limit was reached. Changes inside them go unnoticed.
+    #[must_use]
+    pub fn unwatched_dirs(&self) -> usize {
+        self.watcher.unwatched.load(Ordering::Relaxed)
+    }
+
+    /// The `top` directories that grew the most over the tracking window.
+    pub fn fastest_growing(&mut self, top: usize) -> Vec<Growth> {
+        self.growth.fastest(Instant::now(), top)
+    }
+
+    /// Length of the window growth is ranked over.
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        self.growth.window()
+    }
+
+    /// Wait up to `timeout` for changes and apply them, returning how many
+    /// events were processed.
+    pub fn poll(&mut self, timeout: Duration) -> Result<usize> {
+        let mut events = Vec::new();
+        self.events.read(timeout, &mut events)?;
+        let now = Instant::now();
+
+        // A file written many times in one batch only needs one stat.
+        let mut refreshed: HashSet<PathBuf> = HashSet::new();
+        for event in &events {
+            match event {
+                FsEvent::Changed(path) => {
+                    if refreshed.insert(path.clone()) {
+                        self.refresh_file(path, now);
+                    }
+                }
+                FsEvent::DirectoryAdded(path) => {
+                    refreshed.retain(|changed| !changed.starts_with(path));
+                    self.add_directory(path, now)?;
+                }
+                FsEvent::Removed(path) => {
+                    refreshed.retain(|changed| !changed.starts_with(path));
+                    self.skipped_mounts
+                        .retain(|mount| !Path::new(&mount.path).starts_with(path));
+                    let delta = self.tree.remove(&normalize_path(path));
+                    self.record_growth(path, delta, now);
+                }
+                FsEvent::Overflow => {
+                    log::warn!(
+                        "Change events were dropped; rescanning {}",
+                        self.root.display()
+                    );
+                    self.watcher.unwatched.store(0, Ordering::Relaxed);
+                    let summary = crate::scan_summary(&self.root, &self.opts)?;
+                    self.skipped_mounts = summary.skipped_mounts;
+                    self.replace_tree(summary.entries, summary.errors);
+                    refreshed.clear();
+                }
+                FsEvent::RootRemoved => {
+                    return Err(Error::Io(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("{} was removed or moved", self.root.display()),
+                    )));
+                }
+            }
+        }
+        Ok(events.len())
+    }
+
+    /// Write the live tree to `snapshot`, replacing it atomically.
+    pub fn checkpoint(&self, snapshot: &str) -> Result<()> {
+        let meta = SnapshotMeta {
+            scan_root: self.tree.root().to_string(),
+            started_at: format!("{:?}", self.started_at),
+            finished_at: format!("{:?}", SystemTime::now()),
+            size_basis: match self.opts.basis {
+                SizeBasis::Physical => "physical".to_string(),
+                SizeBasis::Logical => "logical".to_string(),
+            },
+            hardlink_policy: match self.opts.hardlink_policy {
+                HardlinkPolicy::Dedupe => "dedupe".to_string(),
+                HardlinkPolicy::Count => "count".to_string(),
+            },
+            excludes: self.opts.excludes.clone(),
+            ignore_files: self.opts.ignore_files.as_str().to_string(),
+            max_depth: self.opts.max_depth,
+            follow_symlinks: self.opts.follow_symlinks,
+            cross_filesystem: self.opts.cross_filesystem,
+            strategy: self.strategy.to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: self.skipped_mounts.clone(),
+        };
+        let entries: Vec<&DirectoryEntry> = self.tree.entries().collect();
+
+        let tmp_path = format!("{snapshot}.tmp");
+        write_sorted_snapshot(&tmp_path, &meta, &entries, &self.errors, &[])?;
+        fs::rename(&tmp_path, snapshot)?;
+        Ok(())
+    }
+
+    fn replace_tree(&mut self, entries: Vec<DirectoryEntry>, errors: Vec<ErrorItem>) {
+        self.tree = LiveTree::from_entries(
+            &normalize_path(&self.root),
+            self.opts.hardlink_policy,
+            entries,
+        );
+        self.errors = errors;
+    }
+
+    /// Stat `path` again and record its current size.
+    fn refresh_file(&mut self, path: &Path, now: Instant) {
+        let normalized = normalize_path(path);
+        let delta = match fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.is_file() => {
+                if self.exclude.is_excluded(&normalized, false) {
+                    return;
+                }
+                let size = file_size(&metadata, self.opts.basis);
+                self.tree
+                    .set_file(&normalized, size, entry_attributes(&metadata))
+            }
+            Ok(_) => return,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => self.tree.remove(&normalized),
+            Err(err) => {
+                log::debug!("Cannot stat {}: {err}", path.display());
+                return;
+            }
+        };
+        self.record_growth(path, delta, now);
+    }
+
+    /// Scan a directory that appeared and graft it into the tree.
+    fn add_directory(&mut self, path: &Path, now: Instant) -> Result<()> {
+        if self.exclude.is_excluded(&normalize_path(path), true) {
+            return Ok(());
+        }
+        // The scan watches each directory before listing it, so files
+        // created meanwhile are not missed.
+        let summary = match crate::scan_summary(path, &self.opts) {
+            Ok(summary) => summary,
+            Err(Error::InvalidInput(_) | Error::Interrupted { .. }) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let delta = self.tree.insert_subtree(summary.entries);
+        self.errors.extend(summary.errors);
+        self.skipped_mounts.extend(summary.skipped_mounts);
+        self.skipped_mounts.sort_by(|a, b| a.path.cmp(&b.path));
+        self.record_growth(path, delta, now);
+        Ok(())
+    }
+
+    /// Attribute `delta` to the directory holding `path`.
+    fn record_growth(&mut self, path: &Path, delta: i64, now: Instant) {
+        if let Some(parent) = path.parent() {
+            self.growth.record(now, &normalize_path(parent), delta);
+        }
+    }
+}
+
+impl std::fmt::Debug for Watch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watch")
+            .field("root", &self.root)
+            .field("entries", &self.tree.len())
+            .field("unwatched", &self.unwatched_dirs())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(unix)]
+fn file_size(metadata: &fs::Metadata, basis: SizeBasis) -> u64 {
+    use crate::services::size;
+    match basis {
+        SizeBasis::Logical => size::logical_size(metadata),
+        SizeBasis::Physical => size::physical_size_from_metadata(metadata),
+    }
+}
+
+#[cfg(not(unix))]
+fn file_size(metadata: &fs::Metadata, _basis: SizeBasis) -> u64 {
+    crate::services::size::logical_size(metadata)
+}
+
+/// Stand-in for platforms without inotify; watch mode cannot start there.
+#[cfg(not(target_os = "linux"))]
+mod inotify {
+    use super::FsEvent;
+    use std::io;
+    use std::path::Path;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    pub(crate) struct Inotify;
+
+    pub(crate) struct Watches;
+
+    impl Watches {
+        pub(crate) fn watch(&self, _dir: &Path) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Inotify {
+        pub(crate) fn new(_root: &Path) -> io::Result<Self> {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "watch mode relies on inotify and is only available on Linux",
+            ))
+        }
+
+        pub(crate) fn watches(&self) -> Arc<Watches> {
+            Arc::new(Watches)
+        }
+
+        pub(crate) fn read(
+            &mut self,
+            _timeout: Duration,
+            _events: &mut Vec<FsEvent>,
+        ) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! In-memory usage tree kept current by filesystem events.
+
+use crate::HardlinkPolicy;
+use crate::models::{DirectoryEntry, EntryAttributes, EntryKind};
+use std::collections::{BTreeMap, HashMap};
+
+/// Every entry of a scanned tree, keyed by path, with directory totals that
+/// are adjusted as files change instead of recomputed.
+#[derive(Debug)]
+pub struct LiveTree {
+    root: String,
+    hardlink_policy: HardlinkPolicy,
+    entries: BTreeMap<String, DirectoryEntry>,
+    /// Path that carries the size of each multiply-linked file under dedupe.
+    link_owners: HashMap<(u64, u64), String>,
+}
+
+impl LiveTree {
+    /// Build the tree from the entries of a full scan of `root`.
+    #[must_use]
+    pub fn from_entries(
+        root: &str,
+        hardlink_policy: HardlinkPolicy,
+        entries: Vec<DirectoryEntry>,
+    ) -> Self {
+        let mut tree = Self {
+            root: root.to_string(),
+            hardlink_policy,
+            entries: BTreeMap::new(),
+            link_owners: HashMap::new(),
+        };
+        for entry in entries {
+            tree.track_link(&entry);
+            tree.entries.insert(entry.path.clone(), entry);
+        }
+        tree
+    }
+
+    /// Path of the watched root.
+    #[must_use]
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    /// Number of entries in the tree.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the tree holds no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The entry recorded at `path`.
+    #[must_use]
+    pub fn get(&self, path: &str) -> Option<&DirectoryEntry> {
+        self.entries.get(path)
+    }
+
+    /// Total size of the watched root.
+    #[must_use]
+    pub fn total_size(&self) -> u64 {
+        self.get(&self.root).map_or(0, |root| root.size_bytes)
+    }
+
+    /// All entries, in path order.
+    pub fn entries(&self) -> impl Iterator<Item = &DirectoryEntry> {
+        self.entries.values()
+    }
+
+    /// Record the current size and attributes of the file at `path`, returning
+    /// the change in bytes. Files whose directory is not in the tree are ignored.
+    pub fn set_file(&mut self, path: &str, size_bytes: u64, attrs: EntryAttributes) -> i64 {
+        let Some(parent) = parent_of(path) else {
+            return 0;
+        };
+        let Some(parent_depth) = self.directory_depth(parent) else {
+            return 0;
+        };
+        let size_bytes = self.counted_size(path, size_bytes, &attrs);
+        // A directory replaced by a file goes away with everything below it.
+        let replaced = match self.entries.get(path) {
+            Some(existing) if existing.kind != EntryKind::File => self.detach(path),
+            _ => 0,
+        };
+
+        let old_size = if let Some(existing) = self.entries.get_mut(path) {
+            let old = existing.size_bytes;
+            let mut updated = std::mem::take(existing).with_attributes(attrs);
+            updated.size_bytes = size_bytes;
+            *existing = updated;
+            old
+        } else {
+            let entry = DirectoryEntry {
+                path: path.to_string(),
+                parent_path: Some(parent.to_string()),
+                depth: parent_depth + 1,
+                size_bytes,
+                ..DirectoryEntry::default()
+            }
+            .with_attributes(attrs);
+            self.entries.insert(path.to_string(), entry);
+            if let Some(parent) = self.entries.get_mut(parent) {
+                parent.file_count = parent.file_count.saturating_add(1);
+            }
+            replaced
+        };
+
+        let delta = signed(size_bytes) - signed(old_size);
+        self.propagate(parent, delta);
+        delta
+    }
+
+    /// Replace whatever is recorded at the root of `subtree` with the entries
+    /// of a fresh scan of it, returning the change in bytes.
+    pub fn insert_subtree(&mut self, subtree: Vec<DirectoryEntry>) -> i64 {
+        let Some(top) = subtree.iter().min_by_key(|entry| entry.depth) else {
+            return 0;
+        };
+        let top_path = top.path.clone();
+        let Some(parent) = parent_of(&top_path).map(str::to_string) else {
+            return 0;
+        };
+        let Some(parent_depth) = self.directory_depth(&parent) else {
+            return 0;
+        };
+
+        let removed = self.detach(&top_path);
+        let top_depth = top.depth;
+        let mut added = 0u64;
+        for mut entry in subtree {
+            entry.depth = entry.depth - top_depth + parent_depth + 1;
+            if entry.path == top_path {
+                added = entry.size_bytes;
+                entry.parent_path = Some(parent.clone());
+            }
+            self.track_link(&entry);
+            self.entries.insert(entry.path.clone(), entry);
+        }
+        let top_is_dir = self
+            .entries
+            .get(&top_path)
+            .is_some_and(|top| top.kind == EntryKind::Directory);
+        if let Some(parent) = self.entries.get_mut(&parent) {
+            if top_is_dir {
+                parent.dir_count = parent.dir_count.saturating_add(1);
+            } else {
+                parent.file_count = parent.file_count.saturating_add(1);
+            }
+        }
+
+        let delta = signed(added) - signed(removed);
+        self.propagate(&parent, delta);
+        delta
+    }
+
+    /// Drop `path` and everything below it, returning the change in bytes.
+    pub fn remove(&mut self, path: &str) -> i64 {
+        let Some(parent) = parent_of(path).map(str::to_string) else {
+            return 0;
+        };
+        let removed = self.detach(path);
+        let delta = -signed(removed);
+        self.propagate(&parent, delta);
+        delta
+    }
+
+    /// Remove `path` and its descendants without touching ancestor totals,
+    /// returning the size it carried.
+    fn detach(&mut self, path: &str) -> u64 {
+        let Some(entry) = self.entries.remove(path) else {
+            return 0;
+        };
+        let prefix = format!("{path}/");
+        let descendants: Vec<String> = self
+            .entries
+            .range(prefix.clone()..)
+            .take_while(|(key, _)| key.starts_with(&prefix))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in descendants {
+            self.entries.remove(&key);
+        }
+        self.link_owners
+            .retain(|_, owner| owner.as_str() != path && !owner.starts_with(&prefix));
+
+        if let Some(parent) = entry
+            .parent_path
+            .as_deref()
+            .and_then(|parent| self.entries.get_mut(parent))
+        {
+            if entry.kind == EntryKind::Directory {
+                parent.dir_count = parent.dir_count.saturating_sub(1);
+            } else {
+                parent.file_count = parent.file_count.saturating_sub(1);
+            }
+        }
+        entry.size_bytes
+    }
+
+    /// Apply `delta` to `dir` and each of its ancestors.
+    fn propagate(&mut self, dir: &str, delta: i64) {
+        if delta == 0 {
+            return;
+        }
+        let mut current = Some(dir.to_string());
+        while let Some(path) = current.take() {
+            let Some(entry) = self.entries.get_mut(&path) else {
+                break;
+            };
+            entry.size_bytes = entry.size_bytes.saturating_add_signed(delta);
+            if path != self.root {
+                current.clone_from(&entry.parent_path);
+            }
+        }
+    }
+
+    fn directory_depth(&self, path: &str) -> Option<u16> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.kind == EntryKind::Directory)
+            .map(|entry| entry.depth)
+    }
+
+    /// Size `path` contributes, counting a multiply-linked file only at the
+    /// first path it was seen under when deduplicating.
+    fn counted_size(&mut self, path: &str, size_bytes: u64, attrs: &EntryAttributes) -> u64 {
+        if self.hardlink_policy == HardlinkPolicy::Count || attrs.nlink.is_none_or(|n| n <= 1) {
+            return size_bytes;
+        }
+        let (Some(device), Some(inode)) = (attrs.device, attrs.inode) else {
+            return size_bytes;
+        };
+        let owner = self
+            .link_owners
+            .entry((device, inode))
+            .or_insert_with(|| path.to_string());
+        if owner == path { size_bytes } else { 0 }
+    }
+
+    fn track_link(&mut self, entry: &DirectoryEntry) {
+        if entry.kind == EntryKind::File
+            && entry.size_bytes > 0
+            && entry.nlink.is_some_and(|n| n > 1)
+            && let (Some(device), Some(inode)) = (entry.device, entry.inode)
+        {
+            self.link_owners
+                .entry((device, inode))
+                .or_insert_with(|| entry.path.clone());
+        }
+    }
+}
+
+/// Parent directory of a normalized path.
+pub(crate) fn parent_of(path: &str) -> Option<&str> {
+    match path.rsplit_once('/') {
+        Some(("", _)) if path.len() > 1 => Some("/"),
+        Some((parent, _)) if !parent.is_empty() => Some(parent),
+        _ => None,
+    }
+}
+
+#[allow(clippy::cast_possible_wrap)]
+fn signed(bytes: u64) -> i64 {
+    bytes as i64
+}
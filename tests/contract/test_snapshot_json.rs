@@ -4,7 +4,7 @@
 mod tests {
     use dua::cli::output::format_json;
     use dua::io::snapshot::{read_snapshot, write_snapshot};
-    use dua::models::{DirectoryEntry, ProgressSnapshot, SnapshotMeta};
+    use dua::models::{DirectoryEntry, EntryKind, ProgressSnapshot, SkippedMount, SnapshotMeta};
     use dua::{StrategyKind, Summary};
     use std::time::SystemTime;
     use tempfile::NamedTempFile;
@@ -21,7 +21,15 @@ mod tests {
             size_basis: "physical".to_string(),
             hardlink_policy: "dedupe".to_string(),
             excludes: vec![],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
             strategy: "posix".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
         };
 
         let entries = vec![DirectoryEntry {
@@ -31,6 +39,7 @@ mod tests {
             size_bytes: 5000,
             file_count: 3,
             dir_count: 1,
+            ..Default::default()
         }];
 
         write_snapshot(snapshot_path, &meta, &entries, &[]).unwrap();
@@ -39,6 +48,15 @@ mod tests {
 
         let meta_json = serde_json::to_value(&meta_out).unwrap();
         assert_eq!(meta_json["strategy"], "posix");
+        assert_eq!(meta_json["partial"], false);
+
+        // Footers written before the key was renamed still read as partial.
+        let mut legacy = meta_json.clone();
+        let partial = legacy.as_object_mut().unwrap().remove("partial").unwrap();
+        assert_eq!(partial, false);
+        legacy["incomplete"] = true.into();
+        let legacy: SnapshotMeta = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.incomplete);
 
         // Serialize to JSON
         let json = serde_json::to_string(&entries).unwrap();
@@ -51,6 +69,13 @@ mod tests {
         assert!(json.contains("depth"));
         assert!(json.contains("parent_path"));
 
+        // Rows serialized before the kind was recorded are not taken for
+        // directories.
+        let mut row = serde_json::to_value(&entries[0]).unwrap();
+        row.as_object_mut().unwrap().remove("kind");
+        let row: DirectoryEntry = serde_json::from_value(row).unwrap();
+        assert_eq!(row.kind, EntryKind::Other);
+
         let summary = Summary {
             root: meta_out.scan_root.clone(),
             entries: entries.clone(),
@@ -66,6 +91,11 @@ mod tests {
                 recent_throughput_bytes_per_sec: Some(512),
             }],
             entry_count: entries.len() as u64,
+            skipped_mounts: vec![SkippedMount {
+                path: "/test/home".to_string(),
+                device: 42,
+                fs_type: "ext4".to_string(),
+            }],
         };
 
         let summary_json = format_json(&summary, &summary.entries);
@@ -79,5 +109,10 @@ mod tests {
         assert!(snapshot.get("processed_bytes").is_some());
         assert!(snapshot.get("estimated_completion_ratio").is_some());
         assert!(snapshot.get("recent_throughput_bytes_per_sec").is_some());
+
+        let mount = &summary_value["skipped_mounts"][0];
+        assert_eq!(mount["path"], "/test/home");
+        assert_eq!(mount["device"], 42);
+        assert_eq!(mount["fs_type"], "ext4");
     }
 }
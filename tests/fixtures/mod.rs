@@ -1,5 +1,6 @@
 //! Test fixtures for deterministic testing
 
+use dua::io::snapshot::{EntryFilter, SnapshotReader};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
@@ -18,6 +19,32 @@ pub fn write_file_sync<P: AsRef<Path>, C: AsRef<[u8]>>(
     Ok(())
 }
 
+/// Create `dirs` directories `dNN`, each holding `b.bin` (50 bytes) and
+/// `inner/a.bin` (100 bytes plus the directory number)
+pub fn create_numbered_tree(root: &Path, dirs: usize) -> std::io::Result<()> {
+    for dir in 0..dirs {
+        let nested = root.join(format!("d{dir:02}/inner"));
+        fs::create_dir_all(&nested)?;
+        write_file_sync(nested.join("a.bin"), vec![1u8; 100 + dir])?;
+        write_file_sync(root.join(format!("d{dir:02}/b.bin")), vec![2u8; 50])?;
+    }
+    Ok(())
+}
+
+/// Read the entries of a snapshot as path-sorted
+/// `(path, size_bytes, file_count, dir_count)` rows
+pub fn read_sorted_entries(snapshot: &Path) -> Vec<(String, u64, u32, u32)> {
+    let reader = SnapshotReader::open(snapshot).unwrap();
+    let mut entries: Vec<_> = reader
+        .read_entries(EntryFilter::new())
+        .unwrap()
+        .into_iter()
+        .map(|e| (e.path, e.size_bytes, e.file_count, e.dir_count))
+        .collect();
+    entries.sort();
+    entries
+}
+
 /// Create a simple test directory structure
 #[allow(dead_code)]
 pub fn create_simple_fixture(base: &Path) -> std::io::Result<PathBuf> {
@@ -15,8 +15,32 @@ mod test_snapshot_roundtrip;
 #[cfg(test)]
 mod test_snapshot_errors;
 
+#[cfg(test)]
+mod test_snapshot_reader;
+
+#[cfg(test)]
+mod test_snapshot_sorted;
+
 #[cfg(test)]
 mod test_perf_smoke;
 
 #[cfg(test)]
 mod test_resilience;
+
+#[cfg(test)]
+mod test_resume;
+
+#[cfg(test)]
+mod test_baseline;
+
+#[cfg(test)]
+mod test_watch;
+
+#[cfg(test)]
+mod test_mounts;
+
+#[cfg(test)]
+mod test_symlinks;
+
+#[cfg(test)]
+mod test_hardlinks;
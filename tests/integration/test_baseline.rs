@@ -0,0 +1,146 @@
+//! Incremental rescans against a baseline snapshot
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use crate::fixtures::{create_numbered_tree, read_sorted_entries, write_file_sync};
+    use dua::services::traverse::StrategyKind;
+    use dua::{ScanOptions, SizeBasis};
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn size_of(entries: &[(String, u64, u32, u32)], path: &Path) -> u64 {
+        let path = path.to_string_lossy();
+        entries
+            .iter()
+            .find(|(p, _, _, _)| *p == path)
+            .map_or_else(|| panic!("{path} missing"), |(_, size, _, _)| *size)
+    }
+
+    #[test]
+    fn test_baseline_reuses_unchanged_directories() {
+        for strategy in [StrategyKind::Legacy, StrategyKind::PosixOptimized] {
+            let temp_dir = TempDir::new().unwrap();
+            let root = temp_dir.path().join("data");
+            create_numbered_tree(&root, 4).unwrap();
+            let opts = ScanOptions {
+                basis: SizeBasis::Logical,
+                strategy_override: Some(strategy),
+                ..Default::default()
+            };
+            let baseline = temp_dir.path().join("baseline.parquet");
+            let rescan = temp_dir.path().join("rescan.parquet");
+            let fresh = temp_dir.path().join("fresh.parquet");
+            dua::scan_to_snapshot(&root, &opts, baseline.to_str().unwrap()).unwrap();
+
+            // Let the coarse filesystem clock tick past the baseline's mtimes.
+            thread::sleep(Duration::from_millis(50));
+            // Adding and removing children updates the directories' mtimes.
+            write_file_sync(root.join("d00/new.bin"), vec![3u8; 1000]).unwrap();
+            fs::remove_file(root.join("d01/inner/a.bin")).unwrap();
+            fs::remove_dir(root.join("d01/inner")).unwrap();
+            fs::create_dir(root.join("d02/inner/deeper")).unwrap();
+            write_file_sync(root.join("d02/inner/deeper/c.bin"), vec![4u8; 7]).unwrap();
+            // Rewriting a file in place leaves its directory's mtime alone.
+            write_file_sync(root.join("d03/b.bin"), vec![2u8; 5000]).unwrap();
+
+            let with_baseline = ScanOptions {
+                baseline: Some(baseline.clone()),
+                ..opts.clone()
+            };
+            dua::scan_to_snapshot(&root, &with_baseline, rescan.to_str().unwrap()).unwrap();
+            dua::scan_to_snapshot(&root, &opts, fresh.to_str().unwrap()).unwrap();
+
+            let rescanned = read_sorted_entries(&rescan);
+            let mut expected = read_sorted_entries(&fresh);
+            // The in-place rewrite inside unchanged d3 is served from the baseline.
+            let grown = 5000 - 50;
+            for (path, size, _, _) in &mut expected {
+                if [&root, &root.join("d03"), &root.join("d03/b.bin")]
+                    .iter()
+                    .any(|p| *path == p.to_string_lossy())
+                {
+                    *size -= grown;
+                }
+            }
+            assert_eq!(rescanned, expected, "{strategy}");
+            assert_eq!(size_of(&rescanned, &root.join("d00/new.bin")), 1000);
+            assert_eq!(size_of(&rescanned, &root.join("d03/b.bin")), 50);
+        }
+    }
+
+    #[test]
+    fn test_baseline_rejects_mismatched_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        create_numbered_tree(&root, 4).unwrap();
+        let baseline = temp_dir.path().join("baseline.parquet");
+        let physical = ScanOptions::default();
+        dua::scan_to_snapshot(&root, &physical, baseline.to_str().unwrap()).unwrap();
+
+        let logical = ScanOptions {
+            basis: SizeBasis::Logical,
+            baseline: Some(baseline.clone()),
+            ..Default::default()
+        };
+        let target = temp_dir.path().join("rescan.parquet");
+        let err = dua::scan_to_snapshot(&root, &logical, target.to_str().unwrap())
+            .expect_err("basis mismatch");
+        assert!(matches!(err, dua::Error::InvalidInput(_)), "{err:?}");
+
+        for other in [
+            ScanOptions {
+                max_depth: Some(1),
+                ..physical.clone()
+            },
+            ScanOptions {
+                follow_symlinks: true,
+                ..physical.clone()
+            },
+            ScanOptions {
+                cross_filesystem: true,
+                ..physical.clone()
+            },
+        ] {
+            let other = ScanOptions {
+                baseline: Some(baseline.clone()),
+                ..other
+            };
+            let err = dua::scan_to_snapshot(&root, &other, target.to_str().unwrap())
+                .expect_err("settings mismatch");
+            assert!(matches!(err, dua::Error::InvalidInput(_)), "{err:?}");
+        }
+
+        let both = ScanOptions {
+            resume_from: Some(baseline.clone()),
+            baseline: Some(baseline.clone()),
+            ..Default::default()
+        };
+        let err = dua::scan_to_snapshot(&root, &both, target.to_str().unwrap())
+            .expect_err("resume and baseline together");
+        assert!(matches!(err, dua::Error::InvalidInput(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_baseline_in_place_keeps_snapshot_readable() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        create_numbered_tree(&root, 4).unwrap();
+        let snapshot = temp_dir.path().join("scan.parquet");
+        let opts = ScanOptions::default();
+        dua::scan_to_snapshot(&root, &opts, snapshot.to_str().unwrap()).unwrap();
+        let before = read_sorted_entries(&snapshot);
+
+        let with_baseline = ScanOptions {
+            baseline: Some(snapshot.clone()),
+            ..opts
+        };
+        dua::scan_to_snapshot(&root, &with_baseline, snapshot.to_str().unwrap()).unwrap();
+
+        assert_eq!(read_sorted_entries(&snapshot), before);
+        assert!(!temp_dir.path().join("scan.parquet.baseline.tmp").exists());
+    }
+}
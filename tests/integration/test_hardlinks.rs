@@ -0,0 +1,153 @@
+//! Attribution of hardlinked files
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use crate::fixtures::write_file_sync;
+    use dua::io::snapshot::{EntryFilter, SnapshotReader};
+    use dua::services::hardlinks::read_hardlink_report;
+    use dua::{DirectoryEntry, ScanOptions, SizeBasis, StrategyKind};
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    const STRATEGIES: [StrategyKind; 2] = [StrategyKind::PosixOptimized, StrategyKind::Legacy];
+
+    fn options(strategy: StrategyKind) -> ScanOptions {
+        ScanOptions {
+            basis: SizeBasis::Logical,
+            strategy_override: Some(strategy),
+            ..ScanOptions::default()
+        }
+    }
+
+    fn size(entries: &[DirectoryEntry], path: &Path) -> u64 {
+        let path = path.to_string_lossy();
+        entries
+            .iter()
+            .find(|e| e.path == path)
+            .unwrap_or_else(|| panic!("no entry for {path}"))
+            .size_bytes
+    }
+
+    /// Links to one file spread over many directories, created in reverse so
+    /// that directory order on disk does not match path order.
+    fn linked_tree(root: &Path) {
+        for i in (0..24).rev() {
+            let dir = root.join(format!("snap.{i:02}/data"));
+            fs::create_dir_all(&dir).unwrap();
+            write_file_sync(dir.join(format!("own.{i}")), vec![0u8; 10]).unwrap();
+        }
+        let original = root.join("snap.23/data/blob");
+        write_file_sync(&original, vec![0u8; 5_000]).unwrap();
+        for i in 0..23 {
+            fs::hard_link(&original, root.join(format!("snap.{i:02}/data/blob"))).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_linked_bytes_go_to_the_first_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("backups");
+        linked_tree(&root);
+
+        for strategy in STRATEGIES {
+            for _ in 0..5 {
+                let summary = dua::scan_summary(&root, &options(strategy)).unwrap();
+                let entries = &summary.entries;
+
+                assert_eq!(size(entries, &root), 5_000 + 24 * 10);
+                assert_eq!(
+                    size(entries, &root.join("snap.00/data/blob")),
+                    5_000,
+                    "strategy {strategy:?}"
+                );
+                assert_eq!(size(entries, &root.join("snap.00")), 5_010);
+                assert_eq!(size(entries, &root.join("snap.00/data")), 5_010);
+                for i in 1..24 {
+                    let snap = root.join(format!("snap.{i:02}"));
+                    assert_eq!(size(entries, &snap.join("data/blob")), 0);
+                    assert_eq!(size(entries, &snap.join("data")), 10);
+                    assert_eq!(size(entries, &snap), 10, "strategy {strategy:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_snapshot_attribution_and_report() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("backups");
+        linked_tree(&root);
+        let snapshot = temp_dir.path().join("backups.parquet");
+        let snapshot = snapshot.to_str().unwrap();
+
+        for strategy in STRATEGIES {
+            dua::scan_to_snapshot(&root, &options(strategy), snapshot).unwrap();
+            let reader = SnapshotReader::open(snapshot).unwrap();
+            let entries = reader.read_entries(EntryFilter::new()).unwrap();
+            assert_eq!(size(&entries, &root), 5_240, "strategy {strategy:?}");
+            assert_eq!(size(&entries, &root.join("snap.00")), 5_010);
+            assert_eq!(size(&entries, &root.join("snap.17")), 10);
+
+            let report = read_hardlink_report(&reader).unwrap();
+            assert_eq!(report.groups.len(), 1);
+            let group = &report.groups[0];
+            assert_eq!(group.nlink, 24);
+            assert_eq!(group.paths.len(), 24);
+            assert_eq!(group.size_bytes, 5_000);
+            assert_eq!(group.directories, 24);
+            assert!(group.paths[0].ends_with("snap.00/data/blob"));
+            assert_eq!(report.unique_bytes, 5_000);
+            assert_eq!(report.apparent_bytes, 24 * 5_000);
+
+            assert_eq!(report.directories.len(), 24);
+            let charged: Vec<_> = report
+                .directories
+                .iter()
+                .filter(|d| d.charged_bytes > 0)
+                .collect();
+            assert_eq!(charged.len(), 1);
+            assert!(charged[0].path.ends_with("snap.00/data"));
+        }
+    }
+
+    #[test]
+    fn test_links_in_reused_directories_are_settled() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        fs::create_dir_all(root.join("m/kept")).unwrap();
+        fs::create_dir_all(root.join("z/kept")).unwrap();
+        write_file_sync(root.join("m/kept/blob"), vec![0u8; 3_000]).unwrap();
+        fs::hard_link(root.join("m/kept/blob"), root.join("z/kept/blob")).unwrap();
+        let baseline = temp_dir.path().join("baseline.parquet");
+        let opts = options(StrategyKind::PosixOptimized);
+        dua::scan_to_snapshot(&root, &opts, baseline.to_str().unwrap()).unwrap();
+
+        // A new link sorting before the reused ones takes over the bytes.
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::hard_link(root.join("m/kept/blob"), root.join("a/blob")).unwrap();
+
+        for strategy in STRATEGIES {
+            let opts = ScanOptions {
+                baseline: Some(baseline.clone()),
+                ..options(strategy)
+            };
+            let summary = dua::scan_summary(&root, &opts).unwrap();
+            let target = temp_dir.path().join("rescan.parquet");
+            dua::scan_to_snapshot(&root, &opts, target.to_str().unwrap()).unwrap();
+            let stored = SnapshotReader::open(&target)
+                .unwrap()
+                .read_entries(EntryFilter::new())
+                .unwrap();
+
+            for entries in [&summary.entries, &stored] {
+                assert_eq!(size(entries, &root), 3_000, "strategy {strategy:?}");
+                assert_eq!(size(entries, &root.join("a")), 3_000);
+                assert_eq!(size(entries, &root.join("m/kept/blob")), 0);
+                assert_eq!(size(entries, &root.join("m")), 0);
+                assert_eq!(size(entries, &root.join("z/kept")), 0);
+            }
+        }
+    }
+}
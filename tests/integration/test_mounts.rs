@@ -0,0 +1,244 @@
+//! Scans crossing into other filesystems
+//!
+//! These tests mount a tmpfs inside a temporary directory, which needs root,
+//! so they are ignored by default. Run them with
+//! `sudo -E env "PATH=$PATH" cargo test --test integration_tests test_mounts -- --ignored`.
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use crate::fixtures::write_file_sync;
+    use dua::io::snapshot::SnapshotReader;
+    use dua::services::traverse::detect::{FilesystemInfo, FilesystemKind};
+    use dua::{
+        DirectoryEntry, EntryKind, ScanOptions, SizeBasis, SkippedMount, StrategyKind,
+        StrategyRegistry, TraversalContext, TraversalStrategy,
+    };
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    /// A tmpfs mounted for the duration of a test.
+    struct TmpfsMount(PathBuf);
+
+    impl TmpfsMount {
+        fn new(path: &Path, source: &str) -> Self {
+            fs::create_dir_all(path).unwrap();
+            let output = Command::new("mount")
+                .args(["-t", "tmpfs", source])
+                .arg(path)
+                .output()
+                .expect("run mount");
+            assert!(
+                output.status.success(),
+                "cannot mount tmpfs at {}: {}",
+                path.display(),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            Self(path.to_path_buf())
+        }
+    }
+
+    impl Drop for TmpfsMount {
+        fn drop(&mut self) {
+            let _ = Command::new("umount").arg(&self.0).status();
+        }
+    }
+
+    fn entry<'a>(entries: &'a [DirectoryEntry], path: &Path) -> &'a DirectoryEntry {
+        let path = path.to_string_lossy();
+        entries
+            .iter()
+            .find(|e| e.path == path)
+            .unwrap_or_else(|| panic!("no entry for {path}"))
+    }
+
+    fn crossing(strategy_override: Option<StrategyKind>) -> ScanOptions {
+        ScanOptions {
+            basis: SizeBasis::Logical,
+            cross_filesystem: true,
+            strategy_override,
+            ..ScanOptions::default()
+        }
+    }
+
+    #[test]
+    #[ignore = "mounts a tmpfs, which needs root"]
+    fn test_mount_roots_are_annotated_when_crossing() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        let _mount = TmpfsMount::new(&root.join("scratch"), "dua-scratch");
+        write_file_sync(root.join("local.bin"), vec![0u8; 100]).unwrap();
+        write_file_sync(root.join("scratch/tmp.bin"), vec![0u8; 50]).unwrap();
+
+        for strategy in [None, Some(StrategyKind::Legacy)] {
+            let summary = dua::scan_summary(&root, &crossing(strategy)).unwrap();
+
+            let top = entry(&summary.entries, &root);
+            assert_eq!(top.size_bytes, 150, "strategy {strategy:?}");
+            assert_eq!(top.dir_count, 1);
+            assert!(top.fs_type.is_none(), "the scan root is not a mount");
+
+            let mount = entry(&summary.entries, &root.join("scratch"));
+            assert_eq!(mount.size_bytes, 50);
+            assert_eq!(mount.depth, 1);
+            assert_eq!(mount.fs_type.as_deref(), Some("tmpfs"));
+            assert_eq!(mount.mount_source.as_deref(), Some("dua-scratch"));
+            assert_eq!(
+                entry(&summary.entries, &root.join("scratch/tmp.bin")).depth,
+                2
+            );
+        }
+    }
+
+    #[test]
+    #[ignore = "mounts a tmpfs, which needs root"]
+    fn test_scan_root_mount_is_annotated() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("mnt");
+        let _mount = TmpfsMount::new(&root, "dua-root");
+        write_file_sync(root.join("a.bin"), vec![0u8; 10]).unwrap();
+
+        let summary = dua::scan_summary(&root, &ScanOptions::default()).unwrap();
+        let top = entry(&summary.entries, &root);
+        assert_eq!(top.fs_type.as_deref(), Some("tmpfs"));
+        assert_eq!(top.mount_source.as_deref(), Some("dua-root"));
+    }
+
+    #[test]
+    #[ignore = "mounts a tmpfs, which needs root"]
+    fn test_skipped_mounts_are_recorded() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        let _mount = TmpfsMount::new(&root.join("home"), "dua-home");
+        write_file_sync(root.join("local.bin"), vec![0u8; 100]).unwrap();
+        write_file_sync(root.join("home/big.bin"), vec![0u8; 5000]).unwrap();
+        let device = fs::metadata(root.join("home")).unwrap().dev();
+        let mount_path = root.join("home").to_string_lossy().to_string();
+
+        for strategy in [None, Some(StrategyKind::Legacy)] {
+            let opts = ScanOptions {
+                cross_filesystem: false,
+                ..crossing(strategy)
+            };
+            let summary = dua::scan_summary(&root, &opts).unwrap();
+            assert_eq!(entry(&summary.entries, &root).size_bytes, 100);
+            assert_eq!(
+                summary.skipped_mounts,
+                vec![SkippedMount {
+                    path: mount_path.clone(),
+                    device,
+                    fs_type: "tmpfs".to_string(),
+                }],
+                "strategy {strategy:?}"
+            );
+        }
+
+        let snapshot = temp_dir.path().join("data.parquet");
+        let snapshot = snapshot.to_str().unwrap();
+        dua::scan_to_snapshot(&root, &ScanOptions::default(), snapshot).unwrap();
+        let reader = SnapshotReader::open(snapshot).unwrap();
+        let skipped = &reader.meta().skipped_mounts;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, mount_path);
+        assert_eq!(skipped[0].fs_type, "tmpfs");
+
+        // Crossing filesystems leaves nothing behind.
+        let summary = dua::scan_summary(&root, &crossing(None)).unwrap();
+        assert!(summary.skipped_mounts.is_empty());
+    }
+
+    #[test]
+    #[ignore = "mounts a tmpfs, which needs root"]
+    fn test_view_lists_skipped_mounts_among_their_siblings() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        fs::create_dir_all(root.join("b")).unwrap();
+        write_file_sync(root.join("a.bin"), vec![0u8; 100]).unwrap();
+        write_file_sync(root.join("b/c.bin"), vec![0u8; 5000]).unwrap();
+        let _mount = TmpfsMount::new(&root.join("b/mnt"), "dua-nested");
+        let snapshot = temp_dir.path().join("data.parquet");
+        let snapshot = snapshot.to_str().unwrap();
+        dua::scan_to_snapshot(&root, &ScanOptions::default(), snapshot).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_dua"))
+            .args(["view", snapshot])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let row = |needle: &str| {
+            stdout
+                .lines()
+                .position(|line| line.contains(needle))
+                .unwrap_or_else(|| panic!("no row for {needle} in:\n{stdout}"))
+        };
+
+        // The marker follows the other children of b, before the rows after b.
+        let marker = row("[mount, not scanned] (tmpfs)");
+        assert!(stdout.lines().nth(marker).unwrap().contains("/b/mnt/"));
+        assert_eq!(marker, row("/b/c.bin") + 1, "{stdout}");
+        assert!(marker < row("/a.bin"), "{stdout}");
+    }
+
+    /// Claims only mounts whose source names the object store.
+    struct ObjectStore;
+
+    impl TraversalStrategy for ObjectStore {
+        fn kind(&self) -> StrategyKind {
+            StrategyKind::Custom("object-store")
+        }
+
+        fn is_eligible(&self, _opts: &ScanOptions, fs: &FilesystemInfo) -> bool {
+            fs.kind == FilesystemKind::Tmpfs && fs.source.as_deref() == Some("bucket")
+        }
+
+        fn traverse(
+            &self,
+            root: &Path,
+            depth: u16,
+            context: &TraversalContext,
+        ) -> std::io::Result<u64> {
+            context.insert_entry(DirectoryEntry {
+                path: root.to_string_lossy().to_string(),
+                parent_path: root.parent().map(|p| p.to_string_lossy().to_string()),
+                depth,
+                size_bytes: 1 << 20,
+                kind: EntryKind::Directory,
+                ..DirectoryEntry::default()
+            })?;
+            Ok(1 << 20)
+        }
+    }
+
+    #[test]
+    #[ignore = "mounts a tmpfs, which needs root"]
+    fn test_each_mount_uses_the_strategy_chosen_for_it() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        let _mount = TmpfsMount::new(&root.join("bucket"), "bucket");
+        write_file_sync(root.join("local.bin"), vec![0u8; 100]).unwrap();
+        write_file_sync(root.join("bucket/object"), vec![0u8; 7]).unwrap();
+
+        let mut registry = StrategyRegistry::with_defaults();
+        registry.register(ObjectStore);
+        let opts = ScanOptions {
+            strategies: Some(Arc::new(registry)),
+            ..crossing(None)
+        };
+
+        let summary = dua::scan_summary(&root, &opts).unwrap();
+        assert_ne!(summary.strategy, StrategyKind::Custom("object-store"));
+        let bucket = entry(&summary.entries, &root.join("bucket"));
+        assert_eq!(bucket.size_bytes, 1 << 20);
+        assert_eq!(bucket.mount_source.as_deref(), Some("bucket"));
+        assert_eq!(entry(&summary.entries, &root).size_bytes, 100 + (1 << 20));
+        assert!(
+            summary.entries.iter().all(|e| !e.path.ends_with("object")),
+            "the object store backend walked the mount"
+        );
+    }
+}
@@ -0,0 +1,149 @@
+//! Resuming interrupted scans from their partial snapshots
+
+#[cfg(test)]
+mod tests {
+    use crate::fixtures::{create_numbered_tree, read_sorted_entries};
+    use dua::io::snapshot::SnapshotReader;
+    use dua::services::traverse::StrategyKind;
+    use dua::{IgnoreFileMode, ScanOptions, SizeBasis, StopReason};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// Scan `root` into `snapshot`, stopping partway through.
+    fn interrupted_scan(root: &Path, snapshot: &Path, opts: &ScanOptions) {
+        // Throttled to roughly half the operations the full scan needs.
+        let opts = ScanOptions {
+            max_ops_per_sec: Some(400),
+            deadline: Some(Instant::now() + Duration::from_millis(500)),
+            ..opts.clone()
+        };
+        match dua::scan_to_snapshot(root, &opts, snapshot.to_str().unwrap()) {
+            Err(dua::Error::Interrupted { reason, .. }) => {
+                assert_eq!(reason, StopReason::DeadlineExceeded);
+            }
+            other => panic!("expected an interrupted scan, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resume_merges_interrupted_scan() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        create_numbered_tree(&root, 60).unwrap();
+
+        for strategy in [StrategyKind::Legacy, StrategyKind::PosixOptimized] {
+            let opts = ScanOptions {
+                basis: SizeBasis::Logical,
+                strategy_override: Some(strategy),
+                ..Default::default()
+            };
+            let full = temp_dir.path().join(format!("{strategy}-full.parquet"));
+            let partial = temp_dir.path().join(format!("{strategy}-partial.parquet"));
+            let merged = temp_dir.path().join(format!("{strategy}-merged.parquet"));
+
+            dua::scan_to_snapshot(&root, &opts, full.to_str().unwrap()).unwrap();
+            interrupted_scan(&root, &partial, &opts);
+
+            let partial_meta = SnapshotReader::open(&partial).unwrap().into_meta();
+            assert!(partial_meta.incomplete);
+            assert!(
+                partial_meta
+                    .incomplete_dirs
+                    .contains(&root.to_string_lossy().to_string()),
+                "{strategy}: the root is cut short"
+            );
+            let completed = read_sorted_entries(&partial)
+                .into_iter()
+                .filter(|(path, _, _, _)| path.ends_with("inner"))
+                .filter(|(path, _, _, _)| !partial_meta.incomplete_dirs.contains(path))
+                .count();
+            assert!(completed > 0, "{strategy}: some subtrees finished");
+
+            let resume = ScanOptions {
+                resume_from: Some(partial.clone()),
+                ..opts.clone()
+            };
+            dua::scan_to_snapshot(&root, &resume, merged.to_str().unwrap()).unwrap();
+
+            let merged_meta = SnapshotReader::open(&merged).unwrap().into_meta();
+            assert!(!merged_meta.incomplete);
+            assert!(merged_meta.incomplete_dirs.is_empty());
+            assert_eq!(
+                read_sorted_entries(&merged),
+                read_sorted_entries(&full),
+                "{strategy}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resume_in_place_and_rejects_mismatched_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        create_numbered_tree(&root, 60).unwrap();
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            ..Default::default()
+        };
+        let full = temp_dir.path().join("full.parquet");
+        let snapshot = temp_dir.path().join("scan.parquet");
+        dua::scan_to_snapshot(&root, &opts, full.to_str().unwrap()).unwrap();
+        interrupted_scan(&root, &snapshot, &opts);
+
+        let physical = ScanOptions {
+            basis: SizeBasis::Physical,
+            resume_from: Some(snapshot.clone()),
+            ..Default::default()
+        };
+        let err = dua::scan_to_snapshot(&root, &physical, snapshot.to_str().unwrap())
+            .expect_err("basis mismatch");
+        assert!(matches!(err, dua::Error::InvalidInput(_)), "{err}");
+        let mismatched = [
+            ScanOptions {
+                max_depth: Some(1),
+                ..opts.clone()
+            },
+            ScanOptions {
+                follow_symlinks: true,
+                ..opts.clone()
+            },
+            ScanOptions {
+                cross_filesystem: true,
+                ..opts.clone()
+            },
+            ScanOptions {
+                ignore_files: IgnoreFileMode::Skip,
+                ..opts.clone()
+            },
+        ];
+        for other in mismatched {
+            let other = ScanOptions {
+                resume_from: Some(snapshot.clone()),
+                ..other
+            };
+            let err = dua::scan_to_snapshot(&root, &other, snapshot.to_str().unwrap())
+                .expect_err("settings mismatch");
+            assert!(
+                err.to_string().contains("different"),
+                "{other:?} was accepted: {err}"
+            );
+        }
+        let other_root = ScanOptions {
+            resume_from: Some(snapshot.clone()),
+            ..opts.clone()
+        };
+        let err = dua::scan_summary(temp_dir.path(), &other_root).expect_err("root mismatch");
+        assert!(matches!(err, dua::Error::InvalidInput(_)), "{err}");
+        assert!(SnapshotReader::open(&snapshot).unwrap().meta().incomplete);
+
+        let resume = ScanOptions {
+            resume_from: Some(snapshot.clone()),
+            ..opts
+        };
+        dua::scan_to_snapshot(&root, &resume, snapshot.to_str().unwrap()).unwrap();
+        assert!(!SnapshotReader::open(&snapshot).unwrap().meta().incomplete);
+        assert_eq!(read_sorted_entries(&snapshot), read_sorted_entries(&full));
+        assert!(!temp_dir.path().join("scan.parquet.resume.tmp").exists());
+    }
+}
@@ -1,10 +1,12 @@
 //! Integration test for scan command
 
 use crate::fixtures::write_file_sync;
+use dua::io::snapshot::SnapshotReader;
 use dua::services::traverse::{
-    StrategyKind, detect, posix::PosixTraversal, windows::WindowsTraversal,
+    StrategyKind, detect, io_uring::IoUringTraversal, posix::PosixTraversal,
+    windows::WindowsTraversal,
 };
-use dua::{ScanOptions, SizeBasis};
+use dua::{CancellationToken, EntryKind, IgnoreFileMode, ScanOptions, SizeBasis, StopReason};
 use std::collections::HashMap;
 use std::fs;
 use std::process::Command;
@@ -179,3 +181,435 @@ fn test_optimized_vs_legacy_parity() {
         "legacy traversal recorded unexpected errors"
     );
 }
+
+#[test]
+fn test_scan_honors_excludes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join(".git/objects")).unwrap();
+    fs::create_dir_all(root.join("web/node_modules/pkg")).unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    write_file_sync(root.join(".git/objects/blob"), vec![0u8; 4096]).unwrap();
+    write_file_sync(root.join("web/node_modules/pkg/index.js"), vec![0u8; 2048]).unwrap();
+    write_file_sync(root.join("web/app.js"), b"app").unwrap();
+    write_file_sync(root.join("src/main.rs"), b"fn main() {}").unwrap();
+    write_file_sync(root.join("debug.log"), b"noise").unwrap();
+
+    let opts = ScanOptions {
+        basis: SizeBasis::Logical,
+        excludes: vec![".git".into(), "node_modules".into(), "*.log".into()],
+        ..Default::default()
+    };
+
+    for strategy in [None, Some(StrategyKind::Legacy)] {
+        let mut run_opts = opts.clone();
+        run_opts.strategy_override = strategy;
+        let summary = dua::scan_summary(root, &run_opts).expect("scan with excludes");
+
+        for entry in &summary.entries {
+            assert!(
+                !entry.path.contains(".git")
+                    && !entry.path.contains("node_modules")
+                    && !entry.path.ends_with("debug.log"),
+                "excluded entry leaked into results: {}",
+                entry.path
+            );
+        }
+
+        let web = summary
+            .entries
+            .iter()
+            .find(|e| e.path.ends_with("/web"))
+            .expect("web directory present");
+        assert_eq!(web.dir_count, 0, "node_modules must not be counted");
+        assert_eq!(web.size_bytes, 3);
+
+        let root_path = root.to_string_lossy().replace('\\', "/");
+        let root_entry = summary
+            .entries
+            .iter()
+            .find(|e| e.path == root_path)
+            .expect("root entry present");
+        assert_eq!(root_entry.size_bytes, 3 + 12);
+        assert_eq!(root_entry.file_count, 0);
+        assert_eq!(root_entry.dir_count, 2);
+    }
+}
+
+#[test]
+fn test_scan_respects_ignore_files() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join("app/target/debug")).unwrap();
+    fs::create_dir_all(root.join("app/src")).unwrap();
+    write_file_sync(root.join(".gitignore"), b"*.tmp\n").unwrap();
+    write_file_sync(root.join("app/.gitignore"), b"target/\n").unwrap();
+    write_file_sync(root.join("app/target/debug/app"), vec![0u8; 4096]).unwrap();
+    write_file_sync(root.join("app/src/lib.rs"), b"pub fn f() {}").unwrap();
+    write_file_sync(root.join("app/scratch.tmp"), b"scratch").unwrap();
+
+    let target_path = root.join("app/target").to_string_lossy().replace('\\', "/");
+
+    for strategy in [None, Some(StrategyKind::Legacy)] {
+        let skip_opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            ignore_files: IgnoreFileMode::Skip,
+            strategy_override: strategy,
+            ..Default::default()
+        };
+        let summary = dua::scan_summary(root, &skip_opts).expect("scan respecting ignores");
+        assert!(
+            summary
+                .entries
+                .iter()
+                .all(|e| !e.path.contains("/target") && !e.path.ends_with("scratch.tmp")),
+            "ignored entries leaked: {:?}",
+            summary.entries
+        );
+
+        let collapse_opts = ScanOptions {
+            ignore_files: IgnoreFileMode::Collapse,
+            ..skip_opts.clone()
+        };
+        let summary = dua::scan_summary(root, &collapse_opts).expect("scan collapsing ignores");
+        let collapsed = summary
+            .entries
+            .iter()
+            .find(|e| e.path == target_path)
+            .expect("collapsed target entry");
+        assert_eq!(collapsed.size_bytes, 4096);
+        assert_eq!(collapsed.dir_count, 1);
+        assert!(
+            summary
+                .entries
+                .iter()
+                .all(|e| !e.path.starts_with(&format!("{target_path}/"))),
+            "collapsed subtree must not emit children"
+        );
+
+        let app = summary
+            .entries
+            .iter()
+            .find(|e| e.path.ends_with("/app"))
+            .expect("app entry");
+        assert_eq!(app.dir_count, 2);
+        assert_eq!(app.size_bytes, 4096 + 13 + 8);
+    }
+}
+
+#[test]
+fn test_scan_records_entry_attributes() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join("empty")).unwrap();
+    write_file_sync(root.join("data.bin"), vec![0u8; 64]).unwrap();
+
+    let opts = ScanOptions {
+        basis: SizeBasis::Logical,
+        ..Default::default()
+    };
+
+    for strategy in [None, Some(StrategyKind::Legacy)] {
+        let mut run_opts = opts.clone();
+        run_opts.strategy_override = strategy;
+        let summary = dua::scan_summary(root, &run_opts).expect("scan");
+
+        let find = |suffix: &str| {
+            summary
+                .entries
+                .iter()
+                .find(|e| e.path.ends_with(suffix))
+                .unwrap_or_else(|| panic!("{suffix} present"))
+        };
+
+        let empty = find("/empty");
+        assert_eq!(
+            empty.kind,
+            EntryKind::Directory,
+            "empty dirs are directories"
+        );
+        assert_eq!(empty.file_count + empty.dir_count, 0);
+
+        let file = find("/data.bin");
+        assert_eq!(file.kind, EntryKind::File);
+        assert!(file.mtime_ns.is_some_and(|ns| ns > 0));
+        assert!(file.atime_ns.is_some());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let meta = fs::metadata(root.join("data.bin")).unwrap();
+            assert_eq!(file.inode, Some(meta.ino()));
+            assert_eq!(file.device, Some(meta.dev()));
+            assert_eq!(file.nlink, Some(1));
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_posix_relative_stat_matches_legacy() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    fs::create_dir_all(root.join("dir/deeper")).unwrap();
+    write_file_sync(root.join("dir/a.bin"), vec![1u8; 5_000]).unwrap();
+    write_file_sync(root.join("dir/deeper/b.bin"), vec![2u8; 70_000]).unwrap();
+    std::os::unix::fs::symlink(root.join("dir"), root.join("dir-link")).unwrap();
+
+    for basis in [SizeBasis::Logical, SizeBasis::Physical] {
+        let scan = |strategy| {
+            let opts = ScanOptions {
+                basis,
+                strategy_override: Some(strategy),
+                ..Default::default()
+            };
+            let summary = dua::scan_summary(root, &opts).expect("scan");
+            let mut entries = summary.entries;
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            entries
+        };
+
+        let posix = scan(StrategyKind::PosixOptimized);
+        let legacy = scan(StrategyKind::Legacy);
+
+        assert_eq!(posix.len(), legacy.len(), "entry count for {basis:?}");
+        assert!(!posix.iter().any(|e| e.path.ends_with("/dir-link")));
+        for (p, l) in posix.iter().zip(&legacy) {
+            assert_eq!(p.path, l.path);
+            assert_eq!(p.size_bytes, l.size_bytes, "size of {} ({basis:?})", p.path);
+            assert_eq!(p.kind, l.kind, "kind of {}", p.path);
+            assert_eq!(p.mtime_ns, l.mtime_ns, "mtime of {}", p.path);
+            assert_eq!(p.inode, l.inode, "inode of {}", p.path);
+            assert_eq!(p.device, l.device, "device of {}", p.path);
+            assert_eq!(p.nlink, l.nlink, "nlink of {}", p.path);
+        }
+    }
+}
+
+#[test]
+fn test_io_uring_matches_posix_or_falls_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    // More children than one ring submission holds, to exercise batching.
+    for dir in 0..3 {
+        let dir_path = root.join(format!("d{dir}"));
+        fs::create_dir_all(&dir_path).unwrap();
+        for file in 0..300 {
+            write_file_sync(dir_path.join(format!("f{file}")), vec![0u8; file]).unwrap();
+        }
+    }
+
+    let scan = |strategy| {
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            strategy_override: Some(strategy),
+            ..Default::default()
+        };
+        dua::scan_summary(root, &opts).expect("scan")
+    };
+
+    let uring = scan(StrategyKind::IoUring);
+    let posix = scan(StrategyKind::PosixOptimized);
+
+    let expected = if IoUringTraversal::is_supported() {
+        StrategyKind::IoUring
+    } else if PosixTraversal::is_supported() {
+        StrategyKind::PosixOptimized
+    } else {
+        StrategyKind::Legacy
+    };
+    assert_eq!(uring.strategy, expected);
+    assert!(uring.errors.is_empty());
+
+    let sizes = |summary: &dua::Summary| {
+        let mut sizes: Vec<_> = summary
+            .entries
+            .iter()
+            .map(|e| (e.path.clone(), e.size_bytes, e.file_count, e.inode))
+            .collect();
+        sizes.sort();
+        sizes
+    };
+    assert_eq!(sizes(&uring), sizes(&posix));
+    let root_entry = uring.entries.iter().find(|e| e.depth == 0).unwrap();
+    assert_eq!(root_entry.size_bytes, 3 * (0..300).sum::<u64>());
+}
+
+#[test]
+fn test_fd_budget_and_thread_count_preserve_results() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+
+    // A wide level with nested subtrees, so most directories wait for a worker.
+    for dir in 0..40 {
+        let nested = root.join(format!("wide{dir}/inner"));
+        fs::create_dir_all(&nested).unwrap();
+        write_file_sync(nested.join("data.bin"), vec![0u8; 100 + dir]).unwrap();
+    }
+
+    let scan = |threads, max_open_fds| {
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            threads,
+            max_open_fds,
+            ..Default::default()
+        };
+        let summary = dua::scan_summary(root, &opts).expect("scan");
+        assert!(summary.errors.is_empty(), "{:?}", summary.errors);
+        let mut sizes: Vec<_> = summary
+            .entries
+            .iter()
+            .map(|e| (e.path.clone(), e.size_bytes, e.dir_count))
+            .collect();
+        sizes.sort();
+        sizes
+    };
+
+    let unbounded = scan(None, None);
+    assert_eq!(unbounded.len(), 1 + 40 * 3);
+    assert_eq!(scan(Some(1), Some(1)), unbounded);
+    assert_eq!(scan(Some(3), Some(5)), unbounded);
+}
+
+#[test]
+fn test_cancelled_scan_writes_incomplete_snapshot() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("data");
+    fs::create_dir_all(root.join("a/b")).unwrap();
+    write_file_sync(root.join("a/b/file.bin"), vec![0u8; 64]).unwrap();
+
+    for strategy in [
+        StrategyKind::Legacy,
+        StrategyKind::PosixOptimized,
+        StrategyKind::IoUring,
+    ] {
+        let token = CancellationToken::new();
+        token.cancel();
+        let opts = ScanOptions {
+            strategy_override: Some(strategy),
+            cancellation: Some(token),
+            ..Default::default()
+        };
+        let snapshot = temp_dir.path().join(format!("{strategy}.parquet"));
+
+        let err = dua::scan_to_snapshot(&root, &opts, snapshot.to_str().unwrap())
+            .expect_err("cancelled scan must not report success");
+        let dua::Error::Interrupted { reason, summary } = err else {
+            panic!("unexpected error: {err}");
+        };
+        assert_eq!(reason, StopReason::Cancelled);
+
+        let reader = SnapshotReader::open(&snapshot).expect("partial snapshot is readable");
+        assert!(reader.meta().incomplete);
+        assert_eq!(
+            reader.meta().incomplete_reason.as_deref(),
+            Some("cancelled")
+        );
+        assert_eq!(reader.entry_count(), summary.entry_count);
+        assert!(
+            reader
+                .find_entry(&root.to_string_lossy())
+                .unwrap()
+                .is_some(),
+            "{strategy}: the root is still recorded"
+        );
+    }
+}
+
+#[test]
+fn test_deadline_stops_scan() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    for dir in 0..20 {
+        let dir = root.join(format!("d{dir}"));
+        fs::create_dir(&dir).unwrap();
+        write_file_sync(dir.join("file.bin"), vec![0u8; 128]).unwrap();
+    }
+
+    let expired = ScanOptions {
+        deadline: Some(std::time::Instant::now()),
+        ..Default::default()
+    };
+    match dua::scan_summary(root, &expired) {
+        Err(dua::Error::Interrupted { reason, .. }) => {
+            assert_eq!(reason, StopReason::DeadlineExceeded);
+        }
+        other => panic!("expected deadline error, got {other:?}"),
+    }
+
+    // Throttled to ~60 operations over three seconds, the scan is still
+    // running when the deadline passes.
+    let opts = ScanOptions {
+        max_ops_per_sec: Some(20),
+        deadline: Some(std::time::Instant::now() + std::time::Duration::from_millis(200)),
+        ..Default::default()
+    };
+    let full = dua::scan_summary(root, &ScanOptions::default()).unwrap();
+    match dua::scan_summary(root, &opts) {
+        Err(dua::Error::Interrupted { reason, summary }) => {
+            assert_eq!(reason, StopReason::DeadlineExceeded);
+            assert!(summary.entry_count < full.entry_count);
+            assert!(summary.entries.iter().any(|e| e.depth == 0));
+        }
+        other => panic!("expected deadline error, got {other:?}"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_sigint_writes_partial_snapshot() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::Stdio;
+
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("data");
+    for dir in 0..50 {
+        let dir = root.join(format!("d{dir}"));
+        fs::create_dir_all(&dir).unwrap();
+        write_file_sync(dir.join("file.bin"), vec![0u8; 64]).unwrap();
+    }
+    let snapshot = temp_dir.path().join("scan.parquet");
+
+    // Throttled so the scan is still running when the signal arrives.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dua"))
+        .arg("scan")
+        .arg(&root)
+        .arg("--snapshot")
+        .arg(&snapshot)
+        .args(["--max-ops-per-sec", "20"])
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut line = String::new();
+    stderr.read_line(&mut line).unwrap();
+    assert!(line.starts_with("Scanning:"), "{line}");
+
+    // SAFETY: kill(2) on our own child process has no memory-safety preconditions.
+    let rc = unsafe { libc::kill(i32::try_from(child.id()).unwrap(), libc::SIGINT) };
+    assert_eq!(rc, 0);
+    let status = child.wait().unwrap();
+    let mut rest = String::new();
+    stderr.read_to_string(&mut rest).unwrap();
+    assert_eq!(status.code(), Some(130), "{rest}");
+    assert!(rest.contains("Partial snapshot saved"), "{rest}");
+
+    let reader = SnapshotReader::open(&snapshot).expect("footer is written");
+    assert!(reader.meta().incomplete);
+    assert_eq!(
+        serde_json::to_value(reader.meta()).unwrap()["partial"],
+        true
+    );
+    assert!(
+        reader
+            .errors()
+            .unwrap()
+            .iter()
+            .any(|error| error.code == "INTERRUPTED")
+    );
+}
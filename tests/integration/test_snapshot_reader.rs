@@ -0,0 +1,138 @@
+//! Filtered snapshot reads and row-group pruning
+
+#[cfg(test)]
+mod tests {
+    use dua::io::snapshot::{EntryFilter, SnapshotReader, SnapshotWriter};
+    use dua::models::{DirectoryEntry, EntryKind, ErrorItem, SnapshotMeta};
+    use tempfile::NamedTempFile;
+
+    fn entry(path: &str, parent: &str, depth: u16, size_bytes: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: Some(parent.to_string()),
+            depth,
+            size_bytes,
+            kind: EntryKind::Directory,
+            ..Default::default()
+        }
+    }
+
+    fn meta() -> SnapshotMeta {
+        SnapshotMeta {
+            scan_root: "/r".to_string(),
+            started_at: "2025-10-30T00:00:00Z".to_string(),
+            finished_at: "2025-10-30T00:00:01Z".to_string(),
+            size_basis: "physical".to_string(),
+            hardlink_policy: "dedupe".to_string(),
+            excludes: vec![],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
+            strategy: "legacy".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
+        }
+    }
+
+    /// Three row groups: the root with `/r/a`, the `/r/b` subtree, and `/r/c`.
+    fn write_grouped_snapshot(path: &std::path::Path) {
+        let mut writer = SnapshotWriter::create(path).unwrap();
+        writer
+            .write_entries(&[
+                DirectoryEntry {
+                    path: "/r".to_string(),
+                    parent_path: Some("/".to_string()),
+                    size_bytes: 5_300,
+                    ..Default::default()
+                },
+                entry("/r/a", "/r", 1, 100),
+                entry("/r/a/x", "/r/a", 2, 100),
+            ])
+            .unwrap();
+        writer.end_row_group().unwrap();
+        writer
+            .write_entries(&[
+                entry("/r/b", "/r", 1, 200),
+                entry("/r/b/y", "/r/b", 2, 150),
+                entry("/r/b/z", "/r/b", 2, 50),
+            ])
+            .unwrap();
+        writer.end_row_group().unwrap();
+        writer
+            .write_entries(&[entry("/r/c", "/r", 1, 5_000)])
+            .unwrap();
+        writer
+            .finish(
+                &meta(),
+                &[ErrorItem {
+                    path: "/r/locked".to_string(),
+                    code: "EACCES".to_string(),
+                    message: "Permission denied".to_string(),
+                }],
+            )
+            .unwrap();
+    }
+
+    fn paths(entries: &[DirectoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_reader_exposes_metadata_without_reading_rows() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_grouped_snapshot(temp_file.path());
+
+        let reader = SnapshotReader::open(temp_file.path()).unwrap();
+        assert_eq!(reader.meta().scan_root, "/r");
+        assert_eq!(reader.entry_count(), 7);
+        assert_eq!(reader.errors().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_statistics_prune_row_groups() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_grouped_snapshot(temp_file.path());
+        let reader = SnapshotReader::open(temp_file.path()).unwrap();
+
+        let children_of_b = EntryFilter::new().parent_path("/r/b");
+        assert_eq!(reader.candidate_row_groups(&children_of_b), vec![1]);
+        assert_eq!(
+            paths(&reader.read_entries(children_of_b).unwrap()),
+            vec!["/r/b/y", "/r/b/z"]
+        );
+
+        let shallow = EntryFilter::new().max_depth(0);
+        assert_eq!(reader.candidate_row_groups(&shallow), vec![0]);
+        assert_eq!(paths(&reader.read_entries(shallow).unwrap()), vec!["/r"]);
+
+        let large = EntryFilter::new().min_size(1_000);
+        assert_eq!(reader.candidate_row_groups(&large), vec![0, 2]);
+        assert_eq!(
+            paths(&reader.read_entries(large).unwrap()),
+            vec!["/r", "/r/c"]
+        );
+
+        let missing = EntryFilter::new().path("/r/zzz");
+        assert!(reader.candidate_row_groups(&missing).is_empty());
+        assert!(reader.find_entry("/r/zzz").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_subtree_and_depth_filters_combine() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_grouped_snapshot(temp_file.path());
+        let reader = SnapshotReader::open(temp_file.path()).unwrap();
+
+        let found = reader.find_entry("/r/a/x").unwrap().expect("entry exists");
+        assert_eq!(found.depth, 2);
+
+        let filter = EntryFilter::new().subtree("/r/b").max_depth(1);
+        assert_eq!(paths(&reader.read_entries(filter).unwrap()), vec!["/r/b"]);
+
+        let everything = EntryFilter::new().subtree("/");
+        assert_eq!(reader.read_entries(everything).unwrap().len(), 7);
+    }
+}
@@ -2,8 +2,20 @@
 
 #[cfg(test)]
 mod tests {
-    use dua::io::snapshot::{read_snapshot, write_snapshot};
-    use dua::models::{DirectoryEntry, ErrorItem, SnapshotMeta};
+    use arrow_array::{ArrayRef, RecordBatch, StringArray, UInt16Array, UInt32Array, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use dua::io::snapshot::{
+        ENTRY_ROW_GROUPS_KEY, META_KEY, SCHEMA_VERSION_KEY, SNAPSHOT_SCHEMA_VERSION,
+        SnapshotReader, create_entries_batch, read_schema_version, read_snapshot,
+        read_snapshot_meta, snapshot_schema, upgrade_snapshot, write_snapshot,
+    };
+    use dua::models::{DirectoryEntry, EntryKind, ErrorItem, SnapshotMeta};
+    use parquet::arrow::ArrowWriter;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use parquet::file::metadata::KeyValue;
+    use parquet::file::properties::WriterProperties;
+    use std::fs::File;
+    use std::sync::Arc;
     use tempfile::NamedTempFile;
 
     #[test]
@@ -18,8 +30,16 @@ mod tests {
             finished_at: "2025-10-30T00:01:00Z".to_string(),
             size_basis: "physical".to_string(),
             hardlink_policy: "dedupe".to_string(),
-            excludes: vec![],
+            excludes: vec![".git".to_string(), "*.tmp".to_string()],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
             strategy: "legacy".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
         };
 
         let entries = vec![
@@ -30,14 +50,24 @@ mod tests {
                 size_bytes: 1024,
                 file_count: 5,
                 dir_count: 2,
+                kind: EntryKind::Directory,
+                mtime_ns: Some(1_761_782_400_123_456_789),
+                atime_ns: Some(-1),
+                inode: Some(42),
+                device: Some(2049),
+                nlink: Some(3),
+                fs_type: Some("nfs4".to_string()),
+                mount_source: Some("server:/export".to_string()),
             },
             DirectoryEntry {
                 path: "/test/root/dir2".to_string(),
                 parent_path: Some("/test/root".to_string()),
                 depth: 1,
                 size_bytes: 2048,
-                file_count: 10,
-                dir_count: 3,
+                file_count: 0,
+                dir_count: 0,
+                kind: EntryKind::File,
+                ..Default::default()
             },
         ];
 
@@ -70,6 +100,7 @@ mod tests {
         assert_eq!(read_meta.size_basis, meta.size_basis);
         assert_eq!(read_meta.hardlink_policy, meta.hardlink_policy);
         assert_eq!(read_meta.strategy, meta.strategy);
+        assert_eq!(read_meta.excludes, meta.excludes);
 
         // Verify entries
         assert_eq!(read_entries.len(), entries.len());
@@ -78,6 +109,9 @@ mod tests {
             assert_eq!(read.size_bytes, orig.size_bytes);
             assert_eq!(read.file_count, orig.file_count);
             assert_eq!(read.dir_count, orig.dir_count);
+            assert_eq!(read.attributes(), orig.attributes());
+            assert_eq!(read.fs_type, orig.fs_type);
+            assert_eq!(read.mount_source, orig.mount_source);
         }
 
         // Verify errors
@@ -98,7 +132,15 @@ mod tests {
             size_basis: "logical".to_string(),
             hardlink_policy: "count".to_string(),
             excludes: vec![],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
             strategy: "legacy".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
         };
 
         let write_result = write_snapshot(snapshot_path, &meta, &[], &[]);
@@ -107,8 +149,308 @@ mod tests {
         let read_result = read_snapshot(snapshot_path);
         assert!(read_result.is_ok());
 
-        let (_, entries, errors) = read_result.unwrap();
+        let (read_meta, entries, errors) = read_result.unwrap();
+        assert!(read_meta.excludes.is_empty());
         assert_eq!(entries.len(), 0);
         assert_eq!(errors.len(), 0);
     }
+
+    /// Write a snapshot in the unversioned layout used before entry attributes.
+    fn write_unversioned_snapshot(path: &std::path::Path) {
+        let utf8 = |name: &str| Field::new(name, DataType::Utf8, true);
+        let schema = Arc::new(Schema::new(vec![
+            utf8("path"),
+            utf8("parent_path"),
+            Field::new("depth", DataType::UInt16, true),
+            Field::new("size_bytes", DataType::UInt64, true),
+            Field::new("file_count", DataType::UInt32, true),
+            Field::new("dir_count", DataType::UInt32, true),
+            utf8("meta_scan_root"),
+            utf8("meta_started_at"),
+            utf8("meta_finished_at"),
+            utf8("meta_size_basis"),
+            utf8("meta_hardlink_policy"),
+            utf8("meta_strategy"),
+            utf8("error_path"),
+            utf8("error_code"),
+            utf8("error_message"),
+        ]));
+
+        let strings = |values: [Option<&str>; 3]| -> ArrayRef {
+            Arc::new(StringArray::from(values.to_vec()))
+        };
+        let none = || strings([None, None, None]);
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                strings([Some("/old/dir"), Some("/old/file"), None]),
+                strings([Some("/old"), Some("/old"), None]),
+                Arc::new(UInt16Array::from(vec![Some(1), Some(1), None])),
+                Arc::new(UInt64Array::from(vec![Some(10), Some(20), None])),
+                Arc::new(UInt32Array::from(vec![Some(1), Some(0), None])),
+                Arc::new(UInt32Array::from(vec![Some(0), Some(0), None])),
+                strings([None, None, Some("/old")]),
+                strings([None, None, Some("2025-01-01T00:00:00Z")]),
+                strings([None, None, Some("2025-01-01T00:00:01Z")]),
+                strings([None, None, Some("physical")]),
+                strings([None, None, Some("dedupe")]),
+                strings([None, None, Some("legacy")]),
+                none(),
+                none(),
+                none(),
+            ],
+        )
+        .unwrap();
+
+        let mut writer = ArrowWriter::try_new(File::create(path).unwrap(), schema, None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    fn sample_meta() -> SnapshotMeta {
+        SnapshotMeta {
+            scan_root: "/v".to_string(),
+            started_at: "2025-10-30T00:00:00Z".to_string(),
+            finished_at: "2025-10-30T00:00:01Z".to_string(),
+            size_basis: "logical".to_string(),
+            hardlink_policy: "count".to_string(),
+            excludes: vec![],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
+            strategy: "legacy".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_without_attribute_columns_loads() {
+        let temp_file = NamedTempFile::new().unwrap();
+        write_unversioned_snapshot(temp_file.path());
+        let path = temp_file.path().to_str().unwrap();
+
+        assert_eq!(read_schema_version(path).unwrap(), 1);
+        let (meta, entries, errors) = read_snapshot(path).expect("old snapshots remain readable");
+
+        assert_eq!(meta.scan_root, "/old");
+        assert!(meta.excludes.is_empty());
+        assert!(errors.is_empty());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, EntryKind::Directory);
+        assert_eq!(entries[1].kind, EntryKind::File);
+        assert!(
+            entries
+                .iter()
+                .all(|e| e.mtime_ns.is_none() && e.inode.is_none())
+        );
+    }
+
+    #[test]
+    fn test_version_3_snapshot_without_mount_columns_loads() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let full_schema = snapshot_schema();
+        let entries = vec![DirectoryEntry {
+            path: "/v/a".to_string(),
+            parent_path: Some("/v".to_string()),
+            depth: 1,
+            size_bytes: 10,
+            inode: Some(7),
+            ..Default::default()
+        }];
+        let indices: Vec<usize> = (0..full_schema.fields().len())
+            .filter(|&i| {
+                !matches!(
+                    full_schema.field(i).name().as_str(),
+                    "fs_type" | "mount_source"
+                )
+            })
+            .collect();
+        let batch = create_entries_batch(&full_schema, &entries)
+            .unwrap()
+            .project(&indices)
+            .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![
+                KeyValue::new(SCHEMA_VERSION_KEY.to_string(), "3".to_string()),
+                KeyValue::new(
+                    META_KEY.to_string(),
+                    serde_json::to_string(&sample_meta()).unwrap(),
+                ),
+                KeyValue::new(ENTRY_ROW_GROUPS_KEY.to_string(), "1".to_string()),
+            ]))
+            .build();
+        let mut writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let (_, read_entries, _) = read_snapshot(path).expect("version 3 files remain readable");
+        assert_eq!(read_entries.len(), 1);
+        assert_eq!(read_entries[0].inode, Some(7));
+        assert!(read_entries[0].fs_type.is_none());
+    }
+
+    #[test]
+    fn test_snapshot_records_schema_version() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        write_snapshot(path, &sample_meta(), &[], &[]).unwrap();
+
+        assert_eq!(read_schema_version(path).unwrap(), SNAPSHOT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_newer_schema_version_is_rejected() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let future = SNAPSHOT_SCHEMA_VERSION + 1;
+        let props = WriterProperties::builder()
+            .set_key_value_metadata(Some(vec![KeyValue::new(
+                SCHEMA_VERSION_KEY.to_string(),
+                future.to_string(),
+            )]))
+            .build();
+        let schema = snapshot_schema();
+        let writer =
+            ArrowWriter::try_new(File::create(path).unwrap(), schema, Some(props)).unwrap();
+        writer.close().unwrap();
+
+        let err = read_snapshot(path).expect_err("newer layouts must be refused");
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+        assert!(
+            err.to_string().contains(&format!("version {future}")),
+            "unexpected message: {err}"
+        );
+        assert!(upgrade_snapshot(path, None).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_rewrites_old_snapshot_in_place() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let old_path = temp_dir.path().join("old.parquet");
+        write_unversioned_snapshot(&old_path);
+        let path = old_path.to_str().unwrap();
+
+        let upgrade = upgrade_snapshot(path, None).expect("upgrade succeeds");
+        assert!(upgrade.rewritten());
+        assert_eq!(upgrade.from_version, 1);
+        assert_eq!(read_schema_version(path).unwrap(), SNAPSHOT_SCHEMA_VERSION);
+
+        let (meta, entries, _) = read_snapshot(path).unwrap();
+        assert_eq!(meta.scan_root, "/old");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].kind, EntryKind::Directory);
+
+        let again = upgrade_snapshot(path, None).unwrap();
+        assert!(!again.rewritten(), "current snapshots are left untouched");
+
+        let leftovers = std::fs::read_dir(temp_dir.path()).unwrap().count();
+        assert_eq!(leftovers, 1, "no temporary files remain");
+    }
+
+    #[test]
+    fn test_metadata_in_footer_and_errors_in_trailing_row_group() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        let entries = vec![DirectoryEntry {
+            path: "/v/a".to_string(),
+            parent_path: Some("/v".to_string()),
+            depth: 1,
+            size_bytes: 10,
+            kind: EntryKind::File,
+            ..Default::default()
+        }];
+        let errors = vec![ErrorItem {
+            path: "/v/locked".to_string(),
+            code: "EACCES".to_string(),
+            message: "Permission denied".to_string(),
+        }];
+        write_snapshot(path, &sample_meta(), &entries, &errors).unwrap();
+
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+        assert!(
+            builder
+                .schema()
+                .fields()
+                .iter()
+                .all(|f| !f.name().starts_with("meta_")),
+            "metadata is not stored as columns"
+        );
+
+        let footer = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .unwrap();
+        let value = |key: &str| {
+            footer
+                .iter()
+                .find(|kv| kv.key == key)
+                .and_then(|kv| kv.value.clone())
+        };
+        assert!(value(META_KEY).unwrap().contains("\"scan_root\":\"/v\""));
+        assert_eq!(value(ENTRY_ROW_GROUPS_KEY).as_deref(), Some("1"));
+
+        let row_groups = builder.metadata().row_groups();
+        assert_eq!(row_groups.len(), 2);
+        assert_eq!(row_groups[0].num_rows(), 1);
+        assert_eq!(row_groups[1].num_rows(), 1);
+
+        let (meta, read_entries, read_errors) = read_snapshot(path).unwrap();
+        assert_eq!(meta.scan_root, "/v");
+        assert_eq!(read_entries.len(), 1);
+        assert_eq!(read_entries[0].path, "/v/a");
+        assert_eq!(read_entries[0].kind, EntryKind::File);
+        assert_eq!(read_errors.len(), 1);
+        assert_eq!(read_errors[0].code, "EACCES");
+        assert_eq!(read_snapshot_meta(path).unwrap().size_basis, "logical");
+    }
+
+    #[test]
+    fn test_scan_persists_progress_timeline() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/file.bin"), vec![0u8; 4096]).unwrap();
+        let snapshot = temp_dir.path().join("scan.parquet");
+
+        let summary = dua::scan_to_snapshot(
+            &root,
+            &dua::ScanOptions::default(),
+            snapshot.to_str().unwrap(),
+        )
+        .unwrap();
+        assert!(
+            !summary.progress.is_empty(),
+            "a final progress event is emitted"
+        );
+
+        let reader = SnapshotReader::open(&snapshot).unwrap();
+        let progress = reader.progress().unwrap();
+        assert_eq!(progress.len(), summary.progress.len());
+        let last = progress.last().unwrap();
+        assert_eq!(
+            last.processed_entries,
+            summary.progress.last().unwrap().processed_entries
+        );
+        assert_eq!(
+            last.processed_bytes,
+            summary.progress.last().unwrap().processed_bytes
+        );
+
+        let upgraded = temp_dir.path().join("copy.parquet");
+        upgrade_snapshot(snapshot.to_str().unwrap(), upgraded.to_str()).unwrap();
+        let copied = SnapshotReader::open(&upgraded).unwrap().progress().unwrap();
+        assert_eq!(copied.len(), progress.len(), "rewrites keep the timeline");
+    }
 }
@@ -0,0 +1,159 @@
+//! Path-sorted snapshot layout and external merge of spilled runs
+
+#[cfg(test)]
+mod tests {
+    use dua::io::snapshot::{
+        EntryFilter, SnapshotReader, SortedSnapshotWriter, compare_paths, write_sorted_snapshot,
+    };
+    use dua::models::{DirectoryEntry, SnapshotMeta};
+    use std::cmp::Ordering;
+    use tempfile::TempDir;
+
+    fn meta() -> SnapshotMeta {
+        SnapshotMeta {
+            scan_root: "/r".to_string(),
+            started_at: "2025-10-30T00:00:00Z".to_string(),
+            finished_at: "2025-10-30T00:00:01Z".to_string(),
+            size_basis: "physical".to_string(),
+            hardlink_policy: "dedupe".to_string(),
+            excludes: vec![],
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
+            strategy: "posix".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
+        }
+    }
+
+    /// Post-order entries for `/r/d0`..`/r/d4`, each holding four files.
+    fn post_order_entries() -> Vec<DirectoryEntry> {
+        let mut entries = Vec::new();
+        for dir in (0..5).rev() {
+            let dir_path = format!("/r/d{dir}");
+            for file in 0..4 {
+                entries.push(DirectoryEntry {
+                    path: format!("{dir_path}/f{file}"),
+                    parent_path: Some(dir_path.clone()),
+                    depth: 2,
+                    size_bytes: 10,
+                    ..Default::default()
+                });
+            }
+            entries.push(DirectoryEntry {
+                path: dir_path,
+                parent_path: Some("/r".to_string()),
+                depth: 1,
+                size_bytes: 40,
+                file_count: 4,
+                ..Default::default()
+            });
+        }
+        entries.push(DirectoryEntry {
+            path: "/r".to_string(),
+            parent_path: Some("/".to_string()),
+            size_bytes: 200,
+            dir_count: 5,
+            ..Default::default()
+        });
+        entries
+    }
+
+    #[test]
+    fn test_compare_paths_keeps_subtrees_contiguous() {
+        assert_eq!(compare_paths("/a", "/a/x"), Ordering::Less);
+        assert_eq!(compare_paths("/a/x", "/a-b"), Ordering::Less);
+        assert_eq!(compare_paths("/a-b", "/a/x"), Ordering::Greater);
+        assert_eq!(compare_paths("/a", "/a"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_spilled_runs_merge_into_sorted_clustered_snapshot() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("sorted.parquet");
+
+        let mut writer = SortedSnapshotWriter::create(&path, 7)
+            .unwrap()
+            .with_row_group_rows(5);
+        let input = post_order_entries();
+        let total = input.len();
+        for entry in input {
+            writer.push(entry).unwrap();
+        }
+        assert!(
+            writer.spilled_runs() > 1,
+            "small run capacity forces spills"
+        );
+        writer.finish(&meta(), &[]).unwrap();
+
+        let leftovers: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, vec!["sorted.parquet"], "run files are removed");
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        let entries = reader.read_entries(EntryFilter::new()).unwrap();
+        assert_eq!(entries.len(), total);
+        assert!(
+            entries
+                .windows(2)
+                .all(|w| compare_paths(&w[0].path, &w[1].path) == Ordering::Less),
+            "entries are written in path order"
+        );
+        assert_eq!(entries[0].path, "/r");
+        assert_eq!(entries[1].path, "/r/d0");
+
+        // Groups end where the stream leaves a directory, so subtrees are not split.
+        let subtree = EntryFilter::new().subtree("/r/d3");
+        let candidates = reader.candidate_row_groups(&subtree);
+        assert_eq!(candidates.len(), 1, "a subtree maps to a single row group");
+        assert_eq!(reader.read_entries(subtree).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_in_memory_sort_without_spilling() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("small.parquet");
+
+        let mut writer = SortedSnapshotWriter::create(&path, 1_000).unwrap();
+        for entry in post_order_entries() {
+            writer.push(entry).unwrap();
+        }
+        assert_eq!(writer.spilled_runs(), 0);
+        writer.finish(&meta(), &[]).unwrap();
+
+        let reader = SnapshotReader::open(&path).unwrap();
+        let entries = reader.read_entries(EntryFilter::new()).unwrap();
+        assert_eq!(entries.first().unwrap().path, "/r");
+        assert_eq!(entries.last().unwrap().path, "/r/d4/f3");
+    }
+
+    #[test]
+    fn test_in_memory_entries_are_written_sorted_without_runs() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("memory.parquet");
+        let input = post_order_entries();
+
+        write_sorted_snapshot(&path, &meta(), &input, &[], &[]).unwrap();
+
+        let leftovers: Vec<_> = std::fs::read_dir(temp_dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(leftovers, vec!["memory.parquet"], "nothing is spilled");
+        let entries = SnapshotReader::open(&path)
+            .unwrap()
+            .read_entries(EntryFilter::new())
+            .unwrap();
+        assert_eq!(entries.len(), input.len());
+        assert!(
+            entries
+                .windows(2)
+                .all(|w| compare_paths(&w[0].path, &w[1].path) == Ordering::Less)
+        );
+    }
+}
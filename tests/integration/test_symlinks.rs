@@ -0,0 +1,222 @@
+//! Scans that follow symlinks
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use crate::fixtures::write_file_sync;
+    use dua::{DirectoryEntry, EntryKind, ScanOptions, SizeBasis, StrategyKind, Summary};
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    const STRATEGIES: [StrategyKind; 2] = [StrategyKind::PosixOptimized, StrategyKind::Legacy];
+
+    fn follow(strategy: StrategyKind) -> ScanOptions {
+        ScanOptions {
+            basis: SizeBasis::Logical,
+            follow_symlinks: true,
+            strategy_override: Some(strategy),
+            ..ScanOptions::default()
+        }
+    }
+
+    fn entry<'a>(summary: &'a Summary, path: &Path) -> &'a DirectoryEntry {
+        let path = path.to_string_lossy();
+        summary
+            .entries
+            .iter()
+            .find(|e| e.path == path)
+            .unwrap_or_else(|| panic!("no entry for {path}"))
+    }
+
+    #[test]
+    fn test_symlink_targets_are_measured() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("layers");
+        let store = temp_dir.path().join("store");
+        fs::create_dir_all(&root).unwrap();
+        fs::create_dir_all(store.join("blobs")).unwrap();
+        write_file_sync(store.join("blobs/layer.tar"), vec![0u8; 4_000]).unwrap();
+        write_file_sync(store.join("config.json"), vec![0u8; 300]).unwrap();
+        symlink(store.join("blobs"), root.join("blobs")).unwrap();
+        symlink(store.join("config.json"), root.join("config.json")).unwrap();
+        symlink(store.join("missing"), root.join("dangling")).unwrap();
+
+        for strategy in STRATEGIES {
+            let summary = dua::scan_summary(&root, &follow(strategy)).unwrap();
+
+            let top = entry(&summary, &root);
+            assert_eq!(top.size_bytes, 4_300, "strategy {strategy:?}");
+            assert_eq!(top.file_count, 1);
+            assert_eq!(top.dir_count, 1);
+
+            let blobs = entry(&summary, &root.join("blobs"));
+            assert_eq!(blobs.kind, EntryKind::Directory);
+            assert_eq!(blobs.size_bytes, 4_000);
+            assert_eq!(
+                entry(&summary, &root.join("blobs/layer.tar")).size_bytes,
+                4_000
+            );
+            assert_eq!(
+                entry(&summary, &root.join("config.json")).kind,
+                EntryKind::File
+            );
+
+            assert_eq!(summary.errors.len(), 1, "strategy {strategy:?}");
+            assert!(summary.errors[0].path.ends_with("dangling"));
+            assert_eq!(summary.errors[0].code, "ENOENT");
+        }
+
+        // Without --follow-symlinks the links are skipped.
+        let summary = dua::scan_summary(
+            &root,
+            &ScanOptions {
+                follow_symlinks: false,
+                ..follow(StrategyKind::PosixOptimized)
+            },
+        )
+        .unwrap();
+        assert_eq!(entry(&summary, &root).size_bytes, 0);
+        assert!(summary.errors.is_empty());
+    }
+
+    #[test]
+    fn test_symlink_cycles_are_reported_once_and_not_followed() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        fs::create_dir_all(root.join("a/b")).unwrap();
+        write_file_sync(root.join("a/b/file.bin"), vec![0u8; 100]).unwrap();
+        symlink(&root, root.join("a/b/up")).unwrap();
+        symlink(root.join("a/b"), root.join("shortcut")).unwrap();
+        // A cycle leaving the tree and coming back through another link.
+        let outside = temp_dir.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+        write_file_sync(outside.join("extra.bin"), vec![0u8; 20]).unwrap();
+        symlink(&outside, root.join("ext")).unwrap();
+        symlink(&root, outside.join("back")).unwrap();
+
+        for strategy in STRATEGIES {
+            let summary = dua::scan_summary(&root, &follow(strategy)).unwrap();
+
+            assert_eq!(
+                entry(&summary, &root).size_bytes,
+                120,
+                "strategy {strategy:?}"
+            );
+            let mut loops: Vec<_> = summary
+                .errors
+                .iter()
+                .filter(|error| error.code == "ELOOP")
+                .collect();
+            loops.sort_by(|a, b| a.path.cmp(&b.path));
+            assert_eq!(
+                loops.len(),
+                2,
+                "strategy {strategy:?}: {:?}",
+                summary.errors
+            );
+            assert_eq!(loops[0].path, root.join("a/b/up").to_string_lossy());
+            assert_eq!(loops[1].path, root.join("ext/back").to_string_lossy());
+            assert!(loops[0].message.contains(&*root.to_string_lossy()));
+            assert!(
+                summary
+                    .entries
+                    .iter()
+                    .all(|e| !e.path.contains("/up/") && !e.path.contains("/shortcut/")),
+                "a directory was walked twice"
+            );
+        }
+    }
+
+    #[test]
+    fn test_symlink_to_an_ancestor_of_the_scan_root_is_a_cycle() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("a/b");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        write_file_sync(root.join("sub/f.txt"), vec![0u8; 64]).unwrap();
+        symlink("..", root.join("up")).unwrap();
+
+        for strategy in STRATEGIES {
+            let summary = dua::scan_summary(&root, &follow(strategy)).unwrap();
+
+            assert_eq!(
+                entry(&summary, &root).size_bytes,
+                64,
+                "strategy {strategy:?}"
+            );
+            assert_eq!(entry(&summary, &root.join("sub")).size_bytes, 64);
+            assert!(
+                summary.entries.iter().all(|e| !e.path.contains("/up/")),
+                "the scan root was walked again through {}",
+                root.join("up").display()
+            );
+            assert_eq!(summary.errors.len(), 1, "strategy {strategy:?}");
+            let error = &summary.errors[0];
+            assert_eq!(error.code, "ELOOP");
+            assert_eq!(error.path, root.join("up").to_string_lossy());
+            let canonical_root = fs::canonicalize(&root).unwrap();
+            assert!(error.message.contains(&*canonical_root.to_string_lossy()));
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(windows)]
+mod windows_tests {
+    use crate::fixtures::write_file_sync;
+    use dua::{DirectoryEntry, EntryKind, ScanOptions, SizeBasis, StrategyKind, Summary};
+    use std::fs;
+    use std::os::windows::fs::symlink_dir;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    fn entry<'a>(summary: &'a Summary, path: &Path) -> &'a DirectoryEntry {
+        let path = path.to_string_lossy().replace('\\', "/");
+        summary
+            .entries
+            .iter()
+            .find(|e| e.path == path)
+            .unwrap_or_else(|| panic!("no entry for {path}"))
+    }
+
+    #[test]
+    fn test_windows_backend_follows_links_and_reports_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        let store = temp_dir.path().join("store");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::create_dir_all(&store).unwrap();
+        write_file_sync(root.join("sub/file.bin"), vec![0u8; 100]).unwrap();
+        write_file_sync(store.join("blob.bin"), vec![0u8; 4_000]).unwrap();
+        symlink_dir(&store, root.join("linked")).unwrap();
+        symlink_dir(&root, root.join("sub/up")).unwrap();
+
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            follow_symlinks: true,
+            strategy_override: Some(StrategyKind::WindowsOptimized),
+            ..ScanOptions::default()
+        };
+        let summary = dua::scan_summary(&root, &opts).unwrap();
+
+        assert_eq!(summary.strategy, StrategyKind::WindowsOptimized);
+        assert_eq!(entry(&summary, &root).size_bytes, 4_100);
+        let linked = entry(&summary, &root.join("linked"));
+        assert_eq!(linked.kind, EntryKind::Directory);
+        assert_eq!(linked.size_bytes, 4_000);
+        assert_eq!(
+            entry(&summary, &root.join("linked/blob.bin")).size_bytes,
+            4_000
+        );
+
+        assert_eq!(summary.errors.len(), 1, "{:?}", summary.errors);
+        let error = &summary.errors[0];
+        assert_eq!(error.code, "ELOOP");
+        assert!(error.path.ends_with("sub/up") || error.path.ends_with("sub\\up"));
+        assert!(
+            summary.entries.iter().all(|e| !e.path.contains("/up/")),
+            "the scan root was walked again through sub/up"
+        );
+    }
+}
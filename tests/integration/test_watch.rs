@@ -0,0 +1,151 @@
+//! Watch mode keeping a live tree in step with the filesystem
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use crate::fixtures::{read_sorted_entries, write_file_sync};
+    use dua::services::watch::Watch;
+    use dua::{ScanOptions, SizeBasis};
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    /// Poll until `done` holds, failing after a few seconds.
+    fn poll_until(watch: &mut Watch, done: impl Fn(&Watch) -> bool) {
+        let give_up = Instant::now() + Duration::from_secs(5);
+        while !done(watch) {
+            assert!(Instant::now() < give_up, "watch never caught up");
+            watch.poll(Duration::from_millis(100)).unwrap();
+        }
+    }
+
+    fn size_of(watch: &Watch, path: &Path) -> Option<u64> {
+        watch
+            .tree()
+            .get(&path.to_string_lossy())
+            .map(|entry| entry.size_bytes)
+    }
+
+    #[test]
+    fn test_watch_tracks_changes_and_checkpoints() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::create_dir_all(root.join("static")).unwrap();
+        write_file_sync(root.join("logs/app.log"), vec![b'x'; 100]).unwrap();
+        write_file_sync(root.join("static/old.bin"), vec![0u8; 400]).unwrap();
+
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            ..Default::default()
+        };
+        let mut watch = Watch::start(&root, &opts, Duration::from_secs(60)).unwrap();
+        assert_eq!(watch.tree().total_size(), 500);
+        assert_eq!(watch.unwatched_dirs(), 0);
+
+        let mut log = OpenOptions::new()
+            .append(true)
+            .open(root.join("logs/app.log"))
+            .unwrap();
+        log.write_all(&[b'y'; 900]).unwrap();
+        log.sync_all().unwrap();
+        poll_until(&mut watch, |w| w.tree().total_size() == 1_400);
+        assert_eq!(size_of(&watch, &root.join("logs")), Some(1_000));
+
+        fs::create_dir_all(root.join("new/nested")).unwrap();
+        write_file_sync(root.join("new/nested/data.bin"), vec![1u8; 50]).unwrap();
+        fs::remove_file(root.join("static/old.bin")).unwrap();
+        poll_until(&mut watch, |w| {
+            size_of(w, &root.join("new/nested/data.bin")) == Some(50)
+                && size_of(w, &root.join("static/old.bin")).is_none()
+        });
+        assert_eq!(watch.tree().total_size(), 1_050);
+
+        let growth = watch.fastest_growing(5);
+        assert_eq!(growth[0].path, root.join("logs").to_string_lossy());
+        assert_eq!(growth[0].delta_bytes, 900);
+
+        let checkpoint = temp_dir.path().join("live.parquet");
+        let fresh = temp_dir.path().join("fresh.parquet");
+        watch.checkpoint(checkpoint.to_str().unwrap()).unwrap();
+        dua::scan_to_snapshot(&root, &opts, fresh.to_str().unwrap()).unwrap();
+        assert_eq!(
+            read_sorted_entries(&checkpoint),
+            read_sorted_entries(&fresh)
+        );
+    }
+
+    #[test]
+    fn test_writes_during_the_initial_scan_are_seen() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        for i in 0..5 {
+            fs::create_dir_all(root.join(format!("d{i}"))).unwrap();
+            write_file_sync(root.join(format!("d{i}/f.bin")), vec![0u8; 10]).unwrap();
+        }
+
+        // Each time the scan reaches a directory, write a file into the one
+        // it listed before: those writes land behind the scan.
+        let listed: Arc<Mutex<Vec<PathBuf>>> = Arc::default();
+        let seen = Arc::clone(&listed);
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            threads: Some(1),
+            directory_notifier: Some(Arc::new(move |dir: &Path| {
+                let mut seen = seen.lock().unwrap();
+                if let Some(previous) = seen.last() {
+                    write_file_sync(previous.join("late.bin"), vec![0u8; 100]).unwrap();
+                }
+                seen.push(dir.to_path_buf());
+            })),
+            ..Default::default()
+        };
+        let mut watch = Watch::start(&root, &opts, Duration::from_secs(60)).unwrap();
+
+        let late = listed.lock().unwrap().len() as u64 - 1;
+        assert_eq!(late, 5);
+        poll_until(&mut watch, |w| w.tree().total_size() == 50 + 100 * late);
+        let fresh = dua::scan_summary(
+            &root,
+            &ScanOptions {
+                basis: SizeBasis::Logical,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for entry in &fresh.entries {
+            assert_eq!(
+                size_of(&watch, Path::new(&entry.path)),
+                Some(entry.size_bytes),
+                "{}",
+                entry.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_watch_reports_removed_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("data");
+        fs::create_dir_all(&root).unwrap();
+
+        let mut watch =
+            Watch::start(&root, &ScanOptions::default(), Duration::from_secs(60)).unwrap();
+        fs::remove_dir(&root).unwrap();
+
+        let give_up = Instant::now() + Duration::from_secs(5);
+        loop {
+            match watch.poll(Duration::from_millis(100)) {
+                Err(dua::Error::Io(err)) => {
+                    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+                    break;
+                }
+                Err(other) => panic!("unexpected error: {other:?}"),
+                Ok(_) => assert!(Instant::now() < give_up, "removal never reported"),
+            }
+        }
+    }
+}
@@ -3,13 +3,21 @@
 mod fixtures;
 
 mod integration {
+    mod test_baseline;
     mod test_errors;
+    mod test_hardlinks;
+    mod test_mounts;
     mod test_perf_smoke;
     mod test_resilience;
+    mod test_resume;
     mod test_scan;
     mod test_snapshot_errors;
+    mod test_snapshot_reader;
     mod test_snapshot_roundtrip;
+    mod test_snapshot_sorted;
+    mod test_symlinks;
     mod test_view_drill_down;
+    mod test_watch;
 }
 
 mod contract {
@@ -19,7 +27,17 @@ mod contract {
 
 mod unit {
     mod aggregate_tests;
+    mod budget_tests;
+    mod cli_args_tests;
     mod depth_tests;
+    mod detect_tests;
+    mod diff_tests;
+    mod filter_tests;
+    mod hardlinks_tests;
+    mod ignore_tests;
     mod normalize_path_tests;
+    mod throttle_tests;
     mod traverse_tests;
+    mod tui_tests;
+    mod watch_tests;
 }
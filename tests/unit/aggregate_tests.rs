@@ -19,6 +19,7 @@ mod tests {
                 size_bytes: 100,
                 file_count: 1,
                 dir_count: 0,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: "b".to_string(),
@@ -27,6 +28,7 @@ mod tests {
                 size_bytes: 500,
                 file_count: 2,
                 dir_count: 0,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: "c".to_string(),
@@ -35,6 +37,7 @@ mod tests {
                 size_bytes: 200,
                 file_count: 3,
                 dir_count: 0,
+                ..Default::default()
             },
         ];
 
@@ -55,6 +58,7 @@ mod tests {
                 size_bytes: 100,
                 file_count: 1,
                 dir_count: 0,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: "b".to_string(),
@@ -63,6 +67,7 @@ mod tests {
                 size_bytes: 500,
                 file_count: 2,
                 dir_count: 0,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: "c".to_string(),
@@ -71,6 +76,7 @@ mod tests {
                 size_bytes: 200,
                 file_count: 3,
                 dir_count: 0,
+                ..Default::default()
             },
         ];
 
@@ -91,6 +97,7 @@ mod tests {
                 size_bytes: 100,
                 file_count: 5,
                 dir_count: 0,
+                ..Default::default()
             },
             DirectoryEntry {
                 path: "b".to_string(),
@@ -99,6 +106,7 @@ mod tests {
                 size_bytes: 500,
                 file_count: 2,
                 dir_count: 0,
+                ..Default::default()
             },
         ];
 
@@ -119,6 +127,7 @@ mod tests {
                 size_bytes: 1024,
                 file_count: 0,
                 dir_count: 0,
+                ..Default::default()
             },
             EntryKind::File,
         );
@@ -132,6 +141,7 @@ mod tests {
                 size_bytes: 4096,
                 file_count: 3,
                 dir_count: 1,
+                ..Default::default()
             },
             EntryKind::Directory,
         );
@@ -155,6 +165,7 @@ mod tests {
                 size_bytes: 512,
                 file_count: 0,
                 dir_count: 0,
+                ..Default::default()
             },
             EntryKind::File,
         );
@@ -168,6 +179,7 @@ mod tests {
                 size_bytes: 2048,
                 file_count: 0,
                 dir_count: 0,
+                ..Default::default()
             },
             EntryKind::File,
         );
@@ -180,6 +192,7 @@ mod tests {
                 size_bytes: 8192,
                 file_count: 5,
                 dir_count: 1,
+                ..Default::default()
             },
             EntryKind::Directory,
         );
@@ -220,6 +233,7 @@ mod tests {
                             size_bytes: SIZE_PER_FILE,
                             file_count: 0,
                             dir_count: 0,
+                            ..Default::default()
                         },
                         EntryKind::File,
                     );
@@ -234,6 +248,7 @@ mod tests {
                         size_bytes: SIZE_PER_FILE * files_per_shard_u64,
                         file_count: files_per_shard_u32,
                         dir_count: 0,
+                        ..Default::default()
                     },
                     EntryKind::Directory,
                 );
@@ -0,0 +1,26 @@
+//! Unit tests for the traversal fd budget
+#[cfg(test)]
+mod tests {
+    use dua::services::traverse::budget::{FdBudget, default_max_open_fds};
+
+    #[test]
+    fn acquire_grants_at_most_the_remaining_budget() {
+        let budget = FdBudget::new(5);
+        assert_eq!(budget.acquire(3), 3);
+        assert_eq!(budget.acquire(3), 2);
+        assert_eq!(budget.acquire(1), 0);
+        assert_eq!(budget.held(), 5);
+
+        budget.release(4);
+        assert_eq!(budget.acquire(10), 4);
+        assert_eq!(budget.held(), 5);
+    }
+
+    #[test]
+    fn budget_is_never_empty() {
+        let budget = FdBudget::new(0);
+        assert_eq!(budget.limit(), 1);
+        assert_eq!(budget.acquire(2), 1);
+        assert!(default_max_open_fds() >= 1);
+    }
+}
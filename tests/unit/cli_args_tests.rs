@@ -1,108 +1,421 @@
 //! Unit tests for CLI argument parsing extensions
 #[cfg(test)]
 mod tests {
-	use dua::cli::args::{parse_args, Command};
-
-	fn make_args(raw: &[&str]) -> Vec<String> {
-		raw.iter().map(|s| s.to_string()).collect()
-	}
-
-	#[test]
-	fn parse_scan_with_legacy_traversal_flag() {
-		let argv = make_args(&[
-			"dua",
-			"scan",
-			"/tmp/work",
-			"--snapshot",
-			"out.parquet",
-			"--legacy-traversal",
-		]);
-
-		let parsed = parse_args(&argv).expect("parse scan args");
-		let Command::Scan(scan) = parsed.command else {
-			panic!("expected scan command");
-		};
-
-		assert!(scan.legacy_traversal);
-		assert!(scan.strategy_override.is_none());
-	}
-
-	#[test]
-	fn parse_scan_with_strategy_override() {
-		let argv = make_args(&[
-			"dua",
-			"scan",
-			"/tmp/work",
-			"--snapshot",
-			"out.parquet",
-			"--strategy",
-			"posix",
-		]);
-
-		let parsed = parse_args(&argv).expect("parse scan args");
-		let Command::Scan(scan) = parsed.command else {
-			panic!("expected scan command");
-		};
-
-		assert_eq!(scan.strategy_override.as_deref(), Some("posix"));
-		assert!(!scan.legacy_traversal);
-	}
-
-	#[test]
-	fn parse_scan_with_conflicting_strategy_flags() {
-		let argv = make_args(&[
-			"dua",
-			"scan",
-			"/tmp/work",
-			"--legacy-traversal",
-			"--strategy",
-			"windows",
-		]);
-
-		let parsed = parse_args(&argv).expect("parse scan args");
-		let Command::Scan(scan) = parsed.command else {
-			panic!("expected scan command");
-		};
-
-		assert!(scan.legacy_traversal);
-		assert_eq!(scan.strategy_override.as_deref(), Some("windows"));
-	}
-
-	#[test]
-	fn strategy_flag_requires_value() {
-		let argv = make_args(&["dua", "scan", "/tmp/work", "--strategy"]);
-		let err = parse_args(&argv).expect_err("strategy flag without value should fail");
-		assert!(err.contains("--strategy requires a value"));
-	}
-
-	#[test]
-	fn progress_interval_flag_sets_override() {
-		let argv = make_args(&[
-			"dua",
-			"scan",
-			"/tmp/work",
-			"--progress-interval",
-			"5",
-		]);
-
-		let parsed = parse_args(&argv).expect("parse scan args");
-		let Command::Scan(scan) = parsed.command else {
-			panic!("expected scan command");
-		};
-
-		assert_eq!(scan.progress_interval_secs, Some(5));
-	}
-
-	#[test]
-	fn progress_interval_requires_positive_value() {
-		let argv = make_args(&["dua", "scan", "/tmp/work", "--progress-interval", "0"]);
-		let err = parse_args(&argv)
-			.expect_err("progress interval of zero should be rejected");
-		assert!(err.contains("greater than zero"));
-
-		let missing_value = make_args(&["dua", "scan", "/tmp/work", "--progress-interval"]);
-		let err = parse_args(&missing_value)
-			.expect_err("progress interval flag without value should fail");
-		assert!(err.contains("--progress-interval requires a value"));
-	}
+    use dua::cli::args::{Command, SnapshotCommand, parse_args};
+
+    fn make_args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| (*s).to_string()).collect()
+    }
+
+    #[test]
+    fn parse_scan_with_legacy_traversal_flag() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/tmp/work",
+            "--snapshot",
+            "out.parquet",
+            "--legacy-traversal",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert!(scan.legacy_traversal);
+        assert!(scan.strategy_override.is_none());
+    }
+
+    #[test]
+    fn parse_scan_with_strategy_override() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/tmp/work",
+            "--snapshot",
+            "out.parquet",
+            "--strategy",
+            "posix",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert_eq!(scan.strategy_override.as_deref(), Some("posix"));
+        assert!(!scan.legacy_traversal);
+    }
+
+    #[test]
+    fn parse_scan_with_conflicting_strategy_flags() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/tmp/work",
+            "--legacy-traversal",
+            "--strategy",
+            "windows",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert!(scan.legacy_traversal);
+        assert_eq!(scan.strategy_override.as_deref(), Some("windows"));
+    }
+
+    #[test]
+    fn strategy_flag_requires_value() {
+        let argv = make_args(&["dua", "scan", "/tmp/work", "--strategy"]);
+        let err = parse_args(&argv).expect_err("strategy flag without value should fail");
+        assert!(err.contains("--strategy requires a value"));
+    }
+
+    #[test]
+    fn progress_interval_flag_sets_override() {
+        let argv = make_args(&["dua", "scan", "/tmp/work", "--progress-interval", "5"]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert_eq!(scan.progress_interval_secs, Some(5));
+    }
+
+    #[test]
+    fn progress_interval_requires_positive_value() {
+        let argv = make_args(&["dua", "scan", "/tmp/work", "--progress-interval", "0"]);
+        let err = parse_args(&argv).expect_err("progress interval of zero should be rejected");
+        assert!(err.contains("greater than zero"));
+
+        let missing_value = make_args(&["dua", "scan", "/tmp/work", "--progress-interval"]);
+        let err = parse_args(&missing_value)
+            .expect_err("progress interval flag without value should fail");
+        assert!(err.contains("--progress-interval requires a value"));
+    }
+
+    #[test]
+    fn thread_and_fd_limits_require_positive_values() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/tmp/work",
+            "--threads",
+            "4",
+            "--max-open-fds",
+            "256",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert_eq!(scan.threads, Some(4));
+        assert_eq!(scan.max_open_fds, Some(256));
+
+        let zero = make_args(&["dua", "scan", "/tmp/work", "--threads", "0"]);
+        let err = parse_args(&zero).expect_err("zero threads should be rejected");
+        assert!(err.contains("--threads must be greater than zero"));
+
+        let bad = make_args(&["dua", "scan", "/tmp/work", "--max-open-fds", "many"]);
+        let err = parse_args(&bad).expect_err("non-numeric fd budget should be rejected");
+        assert!(err.contains("--max-open-fds must be a positive integer"));
+    }
+
+    #[test]
+    fn resume_flag_takes_a_snapshot() {
+        let argv = make_args(&["dua", "scan", "/archive", "--resume", "archive.parquet"]);
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(scan.resume.as_deref(), Some("archive.parquet"));
+        assert_eq!(scan.snapshot, None);
+
+        let missing = make_args(&["dua", "scan", "/archive", "--resume"]);
+        let err = parse_args(&missing).expect_err("missing snapshot");
+        assert!(err.contains("--resume requires a snapshot path"));
+    }
+
+    #[test]
+    fn watch_command_parses_intervals() {
+        let argv = make_args(&[
+            "dua",
+            "watch",
+            "/var/log",
+            "--snapshot",
+            "live.parquet",
+            "--refresh",
+            "2",
+            "--window",
+            "30",
+            "--exclude",
+            "*.gz",
+        ]);
+        let parsed = parse_args(&argv).expect("parse watch args");
+        let Command::Watch(watch) = parsed.command else {
+            panic!("expected watch command");
+        };
+        assert_eq!(watch.path, "/var/log");
+        assert_eq!(watch.snapshot.as_deref(), Some("live.parquet"));
+        assert_eq!(watch.refresh_secs, 2);
+        assert_eq!(watch.window_secs, 30);
+        assert_eq!(watch.checkpoint_secs, 300);
+        assert_eq!(watch.top, 10);
+        assert_eq!(watch.excludes, vec!["*.gz".to_string()]);
+
+        let zero = make_args(&["dua", "watch", "/var/log", "--checkpoint-every", "0"]);
+        let err = parse_args(&zero).expect_err("zero interval");
+        assert!(err.contains("--checkpoint-every must be greater than zero"));
+    }
+
+    #[test]
+    fn baseline_flag_takes_a_snapshot() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/archive",
+            "--baseline",
+            "monday.parquet",
+            "--snapshot",
+            "tuesday.parquet",
+        ]);
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(scan.baseline.as_deref(), Some("monday.parquet"));
+        assert_eq!(scan.snapshot.as_deref(), Some("tuesday.parquet"));
+
+        let missing = make_args(&["dua", "scan", "/archive", "--baseline"]);
+        let err = parse_args(&missing).expect_err("missing snapshot");
+        assert!(err.contains("--baseline requires a snapshot path"));
+    }
+
+    #[test]
+    fn gentle_mode_flags() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/srv/share",
+            "--nice",
+            "--ionice",
+            "idle",
+            "--max-ops-per-sec",
+            "2000",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert!(scan.nice);
+        assert_eq!(scan.ionice.as_deref(), Some("idle"));
+        assert_eq!(scan.max_ops_per_sec, Some(2000));
+
+        let zero = make_args(&["dua", "scan", "/srv", "--max-ops-per-sec", "0"]);
+        let err = parse_args(&zero).expect_err("zero rate should be rejected");
+        assert!(err.contains("--max-ops-per-sec must be greater than zero"));
+
+        let missing = make_args(&["dua", "scan", "/srv", "--ionice"]);
+        let err = parse_args(&missing).expect_err("ionice without class should fail");
+        assert!(err.contains("--ionice requires a class"));
+    }
+
+    #[test]
+    fn exclude_flags_accumulate() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/tmp/work",
+            "--exclude",
+            ".git",
+            "--exclude",
+            "node_modules",
+            "--exclude-from",
+            "ignore.txt",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert_eq!(scan.excludes, vec![".git", "node_modules"]);
+        assert_eq!(scan.exclude_from, vec!["ignore.txt"]);
+
+        let missing = make_args(&["dua", "scan", "/tmp/work", "--exclude"]);
+        let err = parse_args(&missing).expect_err("exclude flag without value should fail");
+        assert!(err.contains("--exclude requires a pattern"));
+    }
+
+    #[test]
+    fn collapse_ignored_implies_respect_ignore_files() {
+        let argv = make_args(&["dua", "scan", "/tmp/work", "--collapse-ignored"]);
+
+        let parsed = parse_args(&argv).expect("parse scan args");
+        let Command::Scan(scan) = parsed.command else {
+            panic!("expected scan command");
+        };
+
+        assert!(scan.respect_ignore_files);
+        assert!(scan.collapse_ignored);
+    }
+
+    #[test]
+    fn mount_flags_toggle_cross_mounts_last_one_wins() {
+        let scan_with = |flags: &[&str]| {
+            let mut argv = vec!["dua", "scan", "/home"];
+            argv.extend_from_slice(flags);
+            let Command::Scan(scan) = parse_args(&make_args(&argv)).unwrap().command else {
+                panic!("expected scan command");
+            };
+            scan.cross_mounts
+        };
+
+        assert!(!scan_with(&[]));
+        assert!(scan_with(&["--cross-mounts"]));
+        assert!(!scan_with(&["--cross-mounts", "--one-file-system"]));
+        assert!(scan_with(&["--one-file-system", "--cross-mounts"]));
+    }
+
+    #[test]
+    fn parse_hardlink_and_symlink_flags() {
+        let argv = make_args(&[
+            "dua",
+            "scan",
+            "/var/lib/containers",
+            "--hardlinks",
+            "count",
+            "--follow-symlinks",
+        ]);
+
+        let Command::Scan(scan) = parse_args(&argv).expect("parse scan args").command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(scan.hardlinks, "count");
+        assert!(scan.follow_symlinks);
+
+        let Command::Scan(defaults) = parse_args(&make_args(&["dua", "scan", "/tmp"]))
+            .unwrap()
+            .command
+        else {
+            panic!("expected scan command");
+        };
+        assert_eq!(defaults.hardlinks, "dedupe");
+        assert!(!defaults.follow_symlinks);
+
+        let missing = make_args(&["dua", "scan", "/tmp", "--hardlinks"]);
+        let err = parse_args(&missing).expect_err("hardlinks flag without value should fail");
+        assert!(err.contains("--hardlinks requires a value"));
+    }
+
+    #[test]
+    fn parse_diff_command() {
+        let argv = make_args(&[
+            "dua",
+            "diff",
+            "old.parquet",
+            "new.parquet",
+            "--path",
+            "/var",
+            "--top",
+            "5",
+            "--json",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse diff args");
+        let Command::Diff(diff) = parsed.command else {
+            panic!("expected diff command");
+        };
+
+        assert_eq!(diff.old_snapshot, "old.parquet");
+        assert_eq!(diff.new_snapshot, "new.parquet");
+        assert_eq!(diff.path.as_deref(), Some("/var"));
+        assert_eq!(diff.top, 5);
+        assert!(diff.json);
+
+        let missing = make_args(&["dua", "diff", "old.parquet"]);
+        let err = parse_args(&missing).expect_err("diff requires two snapshots");
+        assert!(err.contains("OLD_SNAPSHOT NEW_SNAPSHOT"));
+    }
+
+    #[test]
+    fn parse_tui_command() {
+        let argv = make_args(&[
+            "dua",
+            "tui",
+            "usage.parquet",
+            "--path",
+            "/data",
+            "--sort",
+            "files",
+        ]);
+
+        let parsed = parse_args(&argv).expect("parse tui args");
+        let Command::Tui(tui) = parsed.command else {
+            panic!("expected tui command");
+        };
+
+        assert_eq!(tui.from_snapshot, "usage.parquet");
+        assert_eq!(tui.path.as_deref(), Some("/data"));
+        assert_eq!(tui.sort, "files");
+
+        let missing = make_args(&["dua", "tui"]);
+        assert!(parse_args(&missing).is_err());
+    }
+
+    #[test]
+    fn parse_hardlinks_command() {
+        let argv = make_args(&["dua", "hardlinks", "usage.parquet", "--top", "3", "--json"]);
+        let parsed = parse_args(&argv).expect("parse hardlinks args");
+        let Command::Hardlinks(hardlinks) = parsed.command else {
+            panic!("expected hardlinks command");
+        };
+        assert_eq!(hardlinks.snapshot, "usage.parquet");
+        assert_eq!(hardlinks.top, 3);
+        assert!(hardlinks.json);
+
+        let defaults = parse_args(&make_args(&["dua", "hardlinks", "usage.parquet"])).unwrap();
+        let Command::Hardlinks(hardlinks) = defaults.command else {
+            panic!("expected hardlinks command");
+        };
+        assert_eq!(hardlinks.top, 10);
+        assert!(!hardlinks.json);
+
+        assert!(parse_args(&make_args(&["dua", "hardlinks"])).is_err());
+        assert!(parse_args(&make_args(&["dua", "hardlinks", "a", "--top", "x"])).is_err());
+    }
+
+    #[test]
+    fn parse_snapshot_upgrade_command() {
+        let argv = make_args(&["dua", "snapshot", "upgrade", "a.parquet", "b.parquet"]);
+        let parsed = parse_args(&argv).expect("parse snapshot upgrade");
+        let Command::Snapshot(SnapshotCommand::Upgrade(upgrade)) = parsed.command else {
+            panic!("expected snapshot upgrade command");
+        };
+        assert_eq!(upgrade.snapshots, vec!["a.parquet", "b.parquet"]);
+        assert!(upgrade.output.is_none());
+
+        let conflicting = make_args(&[
+            "dua",
+            "snapshot",
+            "upgrade",
+            "a.parquet",
+            "b.parquet",
+            "--output",
+            "c.parquet",
+        ]);
+        assert!(parse_args(&conflicting).is_err());
+
+        let unknown = make_args(&["dua", "snapshot", "compact", "a.parquet"]);
+        assert!(parse_args(&unknown).is_err());
+    }
 }
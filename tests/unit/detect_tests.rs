@@ -0,0 +1,129 @@
+//! Unit tests for filesystem detection and mount table parsing
+#[cfg(test)]
+mod tests {
+    use dua::StrategyKind;
+    use dua::services::traverse::detect::{self, FilesystemKind};
+    use dua::services::traverse::mounts::{mount_for_path, parse_mountinfo};
+    use std::path::{Path, PathBuf};
+
+    const MOUNTINFO: &str = "\
+22 1 253:1 / / rw,relatime shared:1 - ext4 /dev/vda1 rw
+40 22 0:35 / /mnt/shared rw,relatime shared:20 - nfs4 filer:/export/home rw,vers=4.2
+41 22 0:36 / /var/lib/docker/overlay2/merged rw - overlay overlay rw,lowerdir=/l
+42 40 0:37 / /mnt/shared/My\\040Files rw - fuse.sshfs user@host:/srv rw
+43 22 0:38 / /mnt/shared rw master:3 - cifs //nas/share rw
+not a mountinfo line
+";
+
+    #[test]
+    fn parses_mountinfo_lines_with_optional_fields_and_escapes() {
+        let table = parse_mountinfo(MOUNTINFO);
+        assert_eq!(table.len(), 5, "malformed lines are skipped");
+
+        assert_eq!(table[0].mount_point, PathBuf::from("/"));
+        assert_eq!(table[0].fs_type, "ext4");
+        assert_eq!((table[0].major, table[0].minor), (253, 1));
+        assert_eq!(table[1].source, "filer:/export/home");
+        assert_eq!(table[3].mount_point, PathBuf::from("/mnt/shared/My Files"));
+        assert_eq!(table[3].fs_type, "fuse.sshfs");
+    }
+
+    #[test]
+    fn picks_deepest_and_latest_matching_mount() {
+        let table = parse_mountinfo(MOUNTINFO);
+
+        let root = mount_for_path(&table, Path::new("/home/user"), None).unwrap();
+        assert_eq!(root.fs_type, "ext4");
+
+        let fuse = mount_for_path(&table, Path::new("/mnt/shared/My Files/a"), None).unwrap();
+        assert_eq!(fuse.fs_type, "fuse.sshfs");
+
+        // Two mounts share /mnt/shared: the later one shadows the earlier,
+        // unless the device identifies the other.
+        let shadowed = mount_for_path(&table, Path::new("/mnt/shared/x"), None).unwrap();
+        assert_eq!(shadowed.fs_type, "cifs");
+        let by_device = mount_for_path(&table, Path::new("/mnt/shared/x"), Some((0, 35))).unwrap();
+        assert_eq!(by_device.fs_type, "nfs4");
+
+        assert!(
+            mount_for_path(&table, Path::new("/mnt/sharedx"), None)
+                .is_some_and(|m| m.fs_type == "ext4")
+        );
+    }
+
+    #[test]
+    fn classifies_statfs_magics_and_type_names() {
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0xEF53),
+            FilesystemKind::Ext
+        );
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0x9123_683E),
+            FilesystemKind::Btrfs
+        );
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0x6969),
+            FilesystemKind::Nfs
+        );
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0xFF53_4D42),
+            FilesystemKind::Cifs
+        );
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0x794C_7630),
+            FilesystemKind::Overlay
+        );
+        assert_eq!(
+            FilesystemKind::from_statfs_magic(0x1234),
+            FilesystemKind::Other
+        );
+
+        assert_eq!(FilesystemKind::from_fs_type("xfs"), FilesystemKind::Xfs);
+        assert_eq!(FilesystemKind::from_fs_type("zfs"), FilesystemKind::Zfs);
+        assert_eq!(FilesystemKind::from_fs_type("tmpfs"), FilesystemKind::Tmpfs);
+        assert_eq!(
+            FilesystemKind::from_fs_type("fuse.sshfs"),
+            FilesystemKind::Fuse
+        );
+        assert_eq!(FilesystemKind::from_fs_type("smb3"), FilesystemKind::Cifs);
+        assert_eq!(FilesystemKind::from_fs_type("proc"), FilesystemKind::Other);
+    }
+
+    #[test]
+    fn network_and_fuse_filesystems_use_legacy_traversal() {
+        for kind in [
+            FilesystemKind::Nfs,
+            FilesystemKind::Cifs,
+            FilesystemKind::Fuse,
+        ] {
+            assert_eq!(detect::strategy_for_filesystem(kind), StrategyKind::Legacy);
+        }
+        for kind in [
+            FilesystemKind::Btrfs,
+            FilesystemKind::Xfs,
+            FilesystemKind::Zfs,
+            FilesystemKind::Tmpfs,
+            FilesystemKind::Overlay,
+        ] {
+            assert_eq!(
+                detect::strategy_for_filesystem(kind),
+                StrategyKind::PosixOptimized
+            );
+        }
+        assert!(FilesystemKind::Nfs.is_network());
+        assert!(!FilesystemKind::Overlay.is_network());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reports_mount_details_for_existing_paths() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let info = detect::filesystem_info_for_path(temp_dir.path());
+        assert_eq!(info.kind, detect::filesystem_kind_for_path(temp_dir.path()));
+        if let Some(mount_point) = info.mount_point {
+            let canonical = temp_dir.path().canonicalize().unwrap();
+            assert!(canonical.starts_with(mount_point));
+            assert!(info.fs_type.is_some());
+        }
+    }
+}
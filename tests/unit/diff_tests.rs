@@ -0,0 +1,142 @@
+//! Unit tests for snapshot diffing
+
+#[cfg(test)]
+mod tests {
+    use dua::models::{DirectoryEntry, SnapshotMeta};
+    use dua::services::diff::{DiffStatus, diff_subtree, ensure_comparable};
+
+    fn entry(path: &str, parent: Option<&str>, depth: u16, size_bytes: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: parent.map(str::to_string),
+            depth,
+            size_bytes,
+            file_count: 0,
+            dir_count: 0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn ranks_changes_by_absolute_delta() {
+        let old = vec![
+            entry("/r", None, 0, 1_000),
+            entry("/r/logs", Some("/r"), 1, 100),
+            entry("/r/cache", Some("/r"), 1, 600),
+            entry("/r/old", Some("/r"), 1, 250),
+            entry("/r/same", Some("/r"), 1, 50),
+        ];
+        let new = vec![
+            entry("/r", None, 0, 5_000),
+            entry("/r/logs", Some("/r"), 1, 4_100),
+            entry("/r/cache", Some("/r"), 1, 100),
+            entry("/r/new", Some("/r"), 1, 750),
+            entry("/r/same", Some("/r"), 1, 50),
+        ];
+
+        let diff = diff_subtree(&old, &new, "/r", None).expect("root present");
+
+        assert_eq!(diff.root.delta_bytes, 4_000);
+        let paths: Vec<&str> = diff.entries.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, vec!["/r/logs", "/r/new", "/r/cache", "/r/old"]);
+
+        let statuses: Vec<DiffStatus> = diff.entries.iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                DiffStatus::Grown,
+                DiffStatus::Added,
+                DiffStatus::Shrunk,
+                DiffStatus::Removed
+            ]
+        );
+        assert_eq!(diff.entries[2].delta_bytes, -500);
+        assert_eq!(diff.entries[3].new_size, None);
+    }
+
+    #[test]
+    fn drills_into_nested_path_and_limits() {
+        let old = vec![
+            entry("/r", None, 0, 10),
+            entry("/r/a", Some("/r"), 1, 10),
+            entry("/r/a/x", Some("/r/a"), 2, 5),
+            entry("/r/a/y", Some("/r/a"), 2, 5),
+        ];
+        let new = vec![
+            entry("/r", None, 0, 40),
+            entry("/r/a", Some("/r"), 1, 40),
+            entry("/r/a/x", Some("/r/a"), 2, 25),
+            entry("/r/a/y", Some("/r/a"), 2, 15),
+        ];
+
+        let diff = diff_subtree(&old, &new, "/r/a", Some(1)).expect("path present");
+        assert_eq!(diff.entries.len(), 1);
+        assert_eq!(diff.entries[0].path, "/r/a/x");
+
+        assert!(diff_subtree(&old, &new, "/missing", None).is_none());
+    }
+
+    #[test]
+    fn ranks_growth_at_every_depth_and_reports_subtree_tops() {
+        let old = vec![
+            entry("/r", None, 0, 100),
+            entry("/r/build", Some("/r"), 1, 100),
+            entry("/r/build/cache", Some("/r/build"), 2, 60),
+            entry("/r/build/cache/objs", Some("/r/build/cache"), 3, 60),
+            entry("/r/build/tmp", Some("/r/build"), 2, 40),
+            entry("/r/build/tmp/x", Some("/r/build/tmp"), 3, 40),
+        ];
+        let new = vec![
+            entry("/r", None, 0, 5_060),
+            entry("/r/build", Some("/r"), 1, 5_060),
+            entry("/r/build/cache", Some("/r/build"), 2, 4_060),
+            entry("/r/build/cache/objs", Some("/r/build/cache"), 3, 4_060),
+            entry("/r/build/out", Some("/r/build"), 2, 1_000),
+            entry("/r/build/out/a.o", Some("/r/build/out"), 3, 1_000),
+        ];
+
+        let diff = diff_subtree(&old, &new, "/r", None).expect("root present");
+        let changes: Vec<(&str, i64)> = diff
+            .entries
+            .iter()
+            .map(|e| (e.path.as_str(), e.delta_bytes))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                ("/r/build", 4_960),
+                ("/r/build/cache", 4_000),
+                ("/r/build/cache/objs", 4_000),
+                ("/r/build/out", 1_000),
+                ("/r/build/tmp", -40),
+            ]
+        );
+        assert_eq!(diff.entries[3].status, DiffStatus::Added);
+        assert_eq!(diff.entries[4].status, DiffStatus::Removed);
+    }
+
+    #[test]
+    fn refuses_snapshots_of_other_roots_or_bases() {
+        let meta = |root: &str, basis: &str| SnapshotMeta {
+            scan_root: root.to_string(),
+            started_at: String::new(),
+            finished_at: String::new(),
+            size_basis: basis.to_string(),
+            hardlink_policy: "dedupe".to_string(),
+            excludes: Vec::new(),
+            ignore_files: "disabled".to_string(),
+            max_depth: None,
+            follow_symlinks: false,
+            cross_filesystem: false,
+            strategy: "posix".to_string(),
+            incomplete: false,
+            incomplete_reason: None,
+            incomplete_dirs: Vec::new(),
+            skipped_mounts: Vec::new(),
+        };
+
+        assert!(ensure_comparable(&meta("/r", "physical"), &meta("/r", "physical")).is_ok());
+        assert!(ensure_comparable(&meta("/r", "physical"), &meta("/s", "physical")).is_err());
+        assert!(ensure_comparable(&meta("/r", "physical"), &meta("/r", "logical")).is_err());
+    }
+}
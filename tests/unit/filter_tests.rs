@@ -0,0 +1,66 @@
+//! Unit tests for exclusion pattern matching
+
+#[cfg(test)]
+mod tests {
+    use dua::services::filter::{ExcludeFilter, read_exclude_file};
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn name_patterns_match_any_depth() {
+        let filter = ExcludeFilter::new(&[".git", "node_modules"]);
+
+        assert!(filter.is_excluded("/repo/.git", true));
+        assert!(filter.is_excluded("/repo/web/node_modules", true));
+        assert!(!filter.is_excluded("/repo/.github", true));
+        assert!(!filter.is_excluded("/repo/src/main.rs", false));
+    }
+
+    #[test]
+    fn wildcards_do_not_cross_separators() {
+        let filter = ExcludeFilter::new(&["*.log", "cache?", "[ab]*.tmp"]);
+
+        assert!(filter.is_excluded("/var/app/server.log", false));
+        assert!(filter.is_excluded("/var/app/cache1", true));
+        assert!(filter.is_excluded("/var/app/b-123.tmp", false));
+        assert!(!filter.is_excluded("/var/app/c-123.tmp", false));
+        assert!(!filter.is_excluded("/var/app/cache", true));
+    }
+
+    #[test]
+    fn path_patterns_match_trailing_components() {
+        let filter = ExcludeFilter::new(&["build/cache", "/data/tmp", "docs/**/drafts"]);
+
+        assert!(filter.is_excluded("/home/user/build/cache", true));
+        assert!(!filter.is_excluded("/home/user/build/cache2", true));
+        assert!(filter.is_excluded("/data/tmp", true));
+        assert!(!filter.is_excluded("/other/data/tmp", true));
+        assert!(filter.is_excluded("/repo/docs/drafts", true));
+        assert!(filter.is_excluded("/repo/docs/a/b/drafts", true));
+    }
+
+    #[test]
+    fn trailing_slash_limits_to_directories() {
+        let filter = ExcludeFilter::new(&["target/"]);
+
+        assert!(filter.is_excluded("/repo/target", true));
+        assert!(!filter.is_excluded("/repo/target", false));
+    }
+
+    #[test]
+    fn empty_filter_excludes_nothing() {
+        let filter = ExcludeFilter::new::<&str>(&[]);
+        assert!(filter.is_empty());
+        assert!(!filter.is_excluded("/anything", true));
+    }
+
+    #[test]
+    fn exclude_file_skips_comments_and_blank_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("excludes.txt");
+        fs::write(&path, "# vcs\n.git\n\n  node_modules  \n*.log\n").unwrap();
+
+        let patterns = read_exclude_file(&path).unwrap();
+        assert_eq!(patterns, vec![".git", "node_modules", "*.log"]);
+    }
+}
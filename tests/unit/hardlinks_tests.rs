@@ -0,0 +1,86 @@
+//! Unit tests for the hardlink report
+
+#[cfg(test)]
+mod tests {
+    use dua::models::{DirectoryEntry, EntryKind};
+    use dua::services::hardlinks::hardlink_report;
+
+    fn linked(path: &str, parent: &str, inode: u64, nlink: u64, size_bytes: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: Some(parent.to_string()),
+            kind: EntryKind::File,
+            size_bytes,
+            device: Some(1),
+            inode: Some(inode),
+            nlink: Some(nlink),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn groups_links_by_inode_in_path_order() {
+        let report = hardlink_report(vec![
+            linked("/b/daily.1/big", "/b/daily.1", 7, 2, 0),
+            linked("/b/daily.0/big", "/b/daily.0", 7, 2, 9_000),
+            linked("/b/daily.0/small", "/b/daily.0", 8, 3, 0),
+            linked("/b/daily.0/small.bak", "/b/daily.0", 8, 3, 10),
+            // A single link found in the scan is not a group.
+            linked("/b/lonely", "/b", 9, 2, 500),
+            DirectoryEntry {
+                kind: EntryKind::File,
+                nlink: Some(1),
+                ..linked("/b/plain", "/b", 10, 1, 40)
+            },
+        ]);
+
+        assert_eq!(report.groups.len(), 2);
+        let big = &report.groups[0];
+        assert_eq!(big.inode, 7);
+        assert_eq!(big.size_bytes, 9_000);
+        assert_eq!(big.paths, vec!["/b/daily.0/big", "/b/daily.1/big"]);
+        assert_eq!(big.directories, 2);
+
+        let small = &report.groups[1];
+        assert_eq!(small.nlink, 3);
+        assert_eq!(
+            small.paths,
+            vec!["/b/daily.0/small", "/b/daily.0/small.bak"]
+        );
+        assert_eq!(small.directories, 1);
+
+        assert_eq!(report.unique_bytes, 9_010);
+        assert_eq!(report.apparent_bytes, 18_020);
+    }
+
+    #[test]
+    fn directories_report_shared_and_charged_bytes() {
+        let report = hardlink_report(vec![
+            linked("/b/x/a", "/b/x", 1, 2, 100),
+            linked("/b/y/a", "/b/y", 1, 2, 0),
+            linked("/b/y/b", "/b/y", 2, 2, 30),
+            linked("/b/z/b", "/b/z", 2, 2, 0),
+        ]);
+
+        let dirs: Vec<(&str, u64, u64, u64)> = report
+            .directories
+            .iter()
+            .map(|d| {
+                (
+                    d.path.as_str(),
+                    d.linked_files,
+                    d.shared_bytes,
+                    d.charged_bytes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            dirs,
+            vec![
+                ("/b/y", 2, 130, 30),
+                ("/b/x", 1, 100, 100),
+                ("/b/z", 1, 30, 0),
+            ]
+        );
+    }
+}
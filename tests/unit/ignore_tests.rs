@@ -0,0 +1,65 @@
+//! Unit tests for hierarchical ignore-file rules
+
+#[cfg(test)]
+mod tests {
+    use dua::services::ignore::IgnoreStack;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn normalized(path: &std::path::Path) -> String {
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    #[test]
+    fn rules_apply_below_their_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("pkg")).unwrap();
+        fs::write(root.join(".gitignore"), "target/\n*.o\n/dist\n").unwrap();
+
+        let root_norm = normalized(root);
+        let stack = IgnoreStack::new().descend(root, &root_norm);
+
+        assert!(stack.is_ignored(&format!("{root_norm}/target"), true));
+        assert!(!stack.is_ignored(&format!("{root_norm}/target"), false));
+        assert!(stack.is_ignored(&format!("{root_norm}/pkg/main.o"), false));
+        assert!(stack.is_ignored(&format!("{root_norm}/dist"), true));
+        assert!(!stack.is_ignored(&format!("{root_norm}/pkg/dist"), true));
+        assert!(!stack.is_ignored(&format!("{root_norm}/pkg/main.c"), false));
+    }
+
+    #[test]
+    fn deeper_files_and_negations_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let pkg = root.join("pkg");
+        fs::create_dir_all(&pkg).unwrap();
+        fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        fs::write(pkg.join(".duaignore"), "!keep.log\n").unwrap();
+
+        let root_norm = normalized(root);
+        let pkg_norm = normalized(&pkg);
+        let root_stack = IgnoreStack::new().descend(root, &root_norm);
+        let pkg_stack = root_stack.descend(&pkg, &pkg_norm);
+
+        assert!(pkg_stack.is_ignored(&format!("{pkg_norm}/debug.log"), false));
+        assert!(!pkg_stack.is_ignored(&format!("{pkg_norm}/keep.log"), false));
+        assert!(root_stack.is_ignored(&format!("{root_norm}/keep.log"), false));
+    }
+
+    #[test]
+    fn directories_without_ignore_files_inherit_rules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        let nested = root.join("a/b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join(".ignore"), "# comment\n\nbuild\n").unwrap();
+
+        let stack = IgnoreStack::new()
+            .descend(root, &normalized(root))
+            .descend(&nested, &normalized(&nested));
+
+        assert!(stack.is_ignored(&format!("{}/build", normalized(&nested)), true));
+        assert!(!IgnoreStack::new().is_ignored(&format!("{}/build", normalized(&nested)), true));
+    }
+}
@@ -3,11 +3,41 @@
 #[cfg(test)]
 mod aggregate_tests;
 
+#[cfg(test)]
+mod budget_tests;
+
+#[cfg(test)]
+mod cli_args_tests;
+
+#[cfg(test)]
+mod throttle_tests;
+
 #[cfg(test)]
 mod traverse_tests;
 
 #[cfg(test)]
 mod depth_tests;
 
+#[cfg(test)]
+mod detect_tests;
+
+#[cfg(test)]
+mod diff_tests;
+
+#[cfg(test)]
+mod filter_tests;
+
+#[cfg(test)]
+mod ignore_tests;
+
 #[cfg(test)]
 mod normalize_path_tests;
+
+#[cfg(test)]
+mod tui_tests;
+
+#[cfg(test)]
+mod watch_tests;
+
+#[cfg(test)]
+mod hardlinks_tests;
@@ -0,0 +1,72 @@
+//! Unit tests for scan rate limiting and gentle-mode options
+#[cfg(test)]
+mod tests {
+    use dua::services::traverse::throttle::RateLimiter;
+    use dua::{IoPriority, ScanOptions, SizeBasis};
+    use std::fs;
+    use std::time::{Duration, Instant};
+    use tempfile::TempDir;
+
+    #[test]
+    fn limiter_spreads_operations_over_time() {
+        // 100 ops/s with a burst of 10: 40 operations need at least 0.3 s.
+        let limiter = RateLimiter::new(100);
+        let started = Instant::now();
+        for _ in 0..4 {
+            limiter.acquire(10);
+        }
+        assert!(started.elapsed() >= Duration::from_millis(280));
+    }
+
+    #[test]
+    fn limiter_allows_an_initial_burst() {
+        let limiter = RateLimiter::new(1_000);
+        let started = Instant::now();
+        limiter.acquire(100);
+        limiter.acquire(0);
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[test]
+    fn throttled_gentle_scan_is_slower_but_complete() {
+        let temp_dir = TempDir::new().unwrap();
+        for dir in 0..5 {
+            let path = temp_dir.path().join(format!("d{dir}"));
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("f"), b"data").unwrap();
+        }
+
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            nice: true,
+            io_priority: IoPriority::Idle,
+            max_ops_per_sec: Some(50),
+            ..Default::default()
+        };
+
+        // Roughly 20 operations at 50/s with a burst of 5.
+        let started = Instant::now();
+        let summary = dua::scan_summary(temp_dir.path(), &opts).expect("scan");
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(summary.errors.is_empty());
+        assert_eq!(summary.entries.len(), 11);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn nice_scans_leave_the_caller_priority_alone() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("f"), b"data").unwrap();
+        let opts = ScanOptions {
+            nice: true,
+            threads: Some(4),
+            ..Default::default()
+        };
+
+        let before = rustix::process::getpriority_process(None).unwrap();
+        for _ in 0..3 {
+            dua::scan_summary(temp_dir.path(), &opts).expect("scan");
+        }
+        assert_eq!(rustix::process::getpriority_process(None).unwrap(), before);
+    }
+}
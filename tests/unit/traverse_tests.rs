@@ -3,10 +3,15 @@
 #[cfg(test)]
 mod tests {
     use crate::fixtures::write_file_sync;
-    use dua::services::traverse::detect::FilesystemKind;
+    use dua::services::traverse::detect::{FilesystemInfo, FilesystemKind};
     use dua::services::traverse::{StrategyKind, TraversalContext, TraversalDispatcher, detect};
-    use dua::{ScanOptions, SizeBasis};
+    use dua::{
+        DirectoryEntry, EntryKind, ScanOptions, SizeBasis, StrategyRegistry, TraversalStrategy,
+    };
     use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::sync::Arc;
     use std::time::Duration;
     use tempfile::TempDir;
 
@@ -62,7 +67,11 @@ mod tests {
     fn maps_filesystem_kinds_to_expected_strategies() {
         assert_eq!(
             detect::strategy_for_filesystem(FilesystemKind::Ntfs),
-            StrategyKind::WindowsOptimized
+            if cfg!(windows) {
+                StrategyKind::WindowsOptimized
+            } else {
+                StrategyKind::PosixOptimized
+            }
         );
         assert_eq!(
             detect::strategy_for_filesystem(FilesystemKind::Apfs),
@@ -132,9 +141,154 @@ mod tests {
         ));
 
         #[cfg(all(unix, not(target_os = "macos")))]
-        assert!(matches!(
+        assert!(!matches!(
             fs_kind,
-            detect::FilesystemKind::Ext | detect::FilesystemKind::Other
+            detect::FilesystemKind::Ntfs | detect::FilesystemKind::Apfs
         ));
     }
+
+    /// Stand-in for a remote backend: lists a fixed set of objects under
+    /// every root through the context, honouring excludes and reporting
+    /// objects it cannot read.
+    struct ObjectStore {
+        eligible: bool,
+    }
+
+    const OBJECTS: [(&str, u64); 4] = [
+        ("a.bin", 4096),
+        ("b.tmp", 1000),
+        ("c.bin", 2048),
+        ("missing.bin", 0),
+    ];
+
+    impl TraversalStrategy for ObjectStore {
+        fn kind(&self) -> StrategyKind {
+            StrategyKind::Custom("object-store")
+        }
+
+        fn is_eligible(&self, _opts: &ScanOptions, _fs: &FilesystemInfo) -> bool {
+            self.eligible
+        }
+
+        fn traverse(
+            &self,
+            root: &Path,
+            depth: u16,
+            context: &TraversalContext,
+        ) -> std::io::Result<u64> {
+            let root_path = root.to_string_lossy().to_string();
+            let mut total = 0;
+            let mut files = 0;
+            for (name, size) in OBJECTS {
+                let path = root.join(name);
+                if context.is_excluded(&path, false) {
+                    continue;
+                }
+                if name.starts_with("missing") {
+                    let error = io::Error::new(io::ErrorKind::NotFound, "object vanished");
+                    context.record_error(&path, &error)?;
+                    continue;
+                }
+                context.insert_entry(DirectoryEntry {
+                    path: path.to_string_lossy().to_string(),
+                    parent_path: Some(root_path.clone()),
+                    depth: depth + 1,
+                    size_bytes: size,
+                    kind: EntryKind::File,
+                    ..DirectoryEntry::default()
+                })?;
+                total += size;
+                files += 1;
+            }
+            context.insert_entry(DirectoryEntry {
+                path: root_path,
+                depth,
+                size_bytes: total,
+                file_count: files,
+                kind: EntryKind::Directory,
+                ..DirectoryEntry::default()
+            })?;
+            Ok(total)
+        }
+    }
+
+    fn with_object_store(eligible: bool) -> ScanOptions {
+        let mut registry = StrategyRegistry::with_defaults();
+        registry.register(ObjectStore { eligible });
+        ScanOptions {
+            strategies: Some(Arc::new(registry)),
+            ..ScanOptions::default()
+        }
+    }
+
+    #[test]
+    fn registered_strategy_takes_precedence_when_eligible() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file_sync(temp_dir.path().join("local.txt"), b"ignored").unwrap();
+
+        let opts = ScanOptions {
+            excludes: vec!["*.tmp".to_string()],
+            ..with_object_store(true)
+        };
+        let summary = dua::scan_summary(temp_dir.path(), &opts).unwrap();
+        assert_eq!(summary.strategy, StrategyKind::Custom("object-store"));
+
+        let mut names: Vec<&str> = summary
+            .entries
+            .iter()
+            .filter(|e| e.depth == 1)
+            .map(|e| e.path.rsplit('/').next().unwrap())
+            .collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec!["a.bin", "c.bin"],
+            "local.txt or b.tmp leaked in"
+        );
+        let root = summary.entries.iter().find(|e| e.depth == 0).unwrap();
+        assert_eq!(root.size_bytes, 4096 + 2048);
+        assert_eq!(root.file_count, 2);
+
+        assert_eq!(summary.errors.len(), 1);
+        assert!(summary.errors[0].path.ends_with("missing.bin"));
+        assert_eq!(summary.errors[0].code, "ENOENT");
+    }
+
+    #[test]
+    fn ineligible_strategy_is_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file_sync(temp_dir.path().join("local.txt"), b"counted").unwrap();
+
+        let summary = dua::scan_summary(temp_dir.path(), &with_object_store(false)).unwrap();
+        assert_ne!(summary.strategy, StrategyKind::Custom("object-store"));
+        assert_eq!(summary.entries.len(), 2);
+    }
+
+    #[test]
+    fn override_selects_registered_strategy_by_kind() {
+        let temp_dir = TempDir::new().unwrap();
+        let opts = ScanOptions {
+            strategy_override: Some(StrategyKind::Custom("object-store")),
+            ..with_object_store(false)
+        };
+
+        let summary = dua::scan_summary(temp_dir.path(), &opts).unwrap();
+        assert_eq!(summary.strategy, StrategyKind::Custom("object-store"));
+    }
+
+    #[test]
+    fn empty_registry_falls_back_to_legacy() {
+        let temp_dir = TempDir::new().unwrap();
+        write_file_sync(temp_dir.path().join("a.txt"), b"abc").unwrap();
+        let opts = ScanOptions {
+            basis: SizeBasis::Logical,
+            strategies: Some(Arc::new(StrategyRegistry::new())),
+            ..ScanOptions::default()
+        };
+
+        let summary = dua::scan_summary(temp_dir.path(), &opts).unwrap();
+        assert_eq!(summary.strategy, StrategyKind::Legacy);
+        let root = summary.entries.iter().find(|e| e.depth == 0).unwrap();
+        assert_eq!(root.size_bytes, 3);
+    }
 }
@@ -0,0 +1,102 @@
+//! Unit tests for TUI navigation state
+
+#[cfg(test)]
+mod tests {
+    use dua::cli::tui::TuiState;
+    use dua::models::DirectoryEntry;
+    use dua::services::aggregate::SortBy;
+
+    fn entry(
+        path: &str,
+        parent: Option<&str>,
+        depth: u16,
+        size_bytes: u64,
+        file_count: u32,
+    ) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: parent.map(str::to_string),
+            depth,
+            size_bytes,
+            file_count,
+            dir_count: 0,
+            ..Default::default()
+        }
+    }
+
+    fn sample() -> Vec<DirectoryEntry> {
+        vec![
+            entry("/r", None, 0, 1_000, 10),
+            entry("/r/big", Some("/r"), 1, 700, 1),
+            entry("/r/many", Some("/r"), 1, 200, 8),
+            entry("/r/small", Some("/r"), 1, 100, 1),
+            entry("/r/big/inner", Some("/r/big"), 2, 700, 1),
+        ]
+    }
+
+    fn child_paths(state: &TuiState) -> Vec<&str> {
+        state.children().iter().map(|e| e.path.as_str()).collect()
+    }
+
+    #[test]
+    fn starts_at_requested_path_sorted() {
+        let state = TuiState::new(sample(), "/r", SortBy::Size).expect("root present");
+
+        assert_eq!(state.current_path(), "/r");
+        assert_eq!(state.current_size(), 1_000);
+        assert_eq!(child_paths(&state), vec!["/r/big", "/r/many", "/r/small"]);
+        assert!(TuiState::new(sample(), "/missing", SortBy::Size).is_none());
+    }
+
+    #[test]
+    fn selection_clamps_at_bounds() {
+        let mut state = TuiState::new(sample(), "/r", SortBy::Size).expect("root present");
+
+        state.move_selection(-1);
+        assert_eq!(state.selected(), 0);
+        state.move_selection(10);
+        assert_eq!(state.selected(), 2);
+        state.select_first();
+        assert_eq!(state.selected(), 0);
+        state.select_last();
+        assert_eq!(state.selected(), 2);
+    }
+
+    #[test]
+    fn enter_and_leave_restore_selection() {
+        let mut state = TuiState::new(sample(), "/r", SortBy::Size).expect("root present");
+
+        state.move_selection(2);
+        assert!(!state.enter_selected(), "leaf entries cannot be opened");
+
+        state.select_first();
+        assert!(state.enter_selected());
+        assert_eq!(state.current_path(), "/r/big");
+        assert_eq!(state.level(), 1);
+        assert_eq!(child_paths(&state), vec!["/r/big/inner"]);
+
+        assert!(state.leave());
+        assert_eq!(state.current_path(), "/r");
+        assert_eq!(state.selected(), 0);
+        assert!(!state.leave(), "cannot leave the starting directory");
+    }
+
+    #[test]
+    fn resort_keeps_selected_entry() {
+        let mut state = TuiState::new(sample(), "/r", SortBy::Size).expect("root present");
+
+        state.move_selection(1);
+        assert_eq!(
+            state.selected_entry().map(|e| e.path.as_str()),
+            Some("/r/many")
+        );
+
+        state.set_sort(SortBy::Files);
+        assert_eq!(state.sort_by(), SortBy::Files);
+        assert_eq!(child_paths(&state)[0], "/r/many");
+        assert_eq!(
+            state.selected_entry().map(|e| e.path.as_str()),
+            Some("/r/many")
+        );
+    }
+}
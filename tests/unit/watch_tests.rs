@@ -0,0 +1,158 @@
+//! Unit tests for the live usage tree and growth ranking
+
+#[cfg(test)]
+mod tests {
+    use dua::HardlinkPolicy;
+    use dua::models::{DirectoryEntry, EntryAttributes, EntryKind};
+    use dua::services::watch::{GrowthTracker, LiveTree};
+    use std::time::{Duration, Instant};
+
+    fn dir(
+        path: &str,
+        parent: Option<&str>,
+        depth: u16,
+        size: u64,
+        files: u32,
+        dirs: u32,
+    ) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: parent.map(str::to_string),
+            depth,
+            size_bytes: size,
+            file_count: files,
+            dir_count: dirs,
+            kind: EntryKind::Directory,
+            ..Default::default()
+        }
+    }
+
+    fn file(path: &str, parent: &str, depth: u16, size: u64) -> DirectoryEntry {
+        DirectoryEntry {
+            path: path.to_string(),
+            parent_path: Some(parent.to_string()),
+            depth,
+            size_bytes: size,
+            kind: EntryKind::File,
+            ..Default::default()
+        }
+    }
+
+    fn file_attrs() -> EntryAttributes {
+        EntryAttributes {
+            kind: EntryKind::File,
+            ..Default::default()
+        }
+    }
+
+    fn sample_tree() -> LiveTree {
+        LiveTree::from_entries(
+            "/r",
+            HardlinkPolicy::Dedupe,
+            vec![
+                dir("/r", Some("/"), 0, 350, 1, 1),
+                file("/r/top.txt", "/r", 1, 50),
+                dir("/r/logs", Some("/r"), 1, 300, 2, 0),
+                file("/r/logs/a.log", "/r/logs", 2, 100),
+                file("/r/logs/b.log", "/r/logs", 2, 200),
+            ],
+        )
+    }
+
+    fn size(tree: &LiveTree, path: &str) -> u64 {
+        tree.get(path).expect("entry present").size_bytes
+    }
+
+    #[test]
+    fn growing_file_updates_every_ancestor() {
+        let mut tree = sample_tree();
+
+        assert_eq!(tree.set_file("/r/logs/a.log", 1_100, file_attrs()), 1_000);
+
+        assert_eq!(size(&tree, "/r/logs/a.log"), 1_100);
+        assert_eq!(size(&tree, "/r/logs"), 1_300);
+        assert_eq!(tree.total_size(), 1_350);
+        assert_eq!(tree.get("/r/logs").unwrap().file_count, 2);
+    }
+
+    #[test]
+    fn new_files_and_removals_adjust_counts() {
+        let mut tree = sample_tree();
+
+        assert_eq!(tree.set_file("/r/logs/c.log", 25, file_attrs()), 25);
+        assert_eq!(tree.get("/r/logs").unwrap().file_count, 3);
+        assert_eq!(tree.get("/r/logs/c.log").unwrap().depth, 2);
+
+        assert_eq!(tree.remove("/r/logs"), -325);
+        assert!(tree.get("/r/logs/a.log").is_none());
+        assert_eq!(tree.get("/r").unwrap().dir_count, 0);
+        assert_eq!(tree.total_size(), 50);
+        assert_eq!(tree.len(), 2);
+    }
+
+    #[test]
+    fn files_outside_the_tree_are_ignored() {
+        let mut tree = sample_tree();
+
+        assert_eq!(tree.set_file("/r/missing/x", 10, file_attrs()), 0);
+        assert_eq!(tree.set_file("/elsewhere/x", 10, file_attrs()), 0);
+        assert_eq!(tree.total_size(), 350);
+    }
+
+    #[test]
+    fn subtree_scans_are_grafted_at_the_right_depth() {
+        let mut tree = sample_tree();
+        let scanned = vec![
+            dir("/r/logs/archive", Some("/r/logs"), 0, 70, 1, 0),
+            file("/r/logs/archive/old.gz", "/r/logs/archive", 1, 70),
+        ];
+
+        assert_eq!(tree.insert_subtree(scanned), 70);
+        assert_eq!(tree.get("/r/logs/archive").unwrap().depth, 2);
+        assert_eq!(tree.get("/r/logs/archive/old.gz").unwrap().depth, 3);
+        assert_eq!(tree.get("/r/logs").unwrap().dir_count, 1);
+        assert_eq!(tree.total_size(), 420);
+
+        // Scanning the same directory again replaces it rather than adding to it.
+        let rescanned = vec![dir("/r/logs/archive", Some("/r/logs"), 0, 0, 0, 0)];
+        assert_eq!(tree.insert_subtree(rescanned), -70);
+        assert!(tree.get("/r/logs/archive/old.gz").is_none());
+        assert_eq!(tree.get("/r/logs").unwrap().dir_count, 1);
+        assert_eq!(tree.total_size(), 350);
+    }
+
+    #[test]
+    fn hardlinked_files_count_once_under_dedupe() {
+        let mut tree = sample_tree();
+        let linked = EntryAttributes {
+            kind: EntryKind::File,
+            inode: Some(42),
+            device: Some(1),
+            nlink: Some(2),
+            ..Default::default()
+        };
+
+        assert_eq!(tree.set_file("/r/logs/one", 500, linked), 500);
+        assert_eq!(tree.set_file("/r/two", 500, linked), 0);
+        assert_eq!(tree.total_size(), 850);
+    }
+
+    #[test]
+    fn growth_ranks_directories_within_the_window() {
+        let start = Instant::now();
+        let mut growth = GrowthTracker::new(Duration::from_secs(10));
+        growth.record(start, "/r/old", 5_000);
+        growth.record(start + Duration::from_secs(8), "/r/logs", 300);
+        growth.record(start + Duration::from_secs(9), "/r/logs", 700);
+        growth.record(start + Duration::from_secs(9), "/r/cache", 400);
+        growth.record(start + Duration::from_secs(9), "/r/tmp", -900);
+
+        let ranked = growth.fastest(start + Duration::from_secs(15), 10);
+        let paths: Vec<&str> = ranked.iter().map(|g| g.path.as_str()).collect();
+        assert_eq!(paths, vec!["/r/logs", "/r/cache"]);
+        assert_eq!(ranked[0].delta_bytes, 1_000);
+        assert!((ranked[0].bytes_per_sec - 100.0).abs() < f64::EPSILON);
+
+        assert_eq!(growth.fastest(start + Duration::from_secs(15), 1).len(), 1);
+    }
+}